@@ -5,27 +5,24 @@
 
 out vec4 color;
 
-// Hex positions of the board
-uniform hex_positions {
-    vec2 positions[64];
-};
+// Hex positions of the board in world units, one texel per land hex
+uniform sampler2D hex_positions;
 uniform uint total_hex;
+// Inverse of the matrix the hexes are drawn with
+uniform mat4 u_inverse_mvp;
 uniform uvec2 u_resolution;
-uniform float u_scale;
 uniform float u_time;
 
 // COLORS
 const vec4 TAN = vec4(1.0, 0.95, 0.85, 1.0);
 const vec4 LIGHT_BLUE = vec4(0.059, 0.898, 0.91, 1.0);
-// Hexagon size
-const float HEX_SCALE = 0.9 * u_scale;
-// Not sure how I came up with this constant but without it the points from positions[64] dont match with the hex's
-const float SCALE_FACTOR = 0.5;
-const float ASPECT_RATIO = float(u_resolution.x) / float(u_resolution.y);
-// Pixel Dimensions
-const float pixel_scale = 42.0;
-const int pixel_x = int(pixel_scale * u_scale);
-const int pixel_y = int(pixel_scale * u_scale);
+// From the center of a hex of sand to its sides in world units, the hex tiles are 5 units wide
+// so a beach shows around them
+const float SAND_APOTHEM = 2.9;
+// Side of a background pixel, in world units
+const float PIXEL_SIZE = 0.25;
+// Distance from the middle of the land over which the waves fade, in world units
+const float WAVE_SPREAD = 25.0;
 
 // Random 1D value from (-1, 1)
 float rand1d(float value) {
@@ -44,36 +41,53 @@ vec2 polar_coordinates_of(vec2 pos) {
 }
 
 // If r ≤ sec(1/3 * arcsin(sin(3θ))), then the point is within the hexgon
-bool in_hexagon(vec2 polar_pos, float radius_scale = 1) {
+bool in_hexagon(vec2 polar_pos, float apothem) {
     float radius = polar_pos.x;
     float angle = polar_pos.y;
     // Check if the point is within the hexagon equation
-    return radius / radius_scale <= HEX_SCALE / cos((1.0 / 3.0) * asin(sin(3.0 * angle)));
+    return radius <= apothem / cos((1.0 / 3.0) * asin(sin(3.0 * angle)));
 }
 
-void main() {
-    // Pixellate
-    vec2 frag_coord = gl_FragCoord.xy;
-    frag_coord.x -= (float(int(gl_FragCoord.x * 1.0) % pixel_x) / 1.0);
-    frag_coord.y -= (float(int(gl_FragCoord.y * 1.0) % pixel_y) / 1.0);
-    vec2 st = frag_coord.xy / u_resolution.xy; // Normalize screen coordinates
+// Point of the board plane (z = 0) under the fragment, the same ray cast as picking::Picker.
+// False where the view doesn't reach the board, above the horizon of the tilted view.
+bool board_position(out vec2 world) {
+    vec2 ndc = 2.0 * gl_FragCoord.xy / vec2(u_resolution) - 1.0;
+    vec4 near = u_inverse_mvp * vec4(ndc, -1.0, 1.0);
+    vec4 far = u_inverse_mvp * vec4(ndc, 1.0, 1.0);
+    float dz = near.z - far.z;
+    if (abs(dz) < 1e-6 || near.z / dz < 0.0) {
+        return false;
+    }
+    vec4 hit = mix(near, far, near.z / dz);
+    if (abs(hit.w) < 1e-6) {
+        return false;
+    }
+    world = hit.xy / hit.w;
+    return true;
+}
 
+void main() {
     // Initialize color to the default color
     color = vec4(0.15, 0.3, 0.7, 1.0);
 
+    vec2 world;
+    if (!board_position(world)) {
+        return;
+    }
+    // Pixellate, on the board so the pixels move with it
+    world = (floor(world / PIXEL_SIZE) + 0.5) * PIXEL_SIZE;
+
     // Center is the average of all the hexagon points
     vec2 center = vec2(0);
 
     bool land = false;
     // Iterate over the hex positions
     for (uint i = 0u; i < total_hex; i++) {
-        // A normalized point that is (x, y) distance from (0, 0)
-        vec2 delta_origin = st - positions[i] * SCALE_FACTOR;
-
-        center += positions[i] * SCALE_FACTOR;
+        vec2 position = texelFetch(hex_positions, ivec2(i, 0), 0).xy;
+        center += position;
 
         // Check if the fragment is inside hexagon
-        if (in_hexagon(polar_coordinates_of(vec2(delta_origin.x * ASPECT_RATIO, delta_origin.y)))) {
+        if (in_hexagon(polar_coordinates_of(world - position), SAND_APOTHEM)) {
             // On land
             color = TAN;
             land = true;
@@ -84,9 +98,7 @@ void main() {
     center /= total_hex; 
     // Draw ocean
     if (!land) {
-        float pct = distance(st, center);
-        vec2 pos = vec2(st - center);
-        vec2 polar = polar_coordinates_of(vec2(pos.x * ASPECT_RATIO, pos.y));
+        float pct = distance(world, center) / WAVE_SPREAD;
         vec3 waves = LIGHT_BLUE.rgb * (vec3(1) - vec3(pct * sin(u_time / 20) / 2.0) / 2.0);
         color *= vec4(waves, 1.0);
     }
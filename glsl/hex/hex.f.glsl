@@ -2,7 +2,9 @@
 
 in flat uint hex_tex_id;
 in flat uint hex_value;
+in flat uint hovered;
 in vec2 f_tex_coords;
+in vec2 f_token_coords;
 
 out vec4 color;
 
@@ -12,22 +14,71 @@ uniform sampler2D texture_map_chances;
 uniform uvec2 u_resolution;  // Screen resolution
 uniform float u_time;       // Time for animation
 
+// Terrain drawn without a texture, see HexVertex::hex_meta
+const uint WATER = 7;
+const uint HARBOR = 8;
+const uint FRAME = 9;
+const uint FOG = 10;
+// Land drawn without a texture
+const uint GOLD = 11;
+
+const vec3 SEA_BLUE = vec3(0.15, 0.35, 0.75);
+// How much lighter the hex under the cursor is drawn
+const float HOVER_LIGHTEN = 0.25;
+
 vec4 layer(vec4 foreground, vec4 background) {
     return foreground * foreground.a + background * (1.0 - foreground.a);
 }
 
-void main() {
-    // hex_tex starts at DESERT=1 but the position in the texture is 0
-    vec4 hex_texture = texture(texture_map_hex, vec2((f_tex_coords.x + hex_tex_id - 1) / 6, f_tex_coords.y));
+vec4 terrain_color(uint id) {
+    // 0 at the center of the hex
+    float dist = distance(f_tex_coords, vec2(0.5));
+    vec3 sea = SEA_BLUE * (0.9 + 0.1 * sin(u_time + dist * 20.0));
+    if (id == WATER) {
+        return vec4(sea, 1.0);
+    } else if (id == HARBOR) {
+        // Dock in the middle of the water
+        return dist < 0.12 ? vec4(0.55, 0.35, 0.2, 1.0) : vec4(sea, 1.0);
+    } else if (id == FRAME) {
+        return vec4(vec3(0.45, 0.3, 0.15) * (1.0 - dist * 0.4), 1.0);
+    } else {
+        // Fog
+        return vec4(vec3(0.7 + 0.05 * sin(u_time / 2.0 + dist * 12.0)), 1.0);
+    }
+}
+
+vec4 gold_color() {
+    float dist = distance(f_tex_coords, vec2(0.5));
+    // Glint sweeping across the field
+    float glint = 0.1 * sin(u_time * 2.0 + (f_tex_coords.x + f_tex_coords.y) * 10.0);
+    return vec4(vec3(0.85, 0.65, 0.13) * (1.0 - dist * 0.5 + glint), 1.0);
+}
+
+vec4 hex_color() {
+    vec4 hex_texture;
+    if (hex_tex_id == GOLD) {
+        hex_texture = gold_color();
+    } else if (hex_tex_id >= WATER) {
+        return terrain_color(hex_tex_id);
+    } else {
+        // hex_tex starts at DESERT=1 but the position in the texture is 0
+        hex_texture = texture(texture_map_hex, vec2((f_tex_coords.x + hex_tex_id - 1) / 6, f_tex_coords.y));
+    }
     if (hex_tex_id > 1) {
         // Account for 7's not being a roll
         uint offset = 0;
         if (hex_value > 7) {
             offset += 1;
         }
-        vec4 chances_texture = texture(texture_map_chances, vec2((f_tex_coords.x + hex_value - 2 - offset) / 10, f_tex_coords.y));
-        color = layer(chances_texture, hex_texture);
-    } else {
-        color = hex_texture;
+        vec4 chances_texture = texture(texture_map_chances, vec2((f_token_coords.x + hex_value - 2 - offset) / 10, f_token_coords.y));
+        return layer(chances_texture, hex_texture);
+    }
+    return hex_texture;
+}
+
+void main() {
+    color = hex_color();
+    if (hovered != 0) {
+        color.rgb = mix(color.rgb, vec3(1.0), HOVER_LIGHTEN);
     }
 }
\ No newline at end of file
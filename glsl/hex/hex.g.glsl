@@ -9,13 +9,19 @@ in VS_OUT {
     vec2 g_pos;
     flat uint hex_tex;
     flat uint hex_val;
+    flat uint hex_hovered;
 } gs_in[];  // There is only one vertex in points so we will only ever be dealing with gs_in[0]
 
 uniform mat4 u_mvp;
+// Turn of the board on screen, counter-clockwise in radians
+uniform float u_rotation;
 
 out vec2 f_tex_coords;
+// Texture coordinates of the number token, turned back so it stays upright on screen
+out vec2 f_token_coords;
 out flat uint hex_tex_id;
 out flat uint hex_value;
+out flat uint hovered;
 
 // A constant to reduce the radius of the texture coordinates
 const float HEX_FIT = 1.3;
@@ -24,11 +30,15 @@ void main() {
     // Hex metadata will remain consitant throughout the whole primitive
     hex_tex_id = gs_in[0].hex_tex;
     hex_value = gs_in[0].hex_val;
+    hovered = gs_in[0].hex_hovered;
     vec4 center = vec4(gs_in[0].g_pos, 0.0, 1.0);
 
     // We reuse this vertex for every point of the hexagon
     vec4 center_transformed = u_mvp * center;
 
+    // Turns a position on the board the way the board is turned on screen
+    mat2 on_screen = mat2(cos(u_rotation), sin(u_rotation), -sin(u_rotation), cos(u_rotation));
+
     // Create vertices for each 6 points of the hexagon
     float hex_radius = 2.7;
     for (int i = 0; i < 7; i++) {
@@ -37,11 +47,14 @@ void main() {
         vec2 pos = vec2(hex_radius * cos(theta), hex_radius * sin(theta));
         // Generate vertex for hexagon point
         gl_Position = u_mvp * (center + vec4(pos, 0.0, 0.0));
-        f_tex_coords = vec2(-HEX_FIT * pos.x / 6.0 + 0.5, HEX_FIT * pos.y / 6.0 + 0.5);
+        f_tex_coords = vec2(HEX_FIT * pos.x / 6.0 + 0.5, HEX_FIT * pos.y / 6.0 + 0.5);
+        vec2 upright = on_screen * pos;
+        f_token_coords = vec2(HEX_FIT * upright.x / 6.0 + 0.5, HEX_FIT * upright.y / 6.0 + 0.5);
         EmitVertex();
         // Add a vertex for the center so triangle_strip wraps properly
         gl_Position = center_transformed;
         f_tex_coords = vec2(0.5);
+        f_token_coords = vec2(0.5);
         EmitVertex();
     }
     EndPrimitive();
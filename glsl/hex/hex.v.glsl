@@ -7,6 +7,7 @@ out VS_OUT {
     vec2 g_pos;
     flat uint hex_tex;
     flat uint hex_val;
+    flat uint hex_hovered;
 } vs_out;
 
 uint hex_texture_id(in uint meta) {
@@ -19,9 +20,15 @@ uint hex_value(in uint meta) {
     return (meta >> 8) & 255;
 }
 
+uint hex_hovered(in uint meta) {
+    // Bit after the hex value
+    return (meta >> 16) & 1;
+}
+
 void main() {
     vs_out.hex_tex = hex_texture_id(hex_meta);
     vs_out.hex_val = hex_value(hex_meta);
+    vs_out.hex_hovered = hex_hovered(hex_meta);
     vs_out.g_pos = pos;
     // Determine position in geometry shader
     gl_Position = vec4(0.0);
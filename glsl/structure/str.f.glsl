@@ -2,15 +2,158 @@
 
 in flat uint b_id;
 in flat uint b_color_id;
+in flat uint b_info;
+in flat uint b_highlight;
 in vec2 f_tex_coords;
 
 out vec4 color;
 
-uniform sampler2D texture_map_structures;
-
+// One texel per colour ID: the player colour, and the id of the pattern drawn over it in
+// alpha, see palette::Palette::texels
+uniform sampler2D player_palette;
 uniform uvec2 u_resolution;  // Screen resolution
 uniform float u_time;       // Time for animation
 
+const uint ROAD = 0;
+const uint SETTLEMENT = 1;
+const uint CITY = 2;
+const uint SHIP = 3;
+const uint KNIGHT = 4;
+// Bits of b_highlight
+const uint HOVERED = 1;
+const uint GHOST = 2;
+// Ghosts are faint until the cursor is over them
+const float GHOST_ALPHA = 0.35;
+const float GHOST_HOVER_ALPHA = 0.7;
+const float HOVER_LIGHTEN = 0.3;
+
+// Patterns, see palette::Pattern
+const uint STRIPES = 1;
+const uint DOTS = 2;
+const uint CHECKS = 3;
+const uint CROSSHATCH = 4;
+const uint WAVES = 5;
+// How dark the marks of a pattern are
+const float PATTERN_SHADE = 0.55;
+const vec3 OUTLINE = vec3(0.08);
+// Width of the outline, in texture coordinates of buildings. Roads and ships are narrower, so
+// their outline takes more of their width.
+const float OUTLINE_WIDTH = 0.04;
+const float ROUTE_OUTLINE_WIDTH = 0.15;
+// Length of roads and ships over their width, see str.g.glsl
+const float ROUTE_ASPECT = 1.1 / 0.3;
+
+vec4 palette_entry() {
+    int colors = textureSize(player_palette, 0).x;
+    return texelFetch(player_palette, ivec2(int(b_color_id) % colors, 0), 0);
+}
+
+// Whether `p` falls on a mark of the pattern
+bool pattern_mark(uint pattern, vec2 p) {
+    if (pattern == STRIPES) {
+        return fract((p.x + p.y) * 4.0) < 0.4;
+    } else if (pattern == DOTS) {
+        return length(fract(p * 5.0) - 0.5) < 0.22;
+    } else if (pattern == CHECKS) {
+        return mod(floor(p.x * 5.0) + floor(p.y * 5.0), 2.0) == 0.0;
+    } else if (pattern == CROSSHATCH) {
+        return min(fract((p.x + p.y) * 4.0), fract((p.x - p.y) * 4.0)) < 0.2;
+    } else if (pattern == WAVES) {
+        return fract(p.y * 4.0 + 0.15 * sin(p.x * 20.0)) < 0.4;
+    }
+    return false;
+}
+
+// The player colour, with their pattern over it
+vec3 player_color(vec2 p) {
+    vec4 entry = palette_entry();
+    uint pattern = uint(entry.a + 0.5);
+    return pattern_mark(pattern, p) ? entry.rgb * PATTERN_SHADE : entry.rgb;
+}
+
+// Signed distance to a box given by its lower left and upper right corners
+float box(vec2 p, vec2 low, vec2 high) {
+    vec2 d = max(low - p, p - high);
+    return length(max(d, 0.0)) + min(max(d.x, d.y), 0.0);
+}
+
+// Roughly the signed distance to a roof of slope 1 on `base` with its apex at `apex`
+float roof(vec2 p, float base, vec2 apex) {
+    return max(base - p.y, (p.y - apex.y + abs(p.x - apex.x)) / sqrt(2.0));
+}
+
+// Fill with the player colour, outlined, for a shape given by its signed distance
+vec4 silhouette(float dist, float outline) {
+    if (dist > 0.0) {
+        discard;
+    }
+    return vec4(dist > -outline ? OUTLINE : player_color(f_tex_coords), 1.0);
+}
+
+// A house with a pointed roof
+vec4 settlement_color() {
+    vec2 p = f_tex_coords;
+    float body = box(p, vec2(0.25, 0.15), vec2(0.75, 0.55));
+    return silhouette(min(body, roof(p, 0.5, vec2(0.5, 0.85))), OUTLINE_WIDTH);
+}
+
+// A wide hall with a taller house on its right
+vec4 city_color() {
+    vec2 p = f_tex_coords;
+    float hall = box(p, vec2(0.1, 0.15), vec2(0.9, 0.45));
+    float house = min(box(p, vec2(0.5, 0.15), vec2(0.9, 0.65)), roof(p, 0.6, vec2(0.7, 0.85)));
+    return silhouette(min(hall, house), OUTLINE_WIDTH);
+}
+
+// A plank along the edge
+vec4 road_color() {
+    // Stretched so distances are the same along and across
+    vec2 p = f_tex_coords * vec2(ROUTE_ASPECT, 1.0);
+    return silhouette(box(p, vec2(0.0), vec2(ROUTE_ASPECT, 1.0)), ROUTE_OUTLINE_WIDTH);
+}
+
+// A hull pointed at both ends
+vec4 ship_color() {
+    vec2 p = f_tex_coords;
+    float half_width = 0.5 * (1.0 - pow(2.0 * p.x - 1.0, 2.0));
+    return silhouette(abs(p.y - 0.5) - half_width, ROUTE_OUTLINE_WIDTH);
+}
+
+// Knights are drawn as a round shield with a ring per level of strength, faded while inactive
+vec4 knight_color() {
+    uint strength = b_info & 3;
+    bool active = (b_info & 4) != 0;
+    float dist = length(f_tex_coords - vec2(0.5)) * 2.0;
+    if (dist > 0.8) {
+        discard;
+    }
+    // Rings split the shield evenly, the outer ones light up with strength
+    float ring = floor((1.0 - dist / 0.8) * 3.0);
+    vec3 shade = ring < float(strength) ? vec3(0.85, 0.85, 0.9) : vec3(0.25, 0.25, 0.3);
+    // Thin dark line between the rings
+    float edge = fract((1.0 - dist / 0.8) * 3.0);
+    if (edge < 0.08) {
+        shade = vec3(0.05);
+    }
+    return vec4(shade, active ? 1.0 : 0.5);
+}
+
 void main() {
-    color = texture(texture_map_structures, f_tex_coords);
+    if (b_id == ROAD) {
+        color = road_color();
+    } else if (b_id == SETTLEMENT) {
+        color = settlement_color();
+    } else if (b_id == CITY) {
+        color = city_color();
+    } else if (b_id == SHIP) {
+        color = ship_color();
+    } else {
+        color = knight_color();
+    }
+    bool hovered = (b_highlight & HOVERED) != 0;
+    if ((b_highlight & GHOST) != 0) {
+        color.a *= hovered ? GHOST_HOVER_ALPHA : GHOST_ALPHA;
+    } else if (hovered) {
+        color.rgb = mix(color.rgb, vec3(1.0), HOVER_LIGHTEN);
+    }
 }
\ No newline at end of file
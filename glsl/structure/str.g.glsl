@@ -7,45 +7,70 @@ in VS_OUT {
     vec2 g_pos;
     flat uint building_id;
     flat uint building_color_id;
+    flat uint building_info;
+    flat uint building_highlight;
 } gs_in[];  // There is only one vertex in points so we will only ever be dealing with gs_in[0]
 
 uniform mat4 u_mvp;
+// Turn of the board on screen, counter-clockwise in radians
+uniform float u_rotation;
 
 out vec2 f_tex_coords;
 out flat uint b_id;
 out flat uint b_color_id;
+out flat uint b_info;
+out flat uint b_highlight;
 
-// A constant to reduce the radius of the texture coordinates
-const float BUILDING_FIT = 1.3;
+const uint ROAD = 0;
+const uint SHIP = 3;
+
+// Half the length and width of a road or ship, a bit shorter than the hex side so the ends
+// stay clear of the intersections
+const vec2 ROUTE_DIMENSIONS = vec2(1.1, 0.3);
+// Half a hex side across and up, see topology::BOARD_OFFSET and HEX_HEIGHT
+const vec2 HEX_SIDE = vec2(2.5, 1.4067);
+
+// Direction along the edge, from the orientation in the road info bits
+vec2 edge_direction(uint side) {
+    if (side == 0) {
+        // West side of a hex, upright
+        return vec2(0.0, 1.0);
+    } else if (side == 1) {
+        // North west, rising to the right
+        return normalize(HEX_SIDE);
+    }
+    // North east, falling to the right
+    return normalize(vec2(HEX_SIDE.x, -HEX_SIDE.y));
+}
+
+void emit(vec4 center, vec2 along, vec2 across, vec2 tex_coords) {
+    gl_Position = u_mvp * (center + vec4(along + across, 0.0, 0.0));
+    f_tex_coords = tex_coords;
+    EmitVertex();
+}
 
 void main() {
     // Hex metadata will remain consistant throughout the whole primitive
     b_id = gs_in[0].building_id;
     b_color_id = gs_in[0].building_color_id;
+    b_info = gs_in[0].building_info;
+    b_highlight = gs_in[0].building_highlight;
     vec4 center = vec4(gs_in[0].g_pos, 0.0, 1.0);
 
-    // Length and width of the structure
-    vec2 dimensions = vec2(1.5);
-    // Top left
-    vec4 position = center + vec4(-dimensions.x, dimensions.y, 0, 0);
-    gl_Position = u_mvp * position;
-    f_tex_coords = vec2(0, 1);
-    EmitVertex();
-    // Top Right
-    position = center + vec4(dimensions.x, dimensions.y, 0, 0);
-    gl_Position = u_mvp * position;
-    f_tex_coords = vec2(1, 1);
-    EmitVertex();
-    // Bottom left
-    position = center + vec4(-dimensions.x, -dimensions.y, 0, 0);
-    gl_Position = u_mvp * position;
-    f_tex_coords = vec2(0, 0);
-    EmitVertex();
-    // Bottom right
-    position = center + vec4(dimensions.x, -dimensions.y, 0, 0);
-    gl_Position = u_mvp * position;
-    f_tex_coords = vec2(1, 0);
-    EmitVertex();
+    // Buildings are squares kept upright on screen, roads and ships lie along their edge. The x
+    // texture coordinate always runs along the length of the structure.
+    mat2 upright = mat2(cos(u_rotation), -sin(u_rotation), sin(u_rotation), cos(u_rotation));
+    vec2 along = upright * vec2(1.5, 0.0);
+    vec2 across = upright * vec2(0.0, 1.5);
+    if (b_id == ROAD || b_id == SHIP) {
+        vec2 direction = edge_direction(b_info & 3);
+        along = direction * ROUTE_DIMENSIONS.x;
+        across = vec2(-direction.y, direction.x) * ROUTE_DIMENSIONS.y;
+    }
+    emit(center, -along, across, vec2(0, 1));
+    emit(center, along, across, vec2(1, 1));
+    emit(center, -along, -across, vec2(0, 0));
+    emit(center, along, -across, vec2(1, 0));
 
     EndPrimitive();
 }
\ No newline at end of file
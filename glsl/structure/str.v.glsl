@@ -7,6 +7,8 @@ out VS_OUT {
     vec2 g_pos;
     flat uint building_id;
     flat uint building_color_id;
+    flat uint building_info;
+    flat uint building_highlight;
 } vs_out;
 
 uint building_id(in uint meta) {
@@ -19,9 +21,21 @@ uint building_color_id(in uint meta) {
     return (meta >> 4) & 255;
 }
 
+uint building_info(in uint meta) {
+    // Last 4 bits of meta, knights keep their strength and whether they are active
+    return (meta >> 12) & 15;
+}
+
+uint building_highlight(in uint meta) {
+    // Whether it is under the cursor, then whether it is a ghost previewing a build
+    return (meta >> 16) & 3;
+}
+
 void main() {
     vs_out.building_id = building_id(meta);
     vs_out.building_color_id = building_color_id(meta);
+    vs_out.building_info = building_info(meta);
+    vs_out.building_highlight = building_highlight(meta);
     vs_out.g_pos = pos;
     // Determine position in geometry shader
     gl_Position = vec4(0.0);
@@ -0,0 +1,13 @@
+#version 450 core
+
+in vec2 f_uv;
+in vec4 f_color;
+
+out vec4 color;
+
+// imgui's font atlas, its white texel draws untextured shapes
+uniform sampler2D u_font;
+
+void main() {
+    color = f_color * texture(u_font, f_uv);
+}
\ No newline at end of file
@@ -0,0 +1,17 @@
+#version 450 core
+
+in vec2 pos;
+in vec2 uv;
+in vec4 col;
+
+// Window pixels, origin top left, to normalized device coordinates
+uniform mat4 u_matrix;
+
+out vec2 f_uv;
+out vec4 f_color;
+
+void main() {
+    f_uv = uv;
+    f_color = col;
+    gl_Position = u_matrix * vec4(pos, 0.0, 1.0);
+}
\ No newline at end of file
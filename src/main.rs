@@ -10,6 +10,8 @@ pub mod settings {
         height: 720,
     };
     pub const DEBUG: bool = true;
+    /// Custom player colours, see `settlers::palette`. The classic colours are used without it.
+    pub const PALETTE_FILE: &str = "palette.cfg";
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -1,14 +1,15 @@
-use super::{card::Resource, hex::Hex};
-use crate::settlers::matrix::Vec3;
-use std::{borrow::Borrow, rc::Rc, sync::Arc};
+use super::{
+    card::Resource,
+    map::Board,
+    topology::{Direction, Edge, Intersection},
+};
+use crate::settlers::rules::player::PlayerId;
 
 // House
 // Has pointers to all surrounding tiles
 // position can be found by average of other positions
 // Potentially increase the size of the board so every position has three surrounding tiles
 
-pub const SETTLEMENT_PATH: &'static str = "../../../assets/structures/settlement.png";
-
 #[derive(Clone, Copy)]
 pub struct BuildingVertex {
     pos: [f32; 2],
@@ -16,10 +17,16 @@ pub struct BuildingVertex {
     /// 0 - ROAD,
     /// 1 - SETTLEMENT,
     /// 2 - CITY,
+    /// 3 - SHIP,
+    /// 4 - KNIGHT,
     /// Next 8 bits: Color ID (Player)
     /// ...
-    /// Next 4 bits: Road info
-    meta: u16,
+    /// Next 4 bits: Road info, for roads and ships the side of the hex they lie along
+    /// (0 - WEST, 1 - NORTH WEST, 2 - NORTH EAST), for knights 2 bits of strength then whether
+    /// it is active
+    /// Next bit: Under the cursor
+    /// Next bit: Ghost, a preview of where the structure could be built
+    meta: u32,
 }
 implement_vertex!(BuildingVertex, pos, meta);
 
@@ -33,71 +40,146 @@ impl BuildingVertex {
 
     pub fn set_structure(&mut self, structure: &Structure) {
         let id = match structure {
-            Structure::Road { .. } => 0u16,
+            Structure::Road { .. } => 0u32,
             Structure::Settlement { .. } => 1,
             Structure::City { .. } => 2,
+            Structure::Ship { .. } => 3,
+            Structure::Knight { .. } => 4,
         };
         // Clear first 4 bits then add id
-        self.meta = (self.meta & 0b1111111111110000) | id;
+        self.meta = (self.meta & !0b1111) | id;
+        let info = match structure {
+            Structure::Road { edge, .. } | Structure::Ship { edge, .. } => match edge.side {
+                Direction::NorthWest => 1,
+                Direction::NorthEast => 2,
+                _ => 0,
+            },
+            Structure::Knight {
+                strength, active, ..
+            } => (*strength as u32 & 0b11) | (*active as u32) << 2,
+            Structure::Settlement { .. } | Structure::City { .. } => 0,
+        };
+        self.meta = (self.meta & !(0b1111 << 12)) | info << 12;
     }
 
     pub fn set_color(&mut self, color_id: u8) {
-        let mut data = self.meta;
         // Clearing old color, then inserting new color
-        data = ((data >> 4) & 0b111100000000) | color_id as u16;
-        self.meta = (data << 4) | (self.meta & 0b1111);
+        self.meta = (self.meta & !(0b11111111 << 4)) | (color_id as u32) << 4;
+    }
+
+    pub fn set_hovered(&mut self, hovered: bool) {
+        self.meta = (self.meta & !(1 << 16)) | (hovered as u32) << 16;
+    }
+
+    pub fn set_ghost(&mut self, ghost: bool) {
+        self.meta = (self.meta & !(1 << 17)) | (ghost as u32) << 17;
+    }
+
+    /// Raw bits sent to the shaders, see `meta`
+    pub fn meta(&self) -> u32 {
+        self.meta
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.meta & 1 << 16 != 0
     }
 }
 
-/// Represents a catan structure.
-/// Stores the position of the structure relative to the position of the tiles in Map::tiles.
-#[derive(Debug, Clone)]
+/// Represents a catan structure and the player who owns it.
+/// Settlements and cities sit on intersections, roads and ships on edges, see `topology`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Structure {
     Road {
-        position: Vec3,
+        edge: Edge,
+        owner: PlayerId,
     },
     Settlement {
-        position: Vec3,
-        hexes: [Option<Rc<Hex>>; 3],
+        intersection: Intersection,
+        owner: PlayerId,
     },
     City {
-        position: Vec3,
-        hexes: [Option<Rc<Hex>>; 3],
+        intersection: Intersection,
+        owner: PlayerId,
+    },
+    /// Seafarers, a road over the sea
+    Ship {
+        edge: Edge,
+        owner: PlayerId,
+    },
+    /// Cities & Knights, defends against the barbarians. Strength goes from 1 (basic) to 3
+    /// (mighty), only active knights defend or act.
+    Knight {
+        intersection: Intersection,
+        owner: PlayerId,
+        strength: u8,
+        active: bool,
     },
 }
 
 impl Structure {
+    pub fn owner(&self) -> PlayerId {
+        match self {
+            Self::Road { owner, .. }
+            | Self::Settlement { owner, .. }
+            | Self::City { owner, .. }
+            | Self::Ship { owner, .. }
+            | Self::Knight { owner, .. } => *owner,
+        }
+    }
+
+    /// Intersection of a settlement, city or knight
+    pub fn intersection(&self) -> Option<Intersection> {
+        match self {
+            Self::Road { .. } | Self::Ship { .. } => None,
+            Self::Settlement { intersection, .. }
+            | Self::City { intersection, .. }
+            | Self::Knight { intersection, .. } => Some(*intersection),
+        }
+    }
+
+    /// Settlements and cities, the structures that collect resources
+    pub fn is_building(&self) -> bool {
+        matches!(self, Self::Settlement { .. } | Self::City { .. })
+    }
+
+    /// Edge of a road or ship
+    pub fn edge(&self) -> Option<Edge> {
+        match self {
+            Self::Road { edge, .. } | Self::Ship { edge, .. } => Some(*edge),
+            _ => None,
+        }
+    }
+
+    /// World position the structure is drawn at
+    pub fn position(&self) -> (f32, f32) {
+        match self {
+            Self::Road { edge, .. } | Self::Ship { edge, .. } => edge.position(),
+            Self::Settlement { intersection, .. }
+            | Self::City { intersection, .. }
+            | Self::Knight { intersection, .. } => intersection.position(),
+        }
+    }
+
     /// Return resource for the player based on structure
-    pub fn collect_resources(&self, roll: u8) -> Vec<Resource> {
+    pub fn collect_resources(&self, board: &Board, roll: u8) -> Vec<Resource> {
         // Collect resources will only ever return a vec of length 0 - 3,
         let mut resources = Vec::with_capacity(3);
-        match self {
-            Self::Road { .. } => (),
-            Self::Settlement { hexes, .. } => {
-                for hex_option in hexes.iter() {
-                    // Iter through all surrounding hexes
-                    if let Some(hex) = hex_option.borrow() {
-                        // Ensure the hex is a land tile
-                        if !hex.is_robbed() && hex.resource().chance() == roll {
-                            // And that it is not robbed and was rolled
-                            // You get one card for settlements
-                            resources.push(hex.resource().clone_with_value(1));
-                        }
-                    }
-                }
-            }
-            Self::City { hexes, .. } => {
-                for hex_option in hexes.iter() {
-                    // Iter through all surrounding hexes
-                    if let Some(hex) = hex_option.borrow() {
-                        // Ensure the hex is a land tile
-                        if !hex.is_robbed() && hex.resource().chance() == roll {
-                            // And that it is not robbed and was rolled
-                            // You get two card for cities
-                            resources.push(hex.resource().clone_with_value(2));
-                        }
-                    }
-                }
+        let (intersection, amount) = match self {
+            Self::Road { .. } | Self::Ship { .. } | Self::Knight { .. } => return resources,
+            // You get one card for settlements
+            Self::Settlement { intersection, .. } => (intersection, 1),
+            // You get two card for cities
+            Self::City { intersection, .. } => (intersection, 2),
+        };
+        // Iter through all surrounding hexes
+        for hex in intersection
+            .hexes()
+            .iter()
+            .filter_map(|coord| board.hex(*coord))
+        {
+            // Ensure the hex is a land tile, that it is not robbed and was rolled
+            if hex.is_land() && !hex.is_robbed() && hex.resource().chance() == roll {
+                resources.push(hex.resource().clone_with_value(amount));
             }
         }
         resources
@@ -107,6 +189,7 @@ impl Structure {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::settlers::board::topology::HexCoord;
     #[test]
     fn vertex_color() {
         let mut v = BuildingVertex::new(0., 0.);
@@ -117,8 +200,8 @@ mod tests {
     fn vertex_structure() {
         let mut v = BuildingVertex::new(0., 0.);
         v.set_structure(&Structure::City {
-            position: Vec3::new(0., 0., 0.),
-            hexes: [None, None, None],
+            intersection: Intersection::top(HexCoord::new(1, 1)),
+            owner: 0,
         });
         assert_eq!(v.meta, 2);
     }
@@ -127,9 +210,52 @@ mod tests {
         let mut v = BuildingVertex::new(0., 0.);
         v.set_color(5);
         v.set_structure(&Structure::City {
-            position: Vec3::new(0., 0., 0.),
-            hexes: [None, None, None],
+            intersection: Intersection::top(HexCoord::new(1, 1)),
+            owner: 0,
         });
         assert_eq!(82, v.meta);
     }
+    #[test]
+    fn vertex_knight() {
+        let mut v = BuildingVertex::new(0., 0.);
+        v.set_structure(&Structure::Knight {
+            intersection: Intersection::top(HexCoord::new(1, 1)),
+            owner: 0,
+            strength: 2,
+            active: true,
+        });
+        v.set_color(1);
+        assert_eq!(v.meta, 0b0110_0000_0001_0100);
+    }
+    #[test]
+    fn vertex_road_side() {
+        let hex = HexCoord::new(2, 2);
+        let mut v = BuildingVertex::new(0., 0.);
+        for (direction, side) in [
+            (Direction::West, 0),
+            (Direction::NorthWest, 1),
+            (Direction::NorthEast, 2),
+            // The same edge as the north west side of the neighbour
+            (Direction::SouthEast, 1),
+        ] {
+            v.set_structure(&Structure::Road {
+                edge: Edge::between(hex, direction),
+                owner: 0,
+            });
+            assert_eq!(v.meta, side << 12);
+        }
+    }
+    #[test]
+    fn vertex_highlight() {
+        let mut v = BuildingVertex::new(0., 0.);
+        v.set_color(5);
+        v.set_hovered(true);
+        v.set_ghost(true);
+        v.set_color(2);
+        assert_eq!(v.meta, 0b11_0000_0000_0010_0000);
+        assert!(v.is_hovered());
+        v.set_hovered(false);
+        assert_eq!(v.meta(), 0b10_0000_0000_0010_0000);
+        assert!(!v.is_hovered());
+    }
 }
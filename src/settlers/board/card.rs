@@ -6,6 +6,8 @@ pub enum Resource {
     Ore(u8),
     Wheat(u8),
     Sheep(u8),
+    /// Seafarers gold field, produces a resource of the owner's choice
+    Gold(u8),
     Desert(Option<u8>),
 }
 
@@ -18,6 +20,56 @@ impl Resource {
             Resource::Ore(_) => Resource::Ore(val),
             Resource::Wheat(_) => Resource::Wheat(val),
             Resource::Sheep(_) => Resource::Sheep(val),
+            Resource::Gold(_) => Resource::Gold(val),
+        }
+    }
+
+    /// Two letter code used to write the resource in .focm files
+    pub fn code(&self) -> &'static str {
+        match self {
+            Resource::Desert(_) => "DE",
+            Resource::Wood(_) => "WO",
+            Resource::Brick(_) => "BR",
+            Resource::Ore(_) => "OR",
+            Resource::Wheat(_) => "WH",
+            Resource::Sheep(_) => "SH",
+            Resource::Gold(_) => "GO",
+        }
+    }
+
+    /// Resource matching a .focm code, holding `val`
+    pub fn from_code(code: &str, val: u8) -> Option<Self> {
+        match code {
+            "DE" => Some(Resource::Desert(Some(val))),
+            "WO" => Some(Resource::Wood(val)),
+            "BR" => Some(Resource::Brick(val)),
+            "OR" => Some(Resource::Ore(val)),
+            "WH" => Some(Resource::Wheat(val)),
+            "SH" => Some(Resource::Sheep(val)),
+            "GO" => Some(Resource::Gold(val)),
+            _ => None,
+        }
+    }
+
+    /// Card a player receives from the tile, gold fields let the player choose
+    pub fn card(&self) -> Option<ResourceCard> {
+        match self {
+            Resource::Desert(_) | Resource::Gold(_) => None,
+            Resource::Wood(_) => Some(ResourceCard::Wood),
+            Resource::Brick(_) => Some(ResourceCard::Brick),
+            Resource::Ore(_) => Some(ResourceCard::Ore),
+            Resource::Wheat(_) => Some(ResourceCard::Wheat),
+            Resource::Sheep(_) => Some(ResourceCard::Sheep),
+        }
+    }
+
+    /// Commodity a city on the tile also receives with Cities & Knights
+    pub fn commodity(&self) -> Option<ResourceCard> {
+        match self {
+            Resource::Wood(_) => Some(ResourceCard::Paper),
+            Resource::Sheep(_) => Some(ResourceCard::Cloth),
+            Resource::Ore(_) => Some(ResourceCard::Coin),
+            _ => None,
         }
     }
 
@@ -29,10 +81,40 @@ impl Resource {
             Resource::Ore(c) => *c,
             Resource::Wheat(c) => *c,
             Resource::Sheep(c) => *c,
+            Resource::Gold(c) => *c,
         }
     }
 }
 
+// Cards held in a player's hand, the five resources and the commodities of Cities & Knights
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ResourceCard {
+    Wood,
+    Brick,
+    Ore,
+    Wheat,
+    Sheep,
+    Paper,
+    Cloth,
+    Coin,
+}
+
+impl ResourceCard {
+    pub const RESOURCES: [ResourceCard; 5] = [
+        ResourceCard::Wood,
+        ResourceCard::Brick,
+        ResourceCard::Ore,
+        ResourceCard::Wheat,
+        ResourceCard::Sheep,
+    ];
+    pub const COMMODITIES: [ResourceCard; 3] =
+        [ResourceCard::Paper, ResourceCard::Cloth, ResourceCard::Coin];
+
+    pub fn is_commodity(&self) -> bool {
+        ResourceCard::COMMODITIES.contains(self)
+    }
+}
+
 // Development cards (gambling cards)
 #[allow(dead_code)]
 pub enum Gamble {
@@ -44,11 +126,13 @@ pub enum Gamble {
 }
 
 // Entities that can occupy a tile on the board, e.g the robber or merchant
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
 pub enum Occupant {
     Robber,
     Merchant,
+    /// Seafarers, sits on the sea blocking ships
+    Pirate,
 }
 
 #[cfg(test)]
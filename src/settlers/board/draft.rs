@@ -0,0 +1,306 @@
+//! A map being drawn in the map editor. Unlike a `Board`, a draft may be anything in between
+//! two valid maps: it is checked by writing it as .focm and parsing it back, so the editor
+//! reports exactly what loading the saved file would.
+
+use super::{
+    card::Resource,
+    focm::{self, ParseMapErrors},
+    hex::{Hex, Terrain},
+    map::{Board, FogStack},
+    topology::HexCoord,
+};
+use crate::settlers::expansions::Expansion;
+use rand::{rngs::StdRng, SeedableRng};
+use std::path::Path;
+
+/// What painting a cell puts there
+#[derive(Debug, Clone, Copy)]
+pub enum Brush {
+    /// No hex at all
+    Void,
+    /// Land whose tile is left to the randomizer
+    Land,
+    Water,
+    Harbor,
+    Frame,
+    /// Land of a resource, its chance left to the randomizer
+    Resource(Resource),
+    /// Land with both its resource and chance fixed
+    Tile(Resource),
+}
+
+impl Brush {
+    pub fn cell(&self) -> Option<Hex> {
+        match self {
+            Brush::Void => None,
+            Brush::Land => Some(Hex::new()),
+            Brush::Water => Some(Hex::with_terrain(Terrain::Water)),
+            Brush::Harbor => Some(Hex::with_terrain(Terrain::Harbor)),
+            Brush::Frame => Some(Hex::with_terrain(Terrain::Frame)),
+            Brush::Resource(resource) => Some(Hex::fixed_resource(*resource)),
+            Brush::Tile(resource) => Some(Hex::fixed(*resource)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MapDraft {
+    expansion: Expansion,
+    /// Cells of the map, without the border a `Board` has
+    tiles: Vec<Vec<Option<Hex>>>,
+    distribution: Vec<Resource>,
+    chances: Vec<u8>,
+    /// Kept from the map the draft was opened from, the editor doesn't change it
+    fog: FogStack,
+    island_bonus: u8,
+}
+
+impl MapDraft {
+    /// A sea of `rows` by `columns` water cells, with nothing declared
+    pub fn new(rows: usize, columns: usize) -> Self {
+        MapDraft {
+            expansion: Expansion::Base,
+            tiles: vec![vec![Brush::Water.cell(); columns.max(1)]; rows.max(1)],
+            distribution: Vec::new(),
+            chances: Vec::new(),
+            fog: FogStack::default(),
+            island_bonus: 0,
+        }
+    }
+
+    /// Draft of a parsed map, before it is randomized
+    pub fn from_board(board: &Board) -> Self {
+        let tiles = board.tiles();
+        let (rows, columns) = (tiles.len() - 2, tiles[0].len() - 2);
+        MapDraft {
+            expansion: board.expansion(),
+            tiles: tiles[1..=rows]
+                .iter()
+                .map(|row| row[1..=columns].to_vec())
+                .collect(),
+            distribution: board.distribution().to_vec(),
+            chances: board.chances().to_vec(),
+            fog: board.fog().clone(),
+            island_bonus: board.island_bonus(),
+        }
+    }
+
+    /// Rows and columns of the map
+    pub fn size(&self) -> (usize, usize) {
+        (self.tiles.len(), self.tiles[0].len())
+    }
+
+    /// Grow or shrink the map from its bottom right, new cells are water
+    pub fn resize(&mut self, rows: usize, columns: usize) {
+        let columns = columns.max(1);
+        self.tiles.resize(rows.max(1), Vec::new());
+        for row in self.tiles.iter_mut() {
+            row.resize(columns, Brush::Water.cell());
+        }
+    }
+
+    pub fn expansion(&self) -> Expansion {
+        self.expansion
+    }
+
+    pub fn set_expansion(&mut self, expansion: Expansion) {
+        self.expansion = expansion;
+    }
+
+    /// Whether the board coordinate is a cell of the map, the border around it isn't
+    pub fn contains(&self, coord: HexCoord) -> bool {
+        let (rows, columns) = self.size();
+        coord
+            .index()
+            .is_some_and(|(row, col)| (1..=rows).contains(&row) && (1..=columns).contains(&col))
+    }
+
+    /// Cell of the board coordinate
+    fn cell_mut(&mut self, coord: HexCoord) -> Option<&mut Option<Hex>> {
+        let (row, col) = coord.index()?;
+        self.tiles
+            .get_mut(row.checked_sub(1)?)?
+            .get_mut(col.checked_sub(1)?)
+    }
+
+    /// Paint the cell at a board coordinate, `false` if it is off the map
+    pub fn paint(&mut self, coord: HexCoord, brush: Brush) -> bool {
+        match self.cell_mut(coord) {
+            Some(cell) => {
+                *cell = brush.cell();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Land cells painted, each needs a tile from the resources
+    pub fn land(&self) -> u32 {
+        self.tiles
+            .iter()
+            .flatten()
+            .flatten()
+            .filter(|hex| hex.is_land())
+            .count() as u32
+    }
+
+    /// Tiles of the resource declared by [RESOURCES]
+    pub fn count(&self, code: &str) -> u32 {
+        self.distribution
+            .iter()
+            .filter(|resource| resource.code() == code)
+            .map(|resource| match resource {
+                Resource::Desert(count) => count.unwrap_or(0) as u32,
+                resource => resource.chance() as u32,
+            })
+            .sum()
+    }
+
+    /// Declare `count` tiles of the resource, none removes it from [RESOURCES]
+    pub fn set_count(&mut self, code: &str, count: u8) {
+        let index = self.distribution.iter().position(|r| r.code() == code);
+        self.distribution.retain(|resource| resource.code() != code);
+        if let (Some(resource), true) = (Resource::from_code(code, count), count > 0) {
+            let index = index.unwrap_or(self.distribution.len());
+            self.distribution.insert(index, resource);
+        }
+    }
+
+    pub fn chances(&self) -> &[u8] {
+        &self.chances
+    }
+
+    pub fn set_chances(&mut self, chances: Vec<u8>) {
+        self.chances = chances;
+    }
+
+    pub fn island_bonus(&self) -> u8 {
+        self.island_bonus
+    }
+
+    pub fn set_island_bonus(&mut self, island_bonus: u8) {
+        self.island_bonus = island_bonus;
+    }
+
+    /// The draft as painted, random tiles not picked yet
+    pub fn board(&self) -> Board {
+        let (rows, columns) = self.size();
+        let mut tiles = vec![vec![None; columns + 2]; rows + 2];
+        for (row, cells) in self.tiles.iter().enumerate() {
+            tiles[row + 1][1..=columns].clone_from_slice(cells);
+        }
+        Board::new(
+            self.expansion,
+            tiles,
+            self.distribution.clone(),
+            self.chances.clone(),
+            self.fog.clone(),
+            self.island_bonus,
+        )
+    }
+
+    pub fn to_focm(&self) -> String {
+        self.board().to_focm(false)
+    }
+
+    /// The board loading the saved draft gives, or why it can't be loaded
+    pub fn validate(&self) -> Result<Board, ParseMapErrors> {
+        focm::parse(&self.to_focm())
+    }
+
+    /// What a game on the draft could look like: a valid draft randomized with `seed`, otherwise
+    /// the draft as painted
+    pub fn preview(&self, seed: u64) -> Board {
+        match self.validate() {
+            Ok(mut board) => {
+                board.randomize_with(&mut StdRng::seed_from_u64(seed));
+                board
+            }
+            Err(_) => self.board(),
+        }
+    }
+
+    pub fn save(&self, file: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(file, self.to_focm())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settlers::board::focm::ParseErrorKind;
+    use crate::settlers::board::maps;
+
+    /// Two land cells in the middle of a 3 by 3 sea
+    fn island() -> MapDraft {
+        let mut draft = MapDraft::new(3, 3);
+        assert!(draft.paint(HexCoord::new(2, 2), Brush::Land));
+        assert!(draft.paint(HexCoord::new(2, 3), Brush::Tile(Resource::Ore(9))));
+        draft
+    }
+
+    #[test]
+    fn validates_as_painted() {
+        let mut draft = island();
+        assert_eq!(draft.land(), 2);
+        let kinds: Vec<ParseErrorKind> = draft
+            .validate()
+            .err()
+            .map(|errors| errors.0.into_iter().map(|e| e.kind).collect())
+            .unwrap_or_default();
+        assert_eq!(
+            kinds,
+            vec![
+                ParseErrorKind::EmptySection("RESOURCES"),
+                ParseErrorKind::EmptySection("CHANCES")
+            ]
+        );
+
+        draft.set_count("WO", 1);
+        draft.set_count("OR", 1);
+        draft.set_chances(vec![5, 9]);
+        let board = draft.validate().unwrap();
+        assert!(board.hex(HexCoord::new(2, 3)).unwrap().is_fixed());
+        assert!(draft.to_focm().contains("[LAYOUT]\n-- -- --\n-- ? OR9\n"));
+        // The random cell gets the wood left over
+        let preview = draft.preview(1);
+        assert_eq!(
+            preview.hex(HexCoord::new(2, 2)).unwrap().resource().code(),
+            "WO"
+        );
+    }
+
+    #[test]
+    fn paints_inside_the_map() {
+        let mut draft = MapDraft::new(2, 2);
+        assert!(!draft.paint(HexCoord::new(0, 1), Brush::Land));
+        assert!(!draft.paint(HexCoord::new(3, 1), Brush::Land));
+        assert!(draft.contains(HexCoord::new(2, 2)));
+        assert!(!draft.contains(HexCoord::new(2, 3)));
+        assert!(draft.paint(HexCoord::new(1, 1), Brush::Void));
+        draft.resize(3, 1);
+        assert_eq!(draft.size(), (3, 1));
+        assert!(draft.to_focm().contains("[MAP]\n3x1\n0\nW\nW\n"));
+    }
+
+    #[test]
+    fn declared_counts() {
+        let mut draft = island();
+        draft.set_count("WO", 4);
+        draft.set_count("DE", 1);
+        draft.set_count("WO", 3);
+        assert_eq!((draft.count("WO"), draft.count("DE")), (3, 1));
+        assert!(draft.to_focm().contains("[RESOURCES]\nWO:3, DE:1\n"));
+        draft.set_count("WO", 0);
+        assert_eq!(draft.count("WO"), 0);
+    }
+
+    #[test]
+    fn opens_bundled_maps() {
+        for (name, content) in maps::BUNDLED {
+            let board: Board = content.parse().unwrap();
+            let draft = MapDraft::from_board(&board);
+            assert_eq!(draft.to_focm(), board.to_focm(false), "{} changed", name);
+        }
+    }
+}
@@ -0,0 +1,1523 @@
+use super::{
+    card::Resource,
+    hex::{Fixed, Hex, Terrain},
+    map::{Board, FogStack},
+};
+use crate::settlers::expansions::{Expansion, Ruleset};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Header every .focm file must start with
+pub const FOCM_HEADER: &str = "__FOCM__";
+
+const SECTIONS: [&str; 7] = [
+    "EXPANSION",
+    "RESOURCES",
+    "CHANCES",
+    "MAP",
+    "LAYOUT",
+    "FOG",
+    "ISLAND_BONUS",
+];
+
+/// Cells of the [MAP] section
+const CELL_VOID: char = '0';
+const CELL_LAND: char = '1';
+const CELL_WATER: char = 'W';
+const CELL_HARBOR: char = 'H';
+const CELL_FRAME: char = 'X';
+/// Fog, not '?' which is a tile left to the randomizer in [LAYOUT]
+const CELL_FOG: char = 'F';
+/// Land cells of a specific resource, with the resource's code
+const CELL_RESOURCES: [(char, &str); 7] = [
+    ('d', "DE"),
+    ('w', "WO"),
+    ('b', "BR"),
+    ('o', "OR"),
+    ('g', "WH"),
+    ('s', "SH"),
+    ('a', "GO"),
+];
+
+/// [FOG] tiles that turn out to be sea
+const FOG_SEA: &str = "SEA";
+
+/// Layout token of a cell without a tile
+const LAYOUT_EMPTY: &str = "--";
+/// Layout token of a tile left to the randomizer
+const LAYOUT_RANDOM: &str = "?";
+
+/// Position of a token in the source, both 1-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    FileNotFound(String),
+    Unreadable(String),
+    NotFocm,
+    UnknownSection,
+    DuplicateSection,
+    MissingSection(&'static str),
+    EmptySection(&'static str),
+    UnexpectedLine,
+    UnknownExpansion,
+    /// Resource or map cell the expansion doesn't play with
+    NotInExpansion(Expansion),
+    ResourceFormat,
+    UnknownResource,
+    ResourceCount,
+    ChanceParseError,
+    ChanceNotSuitable,
+    HexChanceMismatch {
+        tiles: u32,
+        deserts: u32,
+        chances: u32,
+    },
+    MapDimensions,
+    MapRowWidth {
+        row: usize,
+        found: usize,
+        expected: usize,
+    },
+    MapRowCount {
+        found: usize,
+        expected: usize,
+    },
+    UnknownCell,
+    MapSizeIncompatability {
+        map: u32,
+        resources: u32,
+    },
+    LayoutParseError,
+    LayoutRowCount {
+        found: usize,
+        expected: usize,
+    },
+    LayoutRowWidth {
+        row: usize,
+        found: usize,
+        expected: usize,
+    },
+    LayoutMapMismatch,
+    TooManyFixedTiles {
+        code: &'static str,
+        resources: u32,
+    },
+    LayoutChanceUnavailable,
+    FogCount {
+        cells: u32,
+        tiles: u32,
+    },
+    FogChanceMismatch {
+        tiles: u32,
+        chances: u32,
+    },
+    IslandBonus,
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FileNotFound(reason) => write!(f, "map file could not be read ({})", reason),
+            Self::Unreadable(reason) => write!(f, "map could not be read ({})", reason),
+            Self::NotFocm => write!(f, "file must start with {}", FOCM_HEADER),
+            Self::UnknownSection => write!(
+                f,
+                "unknown section, expected one of [{}]",
+                SECTIONS.join("], [")
+            ),
+            Self::DuplicateSection => write!(f, "section is declared more than once"),
+            Self::MissingSection(name) => write!(f, "missing [{}] section", name),
+            Self::EmptySection(name) => write!(f, "[{}] section has no data", name),
+            Self::UnexpectedLine => write!(f, "line does not belong to any section"),
+            Self::UnknownExpansion => write!(
+                f,
+                "unknown expansion, expected one of {}",
+                Expansion::ALL.map(|e| e.name()).join(", ")
+            ),
+            Self::NotInExpansion(expansion) => {
+                write!(f, "not available in the {} expansion", expansion)
+            }
+            Self::ResourceFormat => write!(f, "resources must be written as \"RE:X\""),
+            Self::UnknownResource => {
+                write!(
+                    f,
+                    "unknown resource, expected one of WO, BR, OR, WH, SH, GO, DE"
+                )
+            }
+            Self::ResourceCount => write!(f, "resource count must be a number from 1 to 255"),
+            Self::ChanceParseError => write!(f, "chance is not a number"),
+            Self::ChanceNotSuitable => write!(f, "chance must be within 2..=12 and not 7"),
+            Self::HexChanceMismatch {
+                tiles,
+                deserts,
+                chances,
+            } => write!(
+                f,
+                "{} chances given but {} tiles need one ({} tiles, {} deserts)",
+                chances,
+                tiles - deserts,
+                tiles,
+                deserts
+            ),
+            Self::MapDimensions => write!(f, "map dimensions must be written as \"ROWSxCOLUMNS\""),
+            Self::MapRowWidth {
+                row,
+                found,
+                expected,
+            } => write!(
+                f,
+                "row {} has {} characters, expected {}",
+                row, found, expected
+            ),
+            Self::MapRowCount { found, expected } => {
+                write!(f, "map has {} rows, expected {}", found, expected)
+            }
+            Self::UnknownCell => write!(
+                f,
+                "unknown map cell, expected one of 0 1 {} {} {} {} or a resource ({})",
+                CELL_WATER,
+                CELL_HARBOR,
+                CELL_FRAME,
+                CELL_FOG,
+                CELL_RESOURCES
+                    .iter()
+                    .map(|(cell, _)| cell.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            Self::MapSizeIncompatability { map, resources } => write!(
+                f,
+                "map has {} land tiles but [RESOURCES] lists {}",
+                map, resources
+            ),
+            Self::LayoutParseError => write!(
+                f,
+                "layout cells must be a resource code and chance (\"WO8\"), \"DE\", \"{}\" or \"{}\"",
+                LAYOUT_RANDOM, LAYOUT_EMPTY
+            ),
+            Self::LayoutRowCount { found, expected } => {
+                write!(f, "layout has {} rows, expected {}", found, expected)
+            }
+            Self::LayoutRowWidth {
+                row,
+                found,
+                expected,
+            } => write!(
+                f,
+                "layout row {} has {} cells, expected {}",
+                row, found, expected
+            ),
+            Self::LayoutMapMismatch => {
+                write!(f, "layout cell does not match the land in [MAP]")
+            }
+            Self::TooManyFixedTiles { code, resources } => write!(
+                f,
+                "more {} tiles are fixed than the {} listed in [RESOURCES]",
+                code, resources
+            ),
+            Self::LayoutChanceUnavailable => {
+                write!(f, "chance is not left in [CHANCES] for this tile")
+            }
+            Self::FogCount { cells, tiles } => write!(
+                f,
+                "map has {} fog cells but [FOG] holds {} tiles",
+                cells, tiles
+            ),
+            Self::FogChanceMismatch { tiles, chances } => write!(
+                f,
+                "{} chances given but {} fog tiles need one",
+                chances, tiles
+            ),
+            Self::IslandBonus => write!(f, "island bonus must be a number of victory points"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseMapError {
+    pub kind: ParseErrorKind,
+    pub location: Option<Location>,
+    /// The offending text, if the error can be pinned to one
+    pub token: Option<String>,
+}
+
+impl ParseMapError {
+    fn new(kind: ParseErrorKind) -> Self {
+        Self {
+            kind,
+            location: None,
+            token: None,
+        }
+    }
+
+    fn at(kind: ParseErrorKind, line: usize, column: usize, token: &str) -> Self {
+        Self {
+            kind,
+            location: Some(Location { line, column }),
+            token: Some(token.to_string()),
+        }
+    }
+}
+
+impl Display for ParseMapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = self.location {
+            write!(f, "{}: ", location)?;
+        }
+        write!(f, "{}", self.kind)?;
+        if let Some(token) = &self.token {
+            write!(f, " (found `{}`)", token)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseMapError {}
+
+/// Every error found while parsing a map, in the order they appear in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseMapErrors(pub Vec<ParseMapError>);
+
+impl ParseMapErrors {
+    pub fn errors(&self) -> &[ParseMapError] {
+        &self.0
+    }
+}
+
+impl From<ParseMapError> for ParseMapErrors {
+    fn from(error: ParseMapError) -> Self {
+        Self(vec![error])
+    }
+}
+
+impl Display for ParseMapErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} error(s) in map", self.0.len())?;
+        for error in self.0.iter() {
+            write!(f, "\n  {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseMapErrors {}
+
+/// A meaningful (not blank, not comment) line of the source
+#[derive(Debug, Clone, Copy)]
+struct Line<'a> {
+    number: usize,
+    /// Column of the first non-whitespace character
+    column: usize,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Split the line on `sep`, returning each trimmed token with its column
+    fn tokens(&self, sep: char) -> Vec<(usize, &'a str)> {
+        let mut tokens = Vec::new();
+        let mut start = 0;
+        for piece in self.text.split(sep) {
+            let leading = piece.len() - piece.trim_start().len();
+            tokens.push((
+                self.column + self.text[..start + leading].chars().count(),
+                piece.trim(),
+            ));
+            start += piece.len() + sep.len_utf8();
+        }
+        tokens
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseMapError {
+        ParseMapError::at(kind, self.number, self.column, self.text)
+    }
+}
+
+#[derive(Debug)]
+struct Section<'a> {
+    header: Line<'a>,
+    body: Vec<Line<'a>>,
+}
+
+/// Parse the content of a .focm file into a board
+pub fn parse(content: &str) -> Result<Board, ParseMapErrors> {
+    // Filter the comments and newlines from the file
+    let lines: Vec<Line> = content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let text = line.trim();
+            if text.starts_with('#') || text.is_empty() {
+                None
+            } else {
+                Some(Line {
+                    number: i + 1,
+                    column: line[..line.len() - line.trim_start().len()].chars().count() + 1,
+                    text,
+                })
+            }
+        })
+        .collect();
+    // First line should be __FOCM__
+    match lines.first() {
+        Some(line) if line.text == FOCM_HEADER => (),
+        Some(line) => Err(line.error(ParseErrorKind::NotFocm))?,
+        None => Err(ParseMapError::new(ParseErrorKind::NotFocm))?,
+    }
+
+    let mut errors = Vec::new();
+    let sections = split_sections(&lines[1..], &mut errors);
+    let mut section = |name: &'static str| match find_section(&sections, name) {
+        Some(section) if section.body.is_empty() => {
+            errors.push(section.header.error(ParseErrorKind::EmptySection(name)));
+            None
+        }
+        Some(section) => Some(section),
+        None => {
+            errors.push(ParseMapError::new(ParseErrorKind::MissingSection(name)));
+            None
+        }
+    };
+    let expansion_section = section("EXPANSION");
+    let resource_section = section("RESOURCES");
+    let chance_section = section("CHANCES");
+    let map_section = section("MAP");
+
+    let expansion = expansion_section.and_then(|section| {
+        for extra in section.body.iter().skip(1) {
+            errors.push(extra.error(ParseErrorKind::UnexpectedLine));
+        }
+        let line = section.body[0];
+        match line.text.parse::<Expansion>() {
+            Ok(expansion) => Some(expansion),
+            Err(_) => {
+                errors.push(line.error(ParseErrorKind::UnknownExpansion));
+                None
+            }
+        }
+    });
+    // Without a known expansion the rest of the map is checked against the base game
+    let rules = expansion.unwrap_or(Expansion::Base).rules();
+    let resources = resource_section.and_then(|s| parse_resources(s, rules.as_ref(), &mut errors));
+    let chances = chance_section.and_then(|s| parse_chances(s, &mut errors));
+    let mut map = map_section.and_then(|s| parse_map(s, rules.as_ref(), &mut errors));
+    // Optional sections
+    let mut optional = |name: &'static str| match find_section(&sections, name) {
+        Some(section) if section.body.is_empty() => {
+            errors.push(section.header.error(ParseErrorKind::EmptySection(name)));
+            None
+        }
+        section => section,
+    };
+    let fog_section = optional("FOG");
+    let bonus_section = optional("ISLAND_BONUS");
+    let fog = match fog_section {
+        Some(section) => parse_fog(section, rules.as_ref(), &mut errors),
+        None => Some(FogStack::default()),
+    };
+    let island_bonus = bonus_section.map_or(Some(0), |s| parse_island_bonus(s, &mut errors));
+    // Every fog cell needs a tile to reveal
+    if let (Some(fog), Some(map), Some(section)) = (&fog, &map, map_section) {
+        let tiles = fog.tiles.len() as u32;
+        if map.fog != tiles {
+            errors.push(fog_section.map_or(section.header, |fog| fog.header).error(
+                ParseErrorKind::FogCount {
+                    cells: map.fog,
+                    tiles,
+                },
+            ));
+        }
+    }
+
+    if let (Some(resources), Some(section)) = (&resources, resource_section) {
+        let tiles = total_tiles(resources);
+        let deserts = desert_tiles(resources);
+        if let Some(chances) = &chances {
+            // Ensure total chances == total tiles (excepting the desert tiles)
+            if tiles - deserts != chances.len() as u32 {
+                errors.push(section.header.error(ParseErrorKind::HexChanceMismatch {
+                    tiles,
+                    deserts,
+                    chances: chances.len() as u32,
+                }));
+            }
+        }
+        // Ensure the map has a slot for every tile
+        if let (Some(map), Some(section)) = (&map, map_section) {
+            if map.land != tiles {
+                errors.push(
+                    section
+                        .header
+                        .error(ParseErrorKind::MapSizeIncompatability {
+                            map: map.land,
+                            resources: tiles,
+                        }),
+                );
+            }
+        }
+    }
+
+    // Take the tiles fixed by the map and layout out of the declared ones
+    if let (Some(resources), Some(chances), Some(map)) = (&resources, &chances, &mut map) {
+        if errors.is_empty() {
+            let mut pool = TilePool::new(resources, chances);
+            for (location, resource) in map.fixed.iter() {
+                if let Err(kind) = pool.take_tile(resource) {
+                    errors.push(ParseMapError::at(
+                        kind,
+                        location.line,
+                        location.column,
+                        &cell_code(Some(&Hex::fixed_resource(*resource))).to_string(),
+                    ));
+                }
+            }
+            if let Some(layout) = find_section(&sections, "LAYOUT") {
+                apply_layout(layout, &mut map.tiles, &mut pool, &mut errors);
+            }
+        }
+    }
+
+    match (expansion, resources, chances, map, fog, island_bonus) {
+        (
+            Some(expansion),
+            Some(resources),
+            Some(chances),
+            Some(map),
+            Some(fog),
+            Some(island_bonus),
+        ) if errors.is_empty() => Ok(Board::new(
+            expansion,
+            map.tiles,
+            resources,
+            chances,
+            fog,
+            island_bonus,
+        )),
+        _ => {
+            errors.sort_by_key(|e| e.location.map(|l| (l.line, l.column)));
+            Err(ParseMapErrors(errors))
+        }
+    }
+}
+
+fn find_section<'s, 'a>(sections: &'s [Section<'a>], name: &str) -> Option<&'s Section<'a>> {
+    sections
+        .iter()
+        .find(|s| s.header.text[1..s.header.text.len() - 1] == *name)
+}
+
+/// Group lines under the [TAG] that precedes them
+fn split_sections<'a>(lines: &[Line<'a>], errors: &mut Vec<ParseMapError>) -> Vec<Section<'a>> {
+    let mut sections: Vec<Section> = Vec::new();
+    // Whether lines currently belong to a valid section
+    let mut collecting = false;
+    let mut seen_header = false;
+    for line in lines {
+        if line.text.starts_with('[') && line.text.ends_with(']') {
+            let name = &line.text[1..line.text.len() - 1];
+            collecting = false;
+            seen_header = true;
+            if !SECTIONS.contains(&name) {
+                errors.push(line.error(ParseErrorKind::UnknownSection));
+            } else if sections.iter().any(|s| s.header.text == line.text) {
+                errors.push(line.error(ParseErrorKind::DuplicateSection));
+            } else {
+                sections.push(Section {
+                    header: *line,
+                    body: Vec::new(),
+                });
+                collecting = true;
+            }
+        } else if collecting {
+            sections.last_mut().unwrap().body.push(*line);
+        } else if !seen_header {
+            // Lines in an unknown or duplicate section were already reported through their header
+            errors.push(line.error(ParseErrorKind::UnexpectedLine));
+        }
+    }
+    sections
+}
+
+fn parse_resources(
+    section: &Section,
+    rules: &dyn Ruleset,
+    errors: &mut Vec<ParseMapError>,
+) -> Option<Vec<Resource>> {
+    let error_count = errors.len();
+    let mut resources = Vec::new();
+    for line in section.body.iter() {
+        for (column, code, count) in parse_counts(line, errors) {
+            match parse_resource_code(line, column, code, count, rules) {
+                Ok(resource) => resources.push(resource),
+                Err(error) => errors.push(error),
+            }
+        }
+    }
+    (errors.len() == error_count).then_some(resources)
+}
+
+/// Split a line of "RE:X" tokens into their column, code and count
+fn parse_counts<'a>(line: &Line<'a>, errors: &mut Vec<ParseMapError>) -> Vec<(usize, &'a str, u8)> {
+    let mut counts = Vec::new();
+    for (column, token) in line.tokens(',') {
+        let res_split: Vec<_> = token.split(':').collect();
+        if res_split.len() != 2 {
+            // Resources should be in the format "RE:X"
+            errors.push(ParseMapError::at(
+                ParseErrorKind::ResourceFormat,
+                line.number,
+                column,
+                token,
+            ));
+            continue;
+        }
+        match res_split[1].trim().parse::<u8>() {
+            Ok(count) if count > 0 => counts.push((column, res_split[0].trim(), count)),
+            _ => errors.push(ParseMapError::at(
+                ParseErrorKind::ResourceCount,
+                line.number,
+                column + token.find(':').unwrap() + 1,
+                res_split[1].trim(),
+            )),
+        }
+    }
+    counts
+}
+
+/// Resource of a code holding `count`, if the expansion plays with it
+fn parse_resource_code(
+    line: &Line,
+    column: usize,
+    code: &str,
+    count: u8,
+    rules: &dyn Ruleset,
+) -> Result<Resource, ParseMapError> {
+    match Resource::from_code(code, count) {
+        Some(resource) if !rules.resources().contains(&resource.code()) => Err(ParseMapError::at(
+            ParseErrorKind::NotInExpansion(rules.expansion()),
+            line.number,
+            column,
+            code,
+        )),
+        Some(resource) => Ok(resource),
+        None => Err(ParseMapError::at(
+            ParseErrorKind::UnknownResource,
+            line.number,
+            column,
+            code,
+        )),
+    }
+}
+
+fn parse_chances(section: &Section, errors: &mut Vec<ParseMapError>) -> Option<Vec<u8>> {
+    let error_count = errors.len();
+    let chances = section
+        .body
+        .iter()
+        .flat_map(|line| parse_chance_line(line, errors))
+        .collect();
+    (errors.len() == error_count).then_some(chances)
+}
+
+fn parse_chance_line(line: &Line, errors: &mut Vec<ParseMapError>) -> Vec<u8> {
+    let mut chances = Vec::new();
+    for (column, token) in line.tokens(',') {
+        match token.parse::<u8>() {
+            Ok(val) if (2..=12).contains(&val) && val != 7 => chances.push(val),
+            Ok(_) => errors.push(ParseMapError::at(
+                ParseErrorKind::ChanceNotSuitable,
+                line.number,
+                column,
+                token,
+            )),
+            Err(_) => errors.push(ParseMapError::at(
+                ParseErrorKind::ChanceParseError,
+                line.number,
+                column,
+                token,
+            )),
+        }
+    }
+    chances
+}
+
+/// Parse the face down tiles of [FOG]: a line of tiles, "SEA" standing for water, and a line
+/// with the chances of the tiles that need one
+fn parse_fog(
+    section: &Section,
+    rules: &dyn Ruleset,
+    errors: &mut Vec<ParseMapError>,
+) -> Option<FogStack> {
+    let error_count = errors.len();
+    if !rules.terrain().contains(&Terrain::Fog) {
+        errors.push(
+            section
+                .header
+                .error(ParseErrorKind::NotInExpansion(rules.expansion())),
+        );
+        return None;
+    }
+    let mut fog = FogStack::default();
+    for (column, code, count) in parse_counts(&section.body[0], errors) {
+        let tile = if code == FOG_SEA {
+            None
+        } else {
+            match parse_resource_code(&section.body[0], column, code, 0, rules) {
+                Ok(resource) => Some(resource.clone_with_value(0)),
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            }
+        };
+        fog.tiles.extend((0..count).map(|_| tile));
+    }
+    if let Some(line) = section.body.get(1) {
+        fog.chances = parse_chance_line(line, errors);
+    }
+    for extra in section.body.iter().skip(2) {
+        errors.push(extra.error(ParseErrorKind::UnexpectedLine));
+    }
+    let numbered = fog.numbered_tiles();
+    if errors.len() == error_count && numbered != fog.chances.len() as u32 {
+        errors.push(section.header.error(ParseErrorKind::FogChanceMismatch {
+            tiles: numbered,
+            chances: fog.chances.len() as u32,
+        }));
+    }
+    (errors.len() == error_count).then_some(fog)
+}
+
+fn parse_island_bonus(section: &Section, errors: &mut Vec<ParseMapError>) -> Option<u8> {
+    for extra in section.body.iter().skip(1) {
+        errors.push(extra.error(ParseErrorKind::UnexpectedLine));
+    }
+    match section.body[0].text.parse::<u8>() {
+        Ok(points) => Some(points),
+        Err(_) => {
+            errors.push(section.body[0].error(ParseErrorKind::IslandBonus));
+            None
+        }
+    }
+}
+
+/// Kind of each [MAP] cell, see `parse_cell`
+struct ParsedMap {
+    /// The map, with a border of empty tiles
+    tiles: Vec<Vec<Option<Hex>>>,
+    /// Number of land tiles
+    land: u32,
+    /// Number of fog cells
+    fog: u32,
+    /// Land cells with a fixed resource and where they are written
+    fixed: Vec<(Location, Resource)>,
+}
+
+fn parse_map(
+    section: &Section,
+    rules: &dyn Ruleset,
+    errors: &mut Vec<ParseMapError>,
+) -> Option<ParsedMap> {
+    let error_count = errors.len();
+    // Get dimensions
+    let dim_line = section.body[0];
+    let map_dim: Vec<usize> = match dim_line
+        .text
+        .split('x')
+        .map(|dim| dim.trim().parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(dim) if dim.len() == 2 && dim[0] > 0 && dim[1] > 0 => dim,
+        _ => {
+            errors.push(dim_line.error(ParseErrorKind::MapDimensions));
+            return None;
+        }
+    };
+    let (rows, columns) = (map_dim[0], map_dim[1]);
+    let grid = &section.body[1..];
+    if grid.len() < rows {
+        errors.push(dim_line.error(ParseErrorKind::MapRowCount {
+            found: grid.len(),
+            expected: rows,
+        }));
+    }
+    for extra in grid.iter().skip(rows) {
+        errors.push(extra.error(ParseErrorKind::MapRowCount {
+            found: grid.len(),
+            expected: rows,
+        }));
+    }
+    // We add 1 to create a border around the map
+    let mut map = ParsedMap {
+        tiles: vec![vec![None; columns + 2]; rows + 2],
+        land: 0,
+        fog: 0,
+        fixed: Vec::new(),
+    };
+    for (i, row) in grid.iter().take(rows).enumerate() {
+        let width = row.text.chars().count();
+        if width != columns {
+            errors.push(ParseMapError::at(
+                ParseErrorKind::MapRowWidth {
+                    row: i + 1,
+                    found: width,
+                    expected: columns,
+                },
+                row.number,
+                row.column,
+                row.text,
+            ));
+        }
+        for (j, c) in row.text.chars().enumerate().take(columns) {
+            let location = Location {
+                line: row.number,
+                column: row.column + j,
+            };
+            match parse_cell(c) {
+                Some(Some(hex))
+                    if !rules.terrain().contains(&hex.terrain())
+                        || !rules.resources().contains(&hex.resource().code()) =>
+                {
+                    errors.push(ParseMapError::at(
+                        ParseErrorKind::NotInExpansion(rules.expansion()),
+                        location.line,
+                        location.column,
+                        &c.to_string(),
+                    ))
+                }
+                Some(cell) => {
+                    if cell
+                        .as_ref()
+                        .is_some_and(|hex| hex.terrain() == Terrain::Fog)
+                    {
+                        map.fog += 1;
+                    }
+                    if let Some(hex) = cell.as_ref().filter(|hex| hex.is_land()) {
+                        map.land += 1;
+                        if hex.fixed_part() == Fixed::Resource {
+                            map.fixed.push((location, hex.resource()));
+                        }
+                    }
+                    map.tiles[i + 1][j + 1] = cell;
+                }
+                None => errors.push(ParseMapError::at(
+                    ParseErrorKind::UnknownCell,
+                    location.line,
+                    location.column,
+                    &c.to_string(),
+                )),
+            }
+        }
+    }
+    (errors.len() == error_count).then_some(map)
+}
+
+/// Parse a [MAP] cell, `Some(None)` being a cell without a hex
+fn parse_cell(c: char) -> Option<Option<Hex>> {
+    let hex = match c {
+        CELL_VOID => return Some(None),
+        CELL_LAND => Hex::new(),
+        CELL_WATER => Hex::with_terrain(Terrain::Water),
+        CELL_HARBOR => Hex::with_terrain(Terrain::Harbor),
+        CELL_FRAME => Hex::with_terrain(Terrain::Frame),
+        CELL_FOG => Hex::with_terrain(Terrain::Fog),
+        _ => {
+            let (_, code) = CELL_RESOURCES.iter().find(|(cell, _)| *cell == c)?;
+            Hex::fixed_resource(Resource::from_code(code, 0)?)
+        }
+    };
+    Some(Some(hex))
+}
+
+fn cell_code(cell: Option<&Hex>) -> char {
+    let Some(hex) = cell else {
+        return CELL_VOID;
+    };
+    match hex.terrain() {
+        Terrain::Land if hex.fixed_part() == Fixed::Resource => CELL_RESOURCES
+            .iter()
+            .find(|(_, code)| *code == hex.resource().code())
+            .map(|(cell, _)| *cell)
+            .unwrap(),
+        Terrain::Land => CELL_LAND,
+        Terrain::Water => CELL_WATER,
+        Terrain::Harbor => CELL_HARBOR,
+        Terrain::Frame => CELL_FRAME,
+        Terrain::Fog => CELL_FOG,
+    }
+}
+
+/// Tiles and chances declared by the map that have not been fixed in place
+struct TilePool<'r> {
+    resources: &'r [Resource],
+    tiles: Vec<(&'static str, u32)>,
+    chances: Vec<u8>,
+}
+
+impl<'r> TilePool<'r> {
+    fn new(resources: &'r [Resource], chances: &[u8]) -> Self {
+        TilePool {
+            resources,
+            tiles: resources.iter().map(|r| (r.code(), count(r))).collect(),
+            chances: chances.to_vec(),
+        }
+    }
+
+    fn take_tile(&mut self, resource: &Resource) -> Result<(), ParseErrorKind> {
+        let code = resource.code();
+        match self
+            .tiles
+            .iter_mut()
+            .find(|(c, left)| *c == code && *left > 0)
+        {
+            Some((_, left)) => {
+                *left -= 1;
+                Ok(())
+            }
+            None => Err(ParseErrorKind::TooManyFixedTiles {
+                code,
+                resources: self
+                    .resources
+                    .iter()
+                    .filter(|r| r.code() == code)
+                    .map(count)
+                    .sum(),
+            }),
+        }
+    }
+
+    fn take_chance(&mut self, resource: &Resource) -> Result<(), ParseErrorKind> {
+        if matches!(resource, Resource::Desert(_)) {
+            return Ok(());
+        }
+        match self.chances.iter().position(|c| *c == resource.chance()) {
+            Some(index) => {
+                self.chances.swap_remove(index);
+                Ok(())
+            }
+            None => Err(ParseErrorKind::LayoutChanceUnavailable),
+        }
+    }
+}
+
+/// A cell of the [LAYOUT] section
+#[derive(Debug, Clone, Copy)]
+enum LayoutCell {
+    /// "--", a cell without a tile
+    Empty,
+    /// "?", a tile picked by the randomizer
+    Random,
+    /// e.g "WO8" or "DE"
+    Fixed(Resource),
+}
+
+/// Parse a layout cell such as "WO8", "DE", "?" or "--"
+fn parse_layout_cell(token: &str) -> Option<LayoutCell> {
+    match token {
+        LAYOUT_EMPTY => return Some(LayoutCell::Empty),
+        LAYOUT_RANDOM => return Some(LayoutCell::Random),
+        _ => (),
+    }
+    if !token.is_char_boundary(token.len().min(2)) {
+        return None;
+    }
+    let (code, chance) = token.split_at(token.len().min(2));
+    match (Resource::from_code(code, 0)?, chance.parse::<u8>()) {
+        (Resource::Desert(_), _) if chance.is_empty() => {
+            Some(LayoutCell::Fixed(Resource::Desert(None)))
+        }
+        (Resource::Desert(_), _) => None,
+        (resource, Ok(val)) if (2..=12).contains(&val) && val != 7 => {
+            Some(LayoutCell::Fixed(resource.clone_with_value(val)))
+        }
+        _ => None,
+    }
+}
+
+/// Fix the tiles of `map` to the ones given by the layout. Fixed tiles are drawn from the
+/// declared resources and chances, the randomizer fills the "?" cells with what is left.
+fn apply_layout(
+    section: &Section,
+    map: &mut [Vec<Option<Hex>>],
+    pool: &mut TilePool,
+    errors: &mut Vec<ParseMapError>,
+) {
+    // Map rows and columns without the border
+    let (rows, columns) = (map.len() - 2, map[0].len() - 2);
+    if section.body.len() != rows {
+        errors.push(section.header.error(ParseErrorKind::LayoutRowCount {
+            found: section.body.len(),
+            expected: rows,
+        }));
+    }
+    for (i, line) in section.body.iter().take(rows).enumerate() {
+        let cells: Vec<_> = line
+            .tokens(' ')
+            .into_iter()
+            .filter(|(_, token)| !token.is_empty())
+            .collect();
+        if cells.len() != columns {
+            errors.push(line.error(ParseErrorKind::LayoutRowWidth {
+                row: i + 1,
+                found: cells.len(),
+                expected: columns,
+            }));
+            continue;
+        }
+        for (j, (column, token)) in cells.into_iter().enumerate() {
+            let error = |kind| ParseMapError::at(kind, line.number, column, token);
+            let cell = &mut map[i + 1][j + 1];
+            let land = cell.as_ref().filter(|hex| hex.is_land());
+            let resource = match (parse_layout_cell(token), land) {
+                (None, _) => {
+                    errors.push(error(ParseErrorKind::LayoutParseError));
+                    continue;
+                }
+                (Some(LayoutCell::Empty), None) | (Some(LayoutCell::Random), Some(_)) => continue,
+                (Some(LayoutCell::Fixed(resource)), Some(hex)) => {
+                    let resource_fixed = hex.fixed_part() == Fixed::Resource;
+                    if resource_fixed && hex.resource().code() != resource.code() {
+                        errors.push(error(ParseErrorKind::LayoutMapMismatch));
+                        continue;
+                    }
+                    // Cells with a resource in [MAP] already took their tile from the pool
+                    if !resource_fixed {
+                        if let Err(kind) = pool.take_tile(&resource) {
+                            errors.push(error(kind));
+                            continue;
+                        }
+                    }
+                    resource
+                }
+                (Some(_), _) => {
+                    errors.push(error(ParseErrorKind::LayoutMapMismatch));
+                    continue;
+                }
+            };
+            match pool.take_chance(&resource) {
+                Ok(()) => *cell = Some(Hex::fixed(resource)),
+                Err(kind) => errors.push(error(kind)),
+            }
+        }
+    }
+}
+
+/// Write a board back out as .focm
+pub fn write(board: &Board, fixed_layout: bool) -> String {
+    let tiles = board.tiles();
+    let (rows, columns) = (tiles.len() - 2, tiles[0].len() - 2);
+    let resources: Vec<String> = board
+        .distribution()
+        .iter()
+        .map(|res| format!("{}:{}", res.code(), count(res)))
+        .collect();
+    let chances: Vec<String> = board.chances().iter().map(|c| c.to_string()).collect();
+
+    let mut out = format!("{}\n\n", FOCM_HEADER);
+    out += &format!("[EXPANSION]\n{}\n\n", board.expansion());
+    out += &format!("[RESOURCES]\n{}\n\n", resources.join(", "));
+    out += &format!("[CHANCES]\n{}\n\n", chances.join(", "));
+    out += &format!("[MAP]\n{}x{}\n", rows, columns);
+    for row in &tiles[1..=rows] {
+        let row: String = row[1..=columns]
+            .iter()
+            .map(|hex| cell_code(hex.as_ref()))
+            .collect();
+        out += &row;
+        out.push('\n');
+    }
+
+    let fixed = tiles.iter().flatten().flatten().any(|hex| hex.is_fixed());
+    if fixed_layout || fixed {
+        out += "\n[LAYOUT]\n";
+        for row in &tiles[1..=rows] {
+            let row: Vec<String> = row[1..=columns]
+                .iter()
+                .map(|hex| match hex.as_ref().filter(|hex| hex.is_land()) {
+                    None => LAYOUT_EMPTY.to_string(),
+                    Some(hex) if !fixed_layout && !hex.is_fixed() => LAYOUT_RANDOM.to_string(),
+                    Some(hex) => match hex.resource() {
+                        Resource::Desert(_) => "DE".to_string(),
+                        resource => format!("{}{}", resource.code(), resource.chance()),
+                    },
+                })
+                .collect();
+            out += &row.join(" ");
+            out.push('\n');
+        }
+    }
+
+    let fog = board.fog();
+    if !fog.tiles.is_empty() {
+        // Group the stack back into counts, in the order tiles first appear
+        let mut counts: Vec<(&str, u32)> = Vec::new();
+        for tile in fog.tiles.iter() {
+            let code = tile.map_or(FOG_SEA, |resource| resource.code());
+            match counts.iter_mut().find(|(c, _)| *c == code) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((code, 1)),
+            }
+        }
+        let tiles: Vec<String> = counts
+            .iter()
+            .map(|(code, count)| format!("{}:{}", code, count))
+            .collect();
+        let chances: Vec<String> = fog.chances.iter().map(|c| c.to_string()).collect();
+        out += &format!("\n[FOG]\n{}\n", tiles.join(", "));
+        if !chances.is_empty() {
+            out += &format!("{}\n", chances.join(", "));
+        }
+    }
+    if board.island_bonus() > 0 {
+        out += &format!("\n[ISLAND_BONUS]\n{}\n", board.island_bonus());
+    }
+    out
+}
+
+/// Tiles of the resource in a distribution
+fn count(resource: &Resource) -> u32 {
+    match resource {
+        Resource::Desert(count) => count.unwrap_or(0) as u32,
+        Resource::Wood(count)
+        | Resource::Brick(count)
+        | Resource::Ore(count)
+        | Resource::Wheat(count)
+        | Resource::Sheep(count)
+        | Resource::Gold(count) => *count as u32,
+    }
+}
+
+fn total_tiles(resources: &[Resource]) -> u32 {
+    resources.iter().map(count).sum()
+}
+
+fn desert_tiles(resources: &[Resource]) -> u32 {
+    resources
+        .iter()
+        .map(|res| match res {
+            Resource::Desert(count) => count.unwrap_or(0) as u32,
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settlers::board::maps;
+
+    const SMALL: &str = "__FOCM__
+[EXPANSION]
+DEFAULT
+[RESOURCES]
+WO:2, DE:1
+[CHANCES]
+5, 9
+[MAP]
+2x2
+11
+10
+";
+
+    fn kinds(content: &str) -> Vec<ParseErrorKind> {
+        parse(content)
+            .unwrap_err()
+            .0
+            .into_iter()
+            .map(|e| e.kind)
+            .collect()
+    }
+
+    #[test]
+    fn parses_bundled_maps() {
+        for (name, content) in maps::BUNDLED {
+            assert!(parse(content).is_ok(), "{} failed to parse", name);
+        }
+    }
+
+    #[test]
+    fn parses_small_map() {
+        assert!(parse(SMALL).is_ok());
+    }
+
+    #[test]
+    fn parses_large_map() {
+        let row = "1".repeat(11);
+        let content = format!(
+            "__FOCM__\n[EXPANSION]\nDEFAULT\n[RESOURCES]\nWO:60, SH:60, DE:1\n\
+             [CHANCES]\n{}\n[MAP]\n11x11\n{}\n",
+            vec!["6"; 120].join(", "),
+            vec![row; 11].join("\n")
+        );
+        let board = parse(&content).unwrap();
+        assert_eq!(board.hexes().filter(|(_, hex)| hex.is_land()).count(), 121);
+    }
+
+    #[test]
+    fn missing_header() {
+        assert_eq!(kinds(&SMALL[9..]), vec![ParseErrorKind::NotFocm]);
+    }
+
+    #[test]
+    fn resource_error_location() {
+        let errors = parse(&SMALL.replace("WO:2", "XX:2")).unwrap_err();
+        assert_eq!(errors.0.len(), 1);
+        let error = &errors.0[0];
+        assert_eq!(error.kind, ParseErrorKind::UnknownResource);
+        assert_eq!(error.location, Some(Location { line: 5, column: 1 }));
+        assert_eq!(error.token.as_deref(), Some("XX"));
+    }
+
+    #[test]
+    fn chance_error_column() {
+        let errors = parse(&SMALL.replace("5, 9", "5,  7")).unwrap_err();
+        let error = &errors.0[0];
+        assert_eq!(error.kind, ParseErrorKind::ChanceNotSuitable);
+        assert_eq!(error.location, Some(Location { line: 7, column: 5 }));
+    }
+
+    #[test]
+    fn collects_multiple_errors() {
+        let content = SMALL.replace("WO:2", "WO:x").replace("5, 9", "5, 13");
+        assert_eq!(
+            kinds(&content),
+            vec![
+                ParseErrorKind::ResourceCount,
+                ParseErrorKind::ChanceNotSuitable
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_row_width() {
+        let errors = parse(&SMALL.replace("\n10\n", "\n100\n")).unwrap_err();
+        assert_eq!(
+            errors.0[0].kind,
+            ParseErrorKind::MapRowWidth {
+                row: 2,
+                found: 3,
+                expected: 2
+            }
+        );
+        assert_eq!(
+            errors.0[0].kind.to_string(),
+            "row 2 has 3 characters, expected 2"
+        );
+        assert!(parse(&SMALL.replace("\n10\n", "\n1\n")).is_err());
+    }
+
+    #[test]
+    fn rejects_wrong_row_count() {
+        assert_eq!(
+            kinds(&format!("{}01\n", SMALL)),
+            vec![ParseErrorKind::MapRowCount {
+                found: 3,
+                expected: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_tile_mismatches() {
+        assert_eq!(
+            kinds(&SMALL.replace("5, 9", "5")),
+            vec![ParseErrorKind::HexChanceMismatch {
+                tiles: 3,
+                deserts: 1,
+                chances: 1
+            }]
+        );
+        assert_eq!(
+            kinds(&SMALL.replace("\n10\n", "\n11\n")),
+            vec![ParseErrorKind::MapSizeIncompatability {
+                map: 4,
+                resources: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_expansion() {
+        assert_eq!(parse(SMALL).unwrap().expansion(), Expansion::Base);
+        let errors = parse(&SMALL.replace("DEFAULT", "PIRATES")).unwrap_err();
+        assert_eq!(errors.0[0].kind, ParseErrorKind::UnknownExpansion);
+        assert_eq!(errors.0[0].location, Some(Location { line: 3, column: 1 }));
+        assert_eq!(errors.0[0].token.as_deref(), Some("PIRATES"));
+    }
+
+    #[test]
+    fn cells_must_belong_to_expansion() {
+        let errors = parse(&TERRAIN.replace("SEAFARERS", "DEFAULT")).unwrap_err();
+        assert_eq!(
+            errors.0[0].kind,
+            ParseErrorKind::NotInExpansion(Expansion::Base)
+        );
+        assert_eq!(errors.0[0].token.as_deref(), Some("F"));
+        assert!(parse(&TERRAIN.replace("SEAFARERS", "KINGDOM")).is_err());
+        assert_eq!(parse(TERRAIN).unwrap().expansion(), Expansion::Seafarers);
+    }
+
+    #[test]
+    fn reports_missing_sections() {
+        assert_eq!(
+            kinds(&SMALL.replace("[CHANCES]\n5, 9\n", "")),
+            vec![ParseErrorKind::MissingSection("CHANCES")]
+        );
+    }
+
+    #[test]
+    fn round_trip_bundled_maps() {
+        // What a map declares, resources and hexes compared through their debug output
+        let declared = |board: &Board| {
+            (
+                board.expansion(),
+                format!("{:?}", board.distribution()),
+                board.chances().to_vec(),
+                format!("{:?}", board.tiles()),
+            )
+        };
+        for (name, content) in maps::BUNDLED {
+            let board = parse(content).unwrap();
+            let written = write(&board, false);
+            let reparsed = parse(&written).unwrap_or_else(|e| panic!("{}: {}", name, e));
+            assert_eq!(declared(&board), declared(&reparsed), "{} changed", name);
+            assert_eq!(written, write(&reparsed, false), "{} changed", name);
+        }
+    }
+
+    #[test]
+    fn round_trip_fixed_layout() {
+        for (name, content) in maps::BUNDLED {
+            let mut board = parse(content).unwrap();
+            board.randomize();
+            let written = write(&board, true);
+            let mut reparsed = parse(&written).unwrap_or_else(|e| panic!("{}: {}", name, e));
+            // A fixed board keeps its tiles when randomized
+            reparsed.randomize();
+            assert_eq!(written, write(&reparsed, false), "{} changed", name);
+        }
+    }
+
+    #[test]
+    fn layout_must_match_declaration() {
+        let layout = format!("{}[LAYOUT]\nWO5 WO9\nDE --\n", SMALL);
+        assert!(parse(&layout).is_ok());
+        let errors = parse(&layout.replace("WO9", "WO8")).unwrap_err();
+        assert_eq!(errors.0[0].kind, ParseErrorKind::LayoutChanceUnavailable);
+        assert_eq!(
+            errors.0[0].location,
+            Some(Location {
+                line: 13,
+                column: 5
+            })
+        );
+        assert_eq!(
+            kinds(&layout.replace("DE --", "-- DE")),
+            vec![
+                ParseErrorKind::LayoutMapMismatch,
+                ParseErrorKind::LayoutMapMismatch
+            ]
+        );
+        assert_eq!(
+            kinds(&layout.replace("WO9", "BR9")),
+            vec![ParseErrorKind::TooManyFixedTiles {
+                code: "BR",
+                resources: 0
+            }]
+        );
+        assert_eq!(
+            kinds(&layout.replace("DE --", "DE DE")),
+            vec![ParseErrorKind::LayoutMapMismatch]
+        );
+    }
+
+    #[test]
+    fn partial_layout() {
+        let layout = format!("{}[LAYOUT]\n? WO9\n? --\n", SMALL);
+        for _ in 0..10 {
+            let mut board = parse(&layout).unwrap();
+            board.randomize();
+            let written = write(&board, true);
+            assert!(
+                written.contains("\nWO5 WO9\nDE --\n") || written.contains("\nDE WO9\nWO5 --\n")
+            );
+        }
+        // Unrandomized tiles are written back as "?"
+        let written = write(&parse(&layout).unwrap(), false);
+        assert!(written.ends_with("[LAYOUT]\n? WO9\n? --\n"));
+        assert_eq!(
+            kinds(&layout.replace("? WO9", "WO9 WO9")),
+            vec![ParseErrorKind::LayoutChanceUnavailable]
+        );
+        assert_eq!(
+            kinds(&layout.replace("? WO9\n? --", "DE WO9\nDE --")),
+            vec![ParseErrorKind::TooManyFixedTiles {
+                code: "DE",
+                resources: 1
+            }]
+        );
+    }
+
+    const TERRAIN: &str = "__FOCM__
+[EXPANSION]
+SEAFARERS
+[RESOURCES]
+WO:2, OR:1, DE:1
+[CHANCES]
+5, 9, 10
+[MAP]
+3x4
+XWHX
+w1F0
+d1W0
+[FOG]
+SEA:1
+";
+
+    #[test]
+    fn terrain_cells() {
+        let board = parse(TERRAIN).unwrap();
+        let terrain: Vec<Vec<Option<Terrain>>> = board.tiles()[1..4]
+            .iter()
+            .map(|row| {
+                row[1..5]
+                    .iter()
+                    .map(|hex| hex.as_ref().map(|h| h.terrain()))
+                    .collect()
+            })
+            .collect();
+        use Terrain::*;
+        assert_eq!(
+            terrain,
+            vec![
+                vec![Some(Frame), Some(Water), Some(Harbor), Some(Frame)],
+                vec![Some(Land), Some(Land), Some(Fog), None],
+                vec![Some(Land), Some(Land), Some(Water), None],
+            ]
+        );
+        let written = write(&board, false);
+        assert!(written.contains("\nXWHX\nw1F0\nd1W0\n"));
+        assert_eq!(write(&parse(&written).unwrap(), false), written);
+    }
+
+    #[test]
+    fn fog_stack() {
+        let fogged = TERRAIN.replace("SEA:1", "WO:1\n6\n[ISLAND_BONUS]\n2");
+        let board = parse(&fogged).unwrap();
+        assert_eq!(board.fog().tiles.len(), 1);
+        assert_eq!(board.fog().chances, vec![6]);
+        assert_eq!(board.island_bonus(), 2);
+        let written = write(&board, false);
+        assert!(written.contains("\n[FOG]\nWO:1\n6\n"));
+        assert!(written.contains("\n[ISLAND_BONUS]\n2\n"));
+        assert_eq!(write(&parse(&written).unwrap(), false), written);
+        assert!(!write(&parse(TERRAIN).unwrap(), false).contains("ISLAND_BONUS"));
+    }
+
+    #[test]
+    fn fog_errors() {
+        assert_eq!(
+            kinds(&TERRAIN.replace("SEA:1", "SEA:2")),
+            vec![ParseErrorKind::FogCount { cells: 1, tiles: 2 }]
+        );
+        assert_eq!(
+            kinds(&TERRAIN.replace("\n[FOG]\nSEA:1", "")),
+            vec![ParseErrorKind::FogCount { cells: 1, tiles: 0 }]
+        );
+        assert_eq!(
+            kinds(&TERRAIN.replace("SEA:1", "WO:1")),
+            vec![ParseErrorKind::FogChanceMismatch {
+                tiles: 1,
+                chances: 0
+            }]
+        );
+        assert_eq!(
+            kinds(&TERRAIN.replace("SEA:1", "DE:1\n5")),
+            vec![ParseErrorKind::FogChanceMismatch {
+                tiles: 0,
+                chances: 1
+            }]
+        );
+        assert_eq!(
+            kinds(&format!("{}[ISLAND_BONUS]\ntwo\n", TERRAIN)),
+            vec![ParseErrorKind::IslandBonus]
+        );
+        let errors = parse(&SMALL.replace("[MAP]", "[FOG]\nSEA:1\n[MAP]")).unwrap_err();
+        assert_eq!(
+            errors.0[0].kind,
+            ParseErrorKind::NotInExpansion(Expansion::Base)
+        );
+    }
+
+    #[test]
+    fn resource_cells_are_kept_when_randomized() {
+        for _ in 0..10 {
+            let mut board = parse(TERRAIN).unwrap();
+            board.randomize();
+            let tiles = board.tiles();
+            let wood = tiles[2][1].as_ref().unwrap().resource();
+            assert_eq!(wood.code(), "WO");
+            assert!([5, 9, 10].contains(&wood.chance()));
+            assert_eq!(tiles[3][1].as_ref().unwrap().resource().code(), "DE");
+            // Water never gets a resource
+            assert_eq!(tiles[3][3].as_ref().unwrap().resource().code(), "DE");
+            // Once the layout fixes the whole tile the [MAP] cell is plain land
+            let written = write(&board, true);
+            assert_eq!(
+                write(&parse(&written).unwrap(), true),
+                written.replace("w1F0\nd1W0", "11F0\n11W0")
+            );
+        }
+    }
+
+    #[test]
+    fn fixed_cells_are_checked() {
+        let errors = parse(&TERRAIN.replace("w1F0\nd1W0", "o1F0\no1W0")).unwrap_err();
+        assert_eq!(
+            errors.0[0].kind,
+            ParseErrorKind::TooManyFixedTiles {
+                code: "OR",
+                resources: 1
+            }
+        );
+        assert_eq!(
+            errors.0[0].location,
+            Some(Location {
+                line: 12,
+                column: 1
+            })
+        );
+        assert_eq!(
+            kinds(&TERRAIN.replace("w1F0", "w1Q0")),
+            vec![ParseErrorKind::UnknownCell]
+        );
+        // A random tile in [LAYOUT], but not a cell of [MAP]
+        assert_eq!(
+            kinds(&TERRAIN.replace("w1F0", "w1?0")),
+            vec![ParseErrorKind::UnknownCell]
+        );
+        let layout = format!(
+            "{}[LAYOUT]\n-- -- -- --\nWO5 ? -- --\nDE ? -- --\n",
+            TERRAIN
+        );
+        assert!(parse(&layout).is_ok());
+        assert_eq!(
+            kinds(&layout.replace("WO5", "OR5")),
+            vec![ParseErrorKind::LayoutMapMismatch]
+        );
+        assert_eq!(
+            kinds(&layout.replace("WO5 ? -- --", "WO5 ? ? --")),
+            vec![ParseErrorKind::LayoutMapMismatch]
+        );
+    }
+}
@@ -3,9 +3,6 @@ use std::{cell::RefCell, fmt::{Display, Formatter}, rc::Rc};
 
 use super::building::Structure;
 
-/// Maximum number of hex's on the board
-pub const MAX_HEX: u32 = 64;
-
 #[derive(Clone, Copy)]
 pub struct HexVertex {
     pos: [f32; 2],
@@ -19,6 +16,12 @@ pub struct HexVertex {
     /// Ore      | 4
     /// Wheat    | 5
     /// Sheep    | 6
+    /// Water    | 7
+    /// Harbor   | 8
+    /// Frame    | 9
+    /// Fog      | 10
+    /// Gold     | 11
+    /// Next bit: Under the cursor
     hex_meta: u32,
 }
 implement_vertex!(HexVertex, pos, hex_meta);
@@ -31,22 +34,43 @@ impl HexVertex {
         }
     }
 
-    pub fn add_meta(&mut self, resource: Option<Resource>) {
-        self.hex_meta = match resource {
-            Some(Resource::Wood(n)) => 0u32 | 2 | (n as u32) << 8,
-            Some(Resource::Brick(n)) => 0u32 | 3 | (n as u32) << 8,
-            Some(Resource::Ore(n)) => 0u32 | 4 | (n as u32) << 8,
-            Some(Resource::Wheat(n)) => 0u32 | 5 | (n as u32) << 8,
-            Some(Resource::Sheep(n)) => 0u32 | 6 | (n as u32) << 8,
-            Some(Resource::Desert(_)) => 0u32 | 1,
-            // Shouldn't ever be none
-            None => 0,
+    pub fn add_meta(&mut self, hex: &Hex) {
+        self.hex_meta = match (hex.terrain(), hex.resource()) {
+            (Terrain::Land, Resource::Wood(n)) => 0u32 | 2 | (n as u32) << 8,
+            (Terrain::Land, Resource::Brick(n)) => 0u32 | 3 | (n as u32) << 8,
+            (Terrain::Land, Resource::Ore(n)) => 0u32 | 4 | (n as u32) << 8,
+            (Terrain::Land, Resource::Wheat(n)) => 0u32 | 5 | (n as u32) << 8,
+            (Terrain::Land, Resource::Sheep(n)) => 0u32 | 6 | (n as u32) << 8,
+            (Terrain::Land, Resource::Gold(n)) => 11 | (n as u32) << 8,
+            (Terrain::Land, Resource::Desert(_)) => 0u32 | 1,
+            (Terrain::Water, _) => 7,
+            (Terrain::Harbor, _) => 8,
+            (Terrain::Frame, _) => 9,
+            (Terrain::Fog, _) => 10,
         }
     }
 
+    pub fn set_hovered(&mut self, hovered: bool) {
+        self.hex_meta = (self.hex_meta & !(1 << 16)) | (hovered as u32) << 16;
+    }
+
     pub fn position(&self) -> (f32, f32) {
         self.pos.into()
     }
+
+    /// Raw bits sent to the shaders, see `hex_meta`
+    pub fn meta(&self) -> u32 {
+        self.hex_meta
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.hex_meta & 1 << 16 != 0
+    }
+
+    /// Whether the vertex is a land tile, as opposed to water, frame or fog
+    pub fn is_land(&self) -> bool {
+        matches!(self.hex_meta & 255, 1..=6 | 11)
+    }
 }
 
 impl Display for HexVertex {
@@ -55,10 +79,38 @@ impl Display for HexVertex {
     }
 }
 
+/// Kind of cell on the board. Cells without a hex (outside the playable area) are `None` in
+/// `Board::tiles`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Terrain {
+    /// Produces resources
+    Land,
+    /// Sea that ships may sail on
+    Water,
+    /// Sea with a port
+    Harbor,
+    /// Edge of the board
+    Frame,
+    /// Face down tile, revealed during exploration
+    Fog,
+}
+
+/// How much of a hex is decided by the map rather than the randomizer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fixed {
+    Nothing,
+    /// The resource is fixed, its chance is random
+    Resource,
+    /// Both the resource and chance are fixed
+    Tile,
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct Hex {
+    terrain: Terrain,
     resource: Resource,
+    fixed: Fixed,
     occupants: Option<Occupant>,
     corners: [Option<Rc<RefCell<Structure>>>; 6],
 }
@@ -67,12 +119,56 @@ pub struct Hex {
 impl Hex {
     pub fn new() -> Self {
         Hex {
+            terrain: Terrain::Land,
             resource: Resource::Desert(None),
+            fixed: Fixed::Nothing,
             occupants: None,
             corners: [None, None, None, None, None, None],
         }
     }
 
+    pub fn with_terrain(terrain: Terrain) -> Self {
+        Hex {
+            terrain,
+            ..Hex::new()
+        }
+    }
+
+    /// A land hex whose tile is decided by the map rather than the randomizer
+    pub fn fixed(resource: Resource) -> Self {
+        Hex {
+            resource,
+            fixed: Fixed::Tile,
+            ..Hex::new()
+        }
+    }
+
+    /// A land hex whose resource is decided by the map, leaving its chance to the randomizer
+    pub fn fixed_resource(resource: Resource) -> Self {
+        Hex {
+            resource: resource.clone_with_value(0),
+            fixed: Fixed::Resource,
+            ..Hex::new()
+        }
+    }
+
+    pub fn terrain(&self) -> Terrain {
+        self.terrain
+    }
+
+    pub fn is_land(&self) -> bool {
+        self.terrain == Terrain::Land
+    }
+
+    pub fn fixed_part(&self) -> Fixed {
+        self.fixed
+    }
+
+    /// Whether the whole tile is fixed
+    pub fn is_fixed(&self) -> bool {
+        self.fixed == Fixed::Tile
+    }
+
     pub fn resource(&self) -> Resource {
         self.resource
     }
@@ -88,6 +184,15 @@ impl Hex {
     }
 
     pub fn is_robbed(&self) -> bool {
-        self.occupants.is_some()
+        self.occupants == Some(Occupant::Robber)
+    }
+
+    pub fn occupant(&self) -> Option<Occupant> {
+        self.occupants
+    }
+
+    pub fn set_occupant(&mut self, occupant: Option<Occupant>) -> &mut Self {
+        self.occupants = occupant;
+        self
     }
 }
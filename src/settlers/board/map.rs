@@ -1,206 +1,385 @@
 use super::{
     building::{BuildingVertex, Structure},
-    card::Resource,
-    hex::{Hex, HexVertex, MAX_HEX},
+    card::{Occupant, Resource},
+    focm::{self, ParseErrorKind, ParseMapError, ParseMapErrors},
+    hex::{Fixed, Hex, HexVertex, Terrain},
+    picking::Pick,
+    topology::{Bounds, Edge, HexCoord, Intersection, BOARD_OFFSET, HEX_HEIGHT},
 };
-use crate::{rand::Rng, settlers::matrix::Vec3};
+use crate::rand::Rng;
+use crate::settlers::expansions::Expansion;
 use rand::seq::SliceRandom;
-use std::fs::read_to_string;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
 
-#[derive(Debug)]
-pub enum ParseMapError {
-    FileNotFound,
-    NotFocm,
-    ExpansionNotFound,
-    ResourcesNotFound,
-    ResourceParseError,
-    ChanceNotFound,
-    ChanceParseError,
-    ChanceNotSuitable,
-    HexChanceMismatch,
-    MapNotFound,
-    MapParseError,
-    MapSizeIncompatability,
+/// Face down tiles of a Seafarers map, drawn as fog hexes are discovered
+#[derive(Debug, Clone, Default)]
+pub struct FogStack {
+    /// Land tiles by resource, `None` being sea
+    pub tiles: Vec<Option<Resource>>,
+    /// Chances of the land tiles that produce
+    pub chances: Vec<u8>,
 }
 
-const BOARD_OFFSET: (f32, f32) = (5., 4.22);
+impl FogStack {
+    /// Tiles that take a chance when revealed
+    pub fn numbered_tiles(&self) -> u32 {
+        self.tiles
+            .iter()
+            .flatten()
+            .filter(|resource| !matches!(resource, Resource::Desert(_)))
+            .count() as u32
+    }
+}
 
 #[derive(Debug)]
 pub struct Board {
+    expansion: Expansion,
     // All structures, including road, settlement, city...
     buildings: Vec<Structure>,
     tiles: Vec<Vec<Option<Hex>>>,
     // Attributes of Hex tiles, used to randomize the map
     distribution: Vec<Resource>,
     chances: Vec<u8>,
+    fog: FogStack,
+    // Victory points for the first settlement on a new island
+    island_bonus: u8,
 }
 
 impl Board {
-    pub fn from_file(file: &'static str) -> Result<Self, ParseMapError> {
-        // Filter the comments and newlines from the file
-        let content = read_to_string(file).map_err(|_| ParseMapError::FileNotFound)?;
-        let lines: Vec<_> = content
-            .lines()
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.starts_with("#") || line.len() == 0 {
-                    // If the line is a comment or the line is blank, filter it
-                    None
-                } else {
-                    Some(line)
-                }
-            })
-            .collect();
-        // First line should be __FOCM__
-        if *lines.get(0).ok_or(ParseMapError::NotFocm)? != "__FOCM__" {
-            return Err(ParseMapError::NotFocm);
+    pub(super) fn new(
+        expansion: Expansion,
+        tiles: Vec<Vec<Option<Hex>>>,
+        distribution: Vec<Resource>,
+        chances: Vec<u8>,
+        fog: FogStack,
+        island_bonus: u8,
+    ) -> Self {
+        Self {
+            expansion,
+            buildings: Vec::new(),
+            tiles,
+            distribution,
+            chances,
+            fog,
+            island_bonus,
         }
-        // Find expansion
-        let expansion_pos = lines
-            .iter()
-            .position(|line| *line == "[EXPANSION]")
-            .ok_or(ParseMapError::ExpansionNotFound)?;
-        let _expansion = lines
-            .get(expansion_pos + 1)
-            .ok_or(ParseMapError::ExpansionNotFound)?;
-        // Find resources
-        let resource_pos = lines
-            .iter()
-            .position(|line| *line == "[RESOURCES]")
-            .ok_or(ParseMapError::ResourcesNotFound)?;
-        let resources = lines
-            .get(resource_pos + 1)
-            .ok_or(ParseMapError::ResourcesNotFound)?;
-        // Parse resources
-        let mut total_tiles: u32 = 0;
-        let resources: Vec<Resource> = resources
-            .split(",")
-            .map(|res| {
-                let res_split: Vec<_> = res.trim().split(":").collect();
-                if res_split.len() != 2 {
-                    // Resources should be in the format "RE:X"
-                    return Err(ParseMapError::ResourceParseError);
-                }
-                let count = res_split[1]
-                    .parse::<u8>()
-                    .map_err(|_| ParseMapError::ResourceParseError)?;
-                total_tiles += count as u32;
-                match res_split[0] {
-                    "WO" => Ok(Resource::Wood(count)),
-                    "BR" => Ok(Resource::Brick(count)),
-                    "OR" => Ok(Resource::Ore(count)),
-                    "WH" => Ok(Resource::Wheat(count)),
-                    "SH" => Ok(Resource::Sheep(count)),
-                    "DE" => Ok(Resource::Desert(Some(count))),
-                    _ => Err(ParseMapError::ResourceParseError),
-                }
-            })
-            .collect::<Result<_, _>>()?;
-        // Find chance values
-        let chance_pos = lines
-            .iter()
-            .position(|line| *line == "[CHANCES]")
-            .ok_or(ParseMapError::ChanceNotFound)?;
-        let chances = lines
-            .get(chance_pos + 1)
-            .ok_or(ParseMapError::ChanceNotFound)?;
-        // Parse chances
-        let chances: Vec<u8> = chances
-            .split(",")
-            .map(|chance| {
-                let c = chance.trim().parse::<u8>();
-                if let Some(val) = c.ok() {
-                    if val < 2 || val > 12 || val == 7 {
-                        Err(ParseMapError::ChanceNotSuitable)
-                    } else {
-                        Ok(val)
-                    }
-                } else {
-                    Err(ParseMapError::ChanceParseError)
-                }
-            })
-            .collect::<Result<_, _>>()?;
-        // Ensure total chances == total tiles (excepting the desert tiles)
-        let desert_tiles = resources
-            .iter()
-            .find(|res| {
-                if let Resource::Desert(_) = res {
-                    true
-                } else {
-                    false
-                }
-            })
-            .map(|res| {
-                if let Resource::Desert(val) = res {
-                    val.unwrap_or(0)
-                } else {
-                    0
-                }
-            })
-            .unwrap();
-        if total_tiles - desert_tiles as u32 != chances.len() as u32 || total_tiles > MAX_HEX {
-            Err(ParseMapError::HexChanceMismatch)?
+    }
+
+    pub fn from_file(file: impl AsRef<Path>) -> Result<Self, ParseMapErrors> {
+        let file = file.as_ref();
+        let reader = File::open(file).map_err(|e| ParseMapError {
+            kind: ParseErrorKind::FileNotFound(e.to_string()),
+            location: None,
+            token: Some(file.display().to_string()),
+        })?;
+        Self::from_reader(reader)
+    }
+
+    /// Parse a map from any reader, e.g. a network stream
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, ParseMapErrors> {
+        let mut content = String::new();
+        reader
+            .read_to_string(&mut content)
+            .map_err(|e| ParseMapError {
+                kind: ParseErrorKind::Unreadable(e.to_string()),
+                location: None,
+                token: None,
+            })?;
+        focm::parse(&content)
+    }
+
+    /// Write the board as .focm. With `fixed_layout` the current tiles are saved as a [LAYOUT],
+    /// so a generated board can be shared as is.
+    pub fn to_focm(&self, fixed_layout: bool) -> String {
+        focm::write(self, fixed_layout)
+    }
+
+    pub fn save(&self, file: impl AsRef<Path>, fixed_layout: bool) -> std::io::Result<()> {
+        std::fs::write(file, self.to_focm(fixed_layout))
+    }
+
+    pub fn expansion(&self) -> Expansion {
+        self.expansion
+    }
+
+    /// Play the map with another expansion's rules. Only maps of the base game fit every
+    /// expansion, `false` and nothing changes otherwise.
+    pub fn set_expansion(&mut self, expansion: Expansion) -> bool {
+        if self.expansion != Expansion::Base && self.expansion != expansion {
+            return false;
         }
-        // Find map
-        let map_pos = lines
-            .iter()
-            .position(|line| *line == "[MAP]")
-            .ok_or(ParseMapError::MapNotFound)?;
-        // Get dimensions
-        let map_dim: Vec<usize> = lines
-            .get(map_pos + 1)
-            .ok_or(ParseMapError::MapNotFound)?
-            .split("x")
-            .map(|dim| {
-                dim.parse::<usize>()
-                    .map_err(|_| ParseMapError::MapParseError)
-            })
-            .collect::<Result<_, _>>()?;
-        if map_dim.len() != 2 {
-            Err(ParseMapError::MapParseError)?
+        self.expansion = expansion;
+        true
+    }
+
+    /// Tiles of the board, surrounded by a border of empty tiles
+    pub fn tiles(&self) -> &Vec<Vec<Option<Hex>>> {
+        &self.tiles
+    }
+
+    /// Resources the randomizer distributes, as declared by [RESOURCES]
+    pub fn distribution(&self) -> &[Resource] {
+        &self.distribution
+    }
+
+    pub fn chances(&self) -> &[u8] {
+        &self.chances
+    }
+
+    /// Tiles left to reveal under the fog
+    pub fn fog(&self) -> &FogStack {
+        &self.fog
+    }
+
+    pub fn island_bonus(&self) -> u8 {
+        self.island_bonus
+    }
+
+    /// Turn a fog hex face up, drawing its tile and chance from the fog stack
+    pub fn reveal(&mut self, coord: HexCoord) -> Option<&Hex> {
+        if self.hex(coord)?.terrain() != Terrain::Fog {
+            return None;
         }
-        // We add 1 to create a border around the map
-        let mut actual_tiles = 0;
-        let mut map: Vec<Vec<Option<Hex>>> = vec![vec![None; map_dim[1] + 2]; map_dim[0] + 2];
-        for i in 1..(map_dim[0] + 1) {
-            let row = lines
-                .get(map_pos + 2 + i - 1)
-                .ok_or(ParseMapError::MapSizeIncompatability)?;
-            for (j, c) in row.chars().enumerate() {
-                match c {
-                    '0' => map[i][j + 1] = None,
-                    '1' => {
-                        map[i][j + 1] = Some(Hex::new());
-                        actual_tiles += 1;
-                    }
-                    _ => Err(ParseMapError::MapParseError)?,
-                }
+        let hex = match self.fog.tiles.pop().flatten() {
+            Some(Resource::Desert(_)) => Hex::fixed(Resource::Desert(None)),
+            Some(resource) => {
+                let chance = self.fog.chances.pop().unwrap_or(0);
+                Hex::fixed(resource.clone_with_value(chance))
             }
+            // An empty stack leaves open sea
+            None => Hex::with_terrain(Terrain::Water),
+        };
+        let cell = self.hex_mut(coord)?;
+        *cell = hex;
+        Some(cell)
+    }
+
+    /// Land hexes reachable from `coord` without crossing the sea
+    pub fn island(&self, coord: HexCoord) -> Vec<HexCoord> {
+        let mut island = Vec::new();
+        let mut frontier = vec![coord];
+        while let Some(hex) = frontier.pop() {
+            if !self.is_land(hex) || island.contains(&hex) {
+                continue;
+            }
+            island.push(hex);
+            frontier.extend(hex.neighbors());
         }
-        if actual_tiles != total_tiles {
-            Err(ParseMapError::MapSizeIncompatability)?
-        }
-        Ok(Self {
-            buildings: Vec::new(),
-            tiles: map,
-            distribution: resources,
-            chances,
+        island
+    }
+
+    pub fn hex(&self, coord: HexCoord) -> Option<&Hex> {
+        let (row, col) = coord.index()?;
+        self.tiles.get(row)?.get(col)?.as_ref()
+    }
+
+    pub fn hex_mut(&mut self, coord: HexCoord) -> Option<&mut Hex> {
+        let (row, col) = coord.index()?;
+        self.tiles.get_mut(row)?.get_mut(col)?.as_mut()
+    }
+
+    /// Every hex of the board with its coordinate
+    pub fn hexes(&self) -> impl Iterator<Item = (HexCoord, &Hex)> {
+        self.tiles.iter().enumerate().flat_map(|(row, tiles)| {
+            tiles.iter().enumerate().filter_map(move |(col, hex)| {
+                hex.as_ref()
+                    .map(|hex| (HexCoord::new(row as i32, col as i32), hex))
+            })
         })
     }
 
+    /// World rectangle covering every hex of the board, `None` without hexes
+    pub fn extents(&self) -> Option<Bounds> {
+        let (half_width, half_height) = (BOARD_OFFSET.0 / 2., HEX_HEIGHT / 2.);
+        Bounds::around(self.hexes().flat_map(|(coord, _)| {
+            let (x, y) = coord.center();
+            [
+                (x - half_width, y - half_height),
+                (x + half_width, y + half_height),
+            ]
+        }))
+    }
+
+    pub fn is_land(&self, coord: HexCoord) -> bool {
+        self.hex(coord).is_some_and(|hex| hex.is_land())
+    }
+
+    /// Intersections touching at least one land hex, where settlements may be built
+    pub fn intersections(&self) -> Vec<Intersection> {
+        let mut intersections: Vec<Intersection> = self
+            .hexes()
+            .filter(|(_, hex)| hex.is_land())
+            .flat_map(|(coord, _)| coord.corners())
+            .collect();
+        intersections.sort();
+        intersections.dedup();
+        intersections
+    }
+
+    /// Edges touching at least one land hex, where roads may be built
+    pub fn edges(&self) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = self
+            .hexes()
+            .filter(|(_, hex)| hex.is_land())
+            .flat_map(|(coord, _)| coord.edges())
+            .collect();
+        edges.sort();
+        edges.dedup();
+        edges
+    }
+
+    pub fn buildings(&self) -> &[Structure] {
+        &self.buildings
+    }
+
+    /// Settlement or city on the intersection
+    pub fn building_at(&self, intersection: Intersection) -> Option<&Structure> {
+        self.buildings.iter().find(|structure| {
+            structure.is_building() && structure.intersection() == Some(intersection)
+        })
+    }
+
+    /// Knight on the intersection
+    pub fn knight_at(&self, intersection: Intersection) -> Option<&Structure> {
+        self.buildings.iter().find(|structure| {
+            matches!(structure, Structure::Knight { .. })
+                && structure.intersection() == Some(intersection)
+        })
+    }
+
+    /// Road or ship on the edge
+    pub fn road_at(&self, edge: Edge) -> Option<&Structure> {
+        self.buildings
+            .iter()
+            .find(|structure| structure.edge() == Some(edge))
+    }
+
+    /// Place a structure, a city replaces the settlement it is built on and a knight the
+    /// knight it was promoted or activated from.
+    /// Whether the move is legal is up to the rules.
+    pub fn build(&mut self, structure: Structure) {
+        let replaces = |built: &Structure| match (built, structure) {
+            (
+                Structure::Settlement { intersection, .. },
+                Structure::City {
+                    intersection: at, ..
+                },
+            )
+            | (
+                Structure::Knight { intersection, .. },
+                Structure::Knight {
+                    intersection: at, ..
+                },
+            ) => *intersection == at,
+            _ => false,
+        };
+        self.buildings.retain(|built| !replaces(built));
+        self.buildings.push(structure);
+    }
+
+    /// Take a structure off the board, e.g. a ship being moved
+    pub fn remove(&mut self, structure: &Structure) -> bool {
+        let count = self.buildings.len();
+        self.buildings.retain(|built| built != structure);
+        self.buildings.len() != count
+    }
+
+    /// Hex the occupant stands on
+    pub fn occupant(&self, occupant: Occupant) -> Option<HexCoord> {
+        self.hexes()
+            .find(|(_, hex)| hex.occupant() == Some(occupant))
+            .map(|(coord, _)| coord)
+    }
+
+    pub fn move_occupant(&mut self, occupant: Occupant, to: HexCoord) {
+        if let Some(from) = self.occupant(occupant) {
+            self.hex_mut(from).unwrap().set_occupant(None);
+        }
+        if let Some(hex) = self.hex_mut(to) {
+            hex.set_occupant(Some(occupant));
+        }
+    }
+
+    pub fn robber(&self) -> Option<HexCoord> {
+        self.occupant(Occupant::Robber)
+    }
+
+    pub fn move_robber(&mut self, to: HexCoord) {
+        self.move_occupant(Occupant::Robber, to);
+    }
+
+    pub fn pirate(&self) -> Option<HexCoord> {
+        self.occupant(Occupant::Pirate)
+    }
+
+    pub fn move_pirate(&mut self, to: HexCoord) {
+        self.move_occupant(Occupant::Pirate, to);
+    }
+
     pub fn randomize(&mut self) {
-        let mut rng = rand::thread_rng();
+        self.randomize_with(&mut rand::thread_rng());
+    }
+
+    /// Shuffle the resources and chances with `rng`, a seeded one gives the same board again
+    pub fn randomize_with(&mut self, rng: &mut impl Rng) {
         let mut distribution = self.distribution.clone();
         let mut chances = self.chances.clone();
-        distribution.shuffle(&mut rng);
-        chances.shuffle(&mut rng);
+        // Tiles and resources fixed by the map are taken out of the distribution
+        for hex in self.tiles.iter().flatten().flatten() {
+            if !hex.is_land() || hex.fixed_part() == Fixed::Nothing {
+                continue;
+            }
+            let resource = hex.resource();
+            if let Some(index) = distribution
+                .iter()
+                .position(|res| res.code() == resource.code())
+            {
+                match &mut distribution[index] {
+                    Resource::Desert(Some(amount_left))
+                    | Resource::Wood(amount_left)
+                    | Resource::Brick(amount_left)
+                    | Resource::Ore(amount_left)
+                    | Resource::Wheat(amount_left)
+                    | Resource::Sheep(amount_left)
+                    | Resource::Gold(amount_left) => {
+                        *amount_left -= 1;
+                        if *amount_left == 0 {
+                            distribution.remove(index);
+                        }
+                    }
+                    Resource::Desert(None) => (),
+                }
+            }
+            if let Some(index) = chances.iter().position(|c| *c == resource.chance()) {
+                if hex.is_fixed() && !matches!(resource, Resource::Desert(_)) {
+                    chances.remove(index);
+                }
+            }
+        }
+        distribution.shuffle(rng);
+        chances.shuffle(rng);
+        self.fog.tiles.shuffle(rng);
+        self.fog.chances.shuffle(rng);
+        // Hexes with a fixed resource only need a chance
+        for hex in self.tiles.iter_mut().flatten().flatten() {
+            let resource = hex.resource();
+            if hex.fixed_part() == Fixed::Resource && !matches!(resource, Resource::Desert(_)) {
+                let chance = chances
+                    .pop()
+                    .expect("Chances and total tiles should be equal");
+                hex.set_resource(resource.clone_with_value(chance));
+            }
+        }
 
         let mut random_tiles = Vec::new();
 
         while distribution.len() > 0 {
             // Get random tile
             let resource_index: usize = rng.gen_range(0..distribution.len());
-            let resource = distribution[resource_index].clone();
+            let resource = distribution[resource_index];
             match &mut distribution[resource_index] {
                 Resource::Desert(amount_left) => {
                     // Option specific for Desert
@@ -216,7 +395,8 @@ impl Board {
                 | Resource::Brick(amount_left)
                 | Resource::Ore(amount_left)
                 | Resource::Wheat(amount_left)
-                | Resource::Sheep(amount_left) => {
+                | Resource::Sheep(amount_left)
+                | Resource::Gold(amount_left) => {
                     // Add Tile and random number to output
                     random_tiles.push(
                         resource.clone_with_value(
@@ -236,64 +416,161 @@ impl Board {
         // Add move each random tile in random_tiles to the map
         for j in 0..self.tiles.len() {
             for i in 0..self.tiles[0].len() {
-                if let Some(hex) = &mut self.tiles[j][i] {
-                    hex.set_resource(random_tiles.pop().unwrap());
+                match &mut self.tiles[j][i] {
+                    Some(hex) if hex.is_land() && hex.fixed_part() == Fixed::Nothing => {
+                        hex.set_resource(random_tiles.pop().unwrap());
+                    }
+                    _ => (),
                 }
             }
         }
         // println!("{:?}", self.tiles);
     }
 
-    pub fn hex_buffers(&self) -> Vec<HexVertex> {
+    pub fn hex_buffers(&self, hovered: Option<Pick>) -> Vec<HexVertex> {
         let mut vertices = Vec::new();
-        let (width, height) = (self.tiles[0].len(), self.tiles.len());
-        for j in 0..height {
-            for i in 0..width {
-                if let Some(hex) = &self.tiles[j][i] {
-                    // Push a single point, the center of the hexagon, to the buffer
-                    // The gpu will transform this point into a hexagon in the geometry shader
-                    let offset = if j % 2 == 0 { BOARD_OFFSET.0 / 2. } else { 0. };
-                    let mut vertex = HexVertex::new(
-                        BOARD_OFFSET.0 * i as f32 + offset,
-                        BOARD_OFFSET.1 * j as f32,
-                    );
-                    vertex.add_meta(Some(hex.resource()));
-                    vertices.push(vertex);
-                } else {
-                    // Water
-                }
-            }
+        for (coord, hex) in self.hexes() {
+            // Push a single point, the center of the hexagon, to the buffer
+            // The gpu will transform this point into a hexagon in the geometry shader
+            let (x, y) = coord.center();
+            let mut vertex = HexVertex::new(x, y);
+            vertex.add_meta(hex);
+            vertex.set_hovered(hovered == Some(Pick::Hex(coord)));
+            vertices.push(vertex);
         }
         vertices
     }
 
-    pub fn building_buffers(&self) -> Vec<BuildingVertex> {
+    /// Structures on the board, then `ghosts` previewing where one could be built
+    pub fn building_buffers(
+        &self,
+        hovered: Option<Pick>,
+        ghosts: &[Structure],
+    ) -> Vec<BuildingVertex> {
         let mut vertices: Vec<BuildingVertex> = Vec::new();
-        for structure in self.buildings.iter() {
-            match structure {
-                Structure::Road { position } => {
-                    vertices.push(BuildingVertex::new(position.x(), position.y()))
-                }
-                Structure::Settlement { position, .. } => {
-                    let settle = BuildingVertex::new(position.x(), position.y());
-                    vertices.push(settle);
-                }
-                Structure::City { position, .. } => {
-                    let city = BuildingVertex::new(position.x(), position.y());
-                    vertices.push(city);
-                }
-            }
+        let on_board = self.buildings.iter().map(|structure| (structure, false));
+        for (structure, ghost) in on_board.chain(ghosts.iter().map(|ghost| (ghost, true))) {
+            let (x, y) = structure.position();
+            let mut vertex = BuildingVertex::new(x, y);
+            vertex.set_structure(structure);
+            vertex.set_color(structure.owner());
+            vertex.set_hovered(hovered.is_some_and(|pick| pick.covers(structure)));
+            vertex.set_ghost(ghost);
+            vertices.push(vertex);
         }
         vertices
     }
 }
 
+impl FromStr for Board {
+    type Err = ParseMapErrors;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        focm::parse(content)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    // use super::*;
+    use super::*;
+    use crate::settlers::board::maps;
+
+    #[test]
+    fn from_str() {
+        for (_, content) in maps::BUNDLED {
+            assert!(content.parse::<Board>().is_ok());
+        }
+    }
+
+    #[test]
+    fn from_reader() {
+        assert!(Board::from_reader(maps::DEFAULT.as_bytes()).is_ok());
+        assert!(Board::from_reader(&b"__FOCM__"[..]).is_err());
+    }
+
+    #[test]
+    fn missing_file() {
+        let errors = Board::from_file("does/not/exist.focm").unwrap_err();
+        assert!(matches!(
+            errors.errors()[0].kind,
+            ParseErrorKind::FileNotFound(_)
+        ));
+    }
+
+    const FOGGY: &str = "__FOCM__
+[EXPANSION]
+SEAFARERS
+[RESOURCES]
+WO:1
+[CHANCES]
+8
+[MAP]
+3x3
+WWW
+1FF
+WWW
+[FOG]
+OR:1, SEA:1
+4
+";
+
+    #[test]
+    fn reveal_fog() {
+        let mut board: Board = FOGGY.parse().unwrap();
+        let (first, second) = (HexCoord::new(2, 2), HexCoord::new(2, 3));
+        // The stack is drawn from the top, the last tile written
+        assert_eq!(board.reveal(first).unwrap().terrain(), Terrain::Water);
+        let ore = board.reveal(second).unwrap();
+        assert_eq!(ore.resource().code(), "OR");
+        assert_eq!(ore.resource().chance(), 4);
+        assert!(board.fog().tiles.is_empty());
+        assert!(board.reveal(second).is_none());
+        assert_eq!(board.island(second), vec![second]);
+        assert!(board.island(first).is_empty());
+    }
+
     #[test]
     fn default_random_generation() {}
 
+    #[test]
+    fn seeded_randomize_repeats() {
+        use rand::{rngs::StdRng, SeedableRng};
+        let layout = |seed: u64| {
+            let mut board: Board = maps::DEFAULT.parse().unwrap();
+            board.randomize_with(&mut StdRng::seed_from_u64(seed));
+            board
+                .hexes()
+                .map(|(_, hex)| format!("{:?}", hex.resource()))
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(layout(7), layout(7));
+        assert_ne!(layout(7), layout(8));
+    }
+
+    #[test]
+    fn set_expansion() {
+        let mut board: Board = maps::DEFAULT.parse().unwrap();
+        assert!(board.set_expansion(Expansion::Seafarers));
+        assert_eq!(board.expansion(), Expansion::Seafarers);
+        let mut foggy: Board = FOGGY.parse().unwrap();
+        assert!(!foggy.set_expansion(Expansion::CitiesAndKnights));
+        assert_eq!(foggy.expansion(), Expansion::Seafarers);
+    }
+
+    #[test]
+    fn extents() {
+        let board: Board = FOGGY.parse().unwrap();
+        let bounds = board.extents().unwrap();
+        // Three rows of three hexes, the middle one shifted half a hex to the right
+        assert_eq!((bounds.min.0, bounds.max.0), (2.5, 20.));
+        let (bottom, top) = (
+            HexCoord::new(1, 1).center().1,
+            HexCoord::new(3, 1).center().1,
+        );
+        assert!((bounds.min.1 - (bottom - HEX_HEIGHT / 2.)).abs() < 1e-4);
+        assert!((bounds.max.1 - (top + HEX_HEIGHT / 2.)).abs() < 1e-4);
+    }
+
     #[test]
     fn check_output() {
         // println!("{:?}", Map::parse_map("src/settlers/board/maps/default.focm"));
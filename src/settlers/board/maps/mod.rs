@@ -0,0 +1,23 @@
+//! Maps bundled into the binary, so the game does not depend on the working directory.
+
+pub const DEFAULT: &str = include_str!("default.focm");
+pub const CUSTOM: &str = include_str!("custom.focm");
+pub const CHUNGUS: &str = include_str!("chungus.focm");
+/// Board of the 5-6 player extension
+pub const SIX: &str = include_str!("six.focm");
+
+/// Every bundled map with the name it is looked up by
+pub const BUNDLED: [(&str, &str); 4] = [
+    ("default", DEFAULT),
+    ("custom", CUSTOM),
+    ("chungus", CHUNGUS),
+    ("six", SIX),
+];
+
+/// Find a bundled map by name, e.g. "default"
+pub fn bundled(name: &str) -> Option<&'static str> {
+    BUNDLED
+        .iter()
+        .find(|(map_name, _)| *map_name == name)
+        .map(|(_, content)| *content)
+}
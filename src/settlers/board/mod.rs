@@ -1,5 +1,10 @@
 pub mod background;
 pub mod building;
 pub mod card;
+pub mod draft;
+pub mod focm;
 pub mod hex;
 pub mod map;
+pub mod maps;
+pub mod picking;
+pub mod topology;
@@ -0,0 +1,207 @@
+//! Screen to world picking: which hex, intersection or edge sits under the cursor.
+//!
+//! The cursor is mapped back through the inverse of the matrix the board is drawn with and
+//! intersected with the board plane (z = 0), so everything here is plain math.
+
+use super::{
+    building::Structure,
+    map::Board,
+    topology::{Edge, HexCoord, Intersection},
+};
+use crate::settlers::matrix::Mat4;
+
+/// How close, in world units, the cursor has to be to an intersection or the middle of an edge
+/// to pick it. Hexes are 5 units wide, and a corner is 1.4 from the middle of its sides.
+pub const PICK_TOLERANCE: f32 = 1.;
+
+/// What is under the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pick {
+    Hex(HexCoord),
+    Intersection(Intersection),
+    Edge(Edge),
+}
+
+impl Pick {
+    /// Whether the structure sits on the picked intersection or edge
+    pub fn covers(&self, structure: &Structure) -> bool {
+        match self {
+            Pick::Hex(_) => false,
+            Pick::Intersection(intersection) => structure.intersection() == Some(*intersection),
+            Pick::Edge(edge) => structure.edge() == Some(*edge),
+        }
+    }
+}
+
+pub struct Picker {
+    inverse: Mat4,
+    window: (f32, f32),
+}
+
+impl Picker {
+    /// Picker for a board drawn with `mvp` in a window of `width` by `height` pixels.
+    /// `None` if the matrix can't be inverted.
+    pub fn new(mvp: &Mat4, width: u32, height: u32) -> Option<Self> {
+        Some(Picker {
+            inverse: mvp.inverse()?,
+            window: (width as f32, height as f32),
+        })
+    }
+
+    /// World position on the board plane under the cursor, in window pixels from the top left
+    pub fn world(&self, cursor_x: f32, cursor_y: f32) -> Option<(f32, f32)> {
+        // Window pixels to normalized device coordinates, y points up
+        let x = 2. * cursor_x / self.window.0 - 1.;
+        let y = 1. - 2. * cursor_y / self.window.1;
+        // Cast a ray from the near to the far plane and find where it crosses z = 0. Staying in
+        // homogeneous coordinates until the end keeps points at infinity out of the way.
+        let near = self.inverse.transform([x, y, -1., 1.]);
+        let far = self.inverse.transform([x, y, 1., 1.]);
+        let dz = near[2] - far[2];
+        if dz.abs() < f32::EPSILON {
+            return None;
+        }
+        let t = near[2] / dz;
+        let [x, y, _, w] = [0, 1, 2, 3].map(|i| near[i] + t * (far[i] - near[i]));
+        if w.abs() < f32::EPSILON {
+            return None;
+        }
+        Some((x / w, y / w))
+    }
+
+    /// Intersection, edge or hex of the board under the cursor, in that order of priority
+    pub fn pick(&self, board: &Board, cursor_x: f32, cursor_y: f32) -> Option<Pick> {
+        let (x, y) = self.world(cursor_x, cursor_y)?;
+        pick_at(board, x, y, PICK_TOLERANCE)
+    }
+}
+
+/// Intersection or edge within `tolerance` of the world position, otherwise the hex containing
+/// it. Only things touching a hex of the board are picked.
+pub fn pick_at(board: &Board, x: f32, y: f32, tolerance: f32) -> Option<Pick> {
+    let hex = HexCoord::at(x, y);
+    let on_board = |hexes: &[HexCoord]| hexes.iter().any(|hex| board.hex(*hex).is_some());
+    let distance = |(px, py): (f32, f32)| ((px - x).powi(2) + (py - y).powi(2)).sqrt();
+    // The closest corner and side of a point are always those of the hex it is in
+    let intersection = hex
+        .corners()
+        .into_iter()
+        .filter(|i| on_board(&i.hexes()))
+        .map(|i| (i, distance(i.position())))
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    if let Some((intersection, d)) = intersection {
+        if d <= tolerance {
+            return Some(Pick::Intersection(intersection));
+        }
+    }
+    let edge = hex
+        .edges()
+        .into_iter()
+        .filter(|e| on_board(&e.hexes()))
+        .map(|e| (e, distance(e.position())))
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    if let Some((edge, d)) = edge {
+        if d <= tolerance {
+            return Some(Pick::Edge(edge));
+        }
+    }
+    board.hex(hex).map(|_| Pick::Hex(hex))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settlers::board::maps;
+    use crate::settlers::camera::{Camera, ViewMode};
+
+    fn board() -> Board {
+        maps::DEFAULT.parse().unwrap()
+    }
+
+    /// Matrices the board is drawn with, looking at the middle of the default map, flat and
+    /// tilted, then turned
+    fn views() -> Vec<Mat4> {
+        let mut views = Vec::new();
+        for rotation in [0., 1., 4.] {
+            for mode in [ViewMode::Flat, ViewMode::Tilted] {
+                let mut camera = Camera::new(15., 12.);
+                camera.set_viewport(800, 600);
+                camera.set_mode(mode);
+                camera.set_rotation(rotation);
+                views.push(camera.mvp());
+            }
+        }
+        views
+    }
+
+    /// Window pixel a world position is drawn at
+    fn screen(mvp: &Mat4, (x, y): (f32, f32)) -> (f32, f32) {
+        let [x, y, _, w] = mvp.transform([x, y, 0., 1.]);
+        ((x / w + 1.) * 400., (1. - y / w) * 300.)
+    }
+
+    #[test]
+    fn cursor_maps_back_to_world() {
+        for mvp in views() {
+            let picker = Picker::new(&mvp, 800, 600).unwrap();
+            for point in [(0., 0.), (12.5, 8.44), (-3., 20.)] {
+                let (cx, cy) = screen(&mvp, point);
+                let (x, y) = picker.world(cx, cy).unwrap();
+                assert!((x - point.0).abs() < 1e-2 && (y - point.1).abs() < 1e-2);
+            }
+        }
+    }
+
+    #[test]
+    fn picks_under_cursor() {
+        let board = board();
+        for mvp in views() {
+            let picker = Picker::new(&mvp, 800, 600).unwrap();
+            let hex = HexCoord::new(3, 3);
+            let (x, y) = screen(&mvp, hex.center());
+            assert_eq!(picker.pick(&board, x, y), Some(Pick::Hex(hex)));
+            let corner = hex.corners()[2];
+            let (x, y) = screen(&mvp, corner.position());
+            assert_eq!(picker.pick(&board, x, y), Some(Pick::Intersection(corner)));
+            let side = hex.edges()[4];
+            let (x, y) = screen(&mvp, side.position());
+            assert_eq!(picker.pick(&board, x, y), Some(Pick::Edge(side)));
+        }
+    }
+
+    #[test]
+    fn tolerance_and_board_bounds() {
+        let board = board();
+        let hex = HexCoord::new(3, 3);
+        let (x, y) = hex.corners()[0].position();
+        // Just outside the tolerance of the corner, but still inside the hex
+        assert_eq!(
+            pick_at(&board, x, y - 1.2, PICK_TOLERANCE),
+            Some(Pick::Hex(hex))
+        );
+        assert_eq!(
+            pick_at(&board, x, y - 0.5, PICK_TOLERANCE),
+            Some(Pick::Intersection(hex.corners()[0]))
+        );
+        // Far off the board nothing is picked
+        assert_eq!(pick_at(&board, -100., -100., PICK_TOLERANCE), None);
+    }
+
+    #[test]
+    fn covers_structures() {
+        let hex = HexCoord::new(3, 3);
+        let city = Structure::City {
+            intersection: hex.corners()[1],
+            owner: 0,
+        };
+        let road = Structure::Road {
+            edge: hex.edges()[1],
+            owner: 0,
+        };
+        assert!(Pick::Intersection(hex.corners()[1]).covers(&city));
+        assert!(!Pick::Intersection(hex.corners()[2]).covers(&city));
+        assert!(Pick::Edge(hex.edges()[1]).covers(&road));
+        assert!(!Pick::Edge(hex.edges()[1]).covers(&city));
+        assert!(!Pick::Hex(hex).covers(&city));
+    }
+}
@@ -0,0 +1,416 @@
+//! Coordinates of hexes, intersections (where settlements go) and edges (where roads go).
+//!
+//! Hexes are addressed by their (row, column) in `Board::tiles`, rows grow upward and even
+//! rows are drawn half a hex to the right. Every intersection is the top or bottom corner of
+//! exactly one hex, and every edge is the west, north west or north east side of exactly one
+//! hex, which gives each of them a single canonical coordinate.
+
+/// Distance between hex centers: (columns, rows)
+pub const BOARD_OFFSET: (f32, f32) = (5., 4.22);
+/// Height of a hex, from its bottom corner to its top corner
+pub const HEX_HEIGHT: f32 = BOARD_OFFSET.1 * 4. / 3.;
+
+/// Neighbouring direction of a hex, counter clockwise from east
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 6] = [
+        Direction::East,
+        Direction::NorthEast,
+        Direction::NorthWest,
+        Direction::West,
+        Direction::SouthWest,
+        Direction::SouthEast,
+    ];
+
+    pub fn opposite(&self) -> Direction {
+        Direction::ALL[(*self as usize + 3) % 6]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HexCoord {
+    pub row: i32,
+    pub col: i32,
+}
+
+impl HexCoord {
+    pub const fn new(row: i32, col: i32) -> Self {
+        HexCoord { row, col }
+    }
+
+    /// Even rows are shifted half a hex to the right
+    fn shifted(&self) -> bool {
+        self.row.rem_euclid(2) == 0
+    }
+
+    pub fn neighbor(&self, direction: Direction) -> HexCoord {
+        // Column of the diagonal neighbours to the left and right
+        let (left, right) = if self.shifted() {
+            (self.col, self.col + 1)
+        } else {
+            (self.col - 1, self.col)
+        };
+        match direction {
+            Direction::East => HexCoord::new(self.row, self.col + 1),
+            Direction::West => HexCoord::new(self.row, self.col - 1),
+            Direction::NorthEast => HexCoord::new(self.row + 1, right),
+            Direction::NorthWest => HexCoord::new(self.row + 1, left),
+            Direction::SouthEast => HexCoord::new(self.row - 1, right),
+            Direction::SouthWest => HexCoord::new(self.row - 1, left),
+        }
+    }
+
+    pub fn neighbors(&self) -> [HexCoord; 6] {
+        Direction::ALL.map(|direction| self.neighbor(direction))
+    }
+
+    /// Corners counter clockwise from the top
+    pub fn corners(&self) -> [Intersection; 6] {
+        [
+            Intersection::top(*self),
+            self.neighbor(Direction::NorthWest).bottom_corner(),
+            self.neighbor(Direction::SouthWest).top_corner(),
+            Intersection::bottom(*self),
+            self.neighbor(Direction::SouthEast).top_corner(),
+            self.neighbor(Direction::NorthEast).bottom_corner(),
+        ]
+    }
+
+    pub fn edges(&self) -> [Edge; 6] {
+        Direction::ALL.map(|direction| Edge::between(*self, direction))
+    }
+
+    fn top_corner(&self) -> Intersection {
+        Intersection::top(*self)
+    }
+
+    fn bottom_corner(&self) -> Intersection {
+        Intersection::bottom(*self)
+    }
+
+    /// World position of the center of the hex
+    pub fn center(&self) -> (f32, f32) {
+        let offset = if self.shifted() {
+            BOARD_OFFSET.0 / 2.
+        } else {
+            0.
+        };
+        (
+            BOARD_OFFSET.0 * self.col as f32 + offset,
+            BOARD_OFFSET.1 * self.row as f32,
+        )
+    }
+
+    /// Tile indices in `Board::tiles`, if the coordinate isn't negative
+    pub fn index(&self) -> Option<(usize, usize)> {
+        Some((
+            usize::try_from(self.row).ok()?,
+            usize::try_from(self.col).ok()?,
+        ))
+    }
+
+    /// Hex whose area contains the world position
+    pub fn at(x: f32, y: f32) -> HexCoord {
+        // Closest center among the hexes of the nearest rows
+        let row = (y / BOARD_OFFSET.1).floor() as i32;
+        let mut best = HexCoord::new(row, 0);
+        let mut best_distance = f32::MAX;
+        for row in [row - 1, row, row + 1, row + 2] {
+            let offset = if row.rem_euclid(2) == 0 { 0.5 } else { 0. };
+            let col = (x / BOARD_OFFSET.0 - offset).round() as i32;
+            for col in [col - 1, col, col + 1] {
+                let hex = HexCoord::new(row, col);
+                let (cx, cy) = hex.center();
+                let distance = (cx - x).powi(2) + (cy - y).powi(2);
+                if distance < best_distance {
+                    best = hex;
+                    best_distance = distance;
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Corner shared by up to three hexes, where settlements and cities are built
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Intersection {
+    pub hex: HexCoord,
+    /// Top or bottom corner of `hex`
+    pub top: bool,
+}
+
+impl Intersection {
+    pub const fn top(hex: HexCoord) -> Self {
+        Intersection { hex, top: true }
+    }
+
+    pub const fn bottom(hex: HexCoord) -> Self {
+        Intersection { hex, top: false }
+    }
+
+    /// The three hexes meeting at the intersection
+    pub fn hexes(&self) -> [HexCoord; 3] {
+        if self.top {
+            [
+                self.hex,
+                self.hex.neighbor(Direction::NorthWest),
+                self.hex.neighbor(Direction::NorthEast),
+            ]
+        } else {
+            [
+                self.hex,
+                self.hex.neighbor(Direction::SouthWest),
+                self.hex.neighbor(Direction::SouthEast),
+            ]
+        }
+    }
+
+    /// The three edges leading away from the intersection
+    pub fn edges(&self) -> [Edge; 3] {
+        if self.top {
+            let north_west = self.hex.neighbor(Direction::NorthWest);
+            [
+                Edge::between(self.hex, Direction::NorthWest),
+                Edge::between(self.hex, Direction::NorthEast),
+                Edge::between(north_west, Direction::East),
+            ]
+        } else {
+            let south_west = self.hex.neighbor(Direction::SouthWest);
+            [
+                Edge::between(self.hex, Direction::SouthWest),
+                Edge::between(self.hex, Direction::SouthEast),
+                Edge::between(south_west, Direction::East),
+            ]
+        }
+    }
+
+    /// Intersections one edge away
+    pub fn neighbors(&self) -> [Intersection; 3] {
+        self.edges().map(|edge| edge.other_end(self))
+    }
+
+    pub fn position(&self) -> (f32, f32) {
+        let (x, y) = self.hex.center();
+        if self.top {
+            (x, y + HEX_HEIGHT / 2.)
+        } else {
+            (x, y - HEX_HEIGHT / 2.)
+        }
+    }
+}
+
+/// Side shared by two hexes, where roads and ships are built
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Edge {
+    pub hex: HexCoord,
+    /// Always `West`, `NorthWest` or `NorthEast` of `hex`
+    pub side: Direction,
+}
+
+impl Edge {
+    /// Edge between `hex` and its neighbour in `direction`
+    pub fn between(hex: HexCoord, direction: Direction) -> Self {
+        match direction {
+            Direction::West | Direction::NorthWest | Direction::NorthEast => Edge {
+                hex,
+                side: direction,
+            },
+            _ => Edge {
+                hex: hex.neighbor(direction),
+                side: direction.opposite(),
+            },
+        }
+    }
+
+    /// The two hexes on either side
+    pub fn hexes(&self) -> [HexCoord; 2] {
+        [self.hex, self.hex.neighbor(self.side)]
+    }
+
+    /// The two intersections at the ends of the edge
+    pub fn ends(&self) -> [Intersection; 2] {
+        let corners = self.hex.corners();
+        match self.side {
+            Direction::NorthEast => [corners[0], corners[5]],
+            Direction::NorthWest => [corners[0], corners[1]],
+            _ => [corners[1], corners[2]],
+        }
+    }
+
+    pub fn other_end(&self, end: &Intersection) -> Intersection {
+        let [a, b] = self.ends();
+        if a == *end {
+            b
+        } else {
+            a
+        }
+    }
+
+    /// The four edges sharing an end with this one
+    pub fn neighbors(&self) -> Vec<Edge> {
+        self.ends()
+            .iter()
+            .flat_map(|end| end.edges())
+            .filter(|edge| edge != self)
+            .collect()
+    }
+
+    /// Midpoint of the edge
+    pub fn position(&self) -> (f32, f32) {
+        let [(ax, ay), (bx, by)] = self.ends().map(|end| end.position());
+        ((ax + bx) / 2., (ay + by) / 2.)
+    }
+
+    /// Angle of the edge in radians, one of three orientations
+    pub fn angle(&self) -> f32 {
+        let [(ax, ay), (bx, by)] = self.ends().map(|end| end.position());
+        (by - ay).atan2(bx - ax)
+    }
+}
+
+/// Rectangle of world positions, e.g. covering the board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: (f32, f32),
+    pub max: (f32, f32),
+}
+
+impl Bounds {
+    /// Smallest rectangle holding every point, `None` without points
+    pub fn around(points: impl IntoIterator<Item = (f32, f32)>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, (x, y)| {
+            let Bounds { min, max } = bounds.unwrap_or(Bounds {
+                min: (x, y),
+                max: (x, y),
+            });
+            Some(Bounds {
+                min: (min.0.min(x), min.1.min(y)),
+                max: (max.0.max(x), max.1.max(y)),
+            })
+        })
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max.0 - self.min.0
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max.1 - self.min.1
+    }
+
+    pub fn center(&self) -> (f32, f32) {
+        (
+            (self.min.0 + self.max.0) / 2.,
+            (self.min.1 + self.max.1) / 2.,
+        )
+    }
+
+    /// Closest position inside the rectangle
+    pub fn clamp(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        (
+            x.clamp(self.min.0, self.max.0),
+            y.clamp(self.min.1, self.max.1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-4 && (a.1 - b.1).abs() < 1e-4
+    }
+
+    #[test]
+    fn neighbors_are_symmetric() {
+        for hex in [
+            HexCoord::new(2, 3),
+            HexCoord::new(3, 3),
+            HexCoord::new(-1, 0),
+        ] {
+            for direction in Direction::ALL {
+                let neighbor = hex.neighbor(direction);
+                assert_eq!(neighbor.neighbor(direction.opposite()), hex);
+            }
+        }
+    }
+
+    #[test]
+    fn corners_are_shared() {
+        let hex = HexCoord::new(2, 2);
+        for (i, corner) in hex.corners().iter().enumerate() {
+            assert!(corner.hexes().contains(&hex), "corner {}", i);
+            // Every hex meeting at the corner agrees on its position
+            for other in corner.hexes() {
+                assert!(other.corners().contains(corner));
+            }
+        }
+        let (x, y) = hex.center();
+        let (w, h) = (BOARD_OFFSET.0, HEX_HEIGHT);
+        let expected = [
+            (0., h / 2.),
+            (-w / 2., h / 4.),
+            (-w / 2., -h / 4.),
+            (0., -h / 2.),
+            (w / 2., -h / 4.),
+            (w / 2., h / 4.),
+        ];
+        for (corner, (dx, dy)) in hex.corners().iter().zip(expected) {
+            assert!(close(corner.position(), (x + dx, y + dy)));
+        }
+    }
+
+    #[test]
+    fn edges_connect_corners() {
+        let hex = HexCoord::new(3, 1);
+        for edge in hex.edges() {
+            assert!(edge.hexes().contains(&hex));
+            for end in edge.ends() {
+                assert!(end.edges().contains(&edge));
+            }
+            assert_eq!(edge.neighbors().len(), 4);
+        }
+        for corner in hex.corners() {
+            for neighbor in corner.neighbors() {
+                assert!(neighbor.neighbors().contains(&corner));
+                let (ax, ay) = corner.position();
+                let (bx, by) = neighbor.position();
+                let length = ((ax - bx).powi(2) + (ay - by).powi(2)).sqrt();
+                assert!(length > 2. && length < 3.2, "{}", length);
+            }
+        }
+    }
+
+    #[test]
+    fn hex_at_position() {
+        for hex in [
+            HexCoord::new(0, 0),
+            HexCoord::new(4, 7),
+            HexCoord::new(5, 2),
+        ] {
+            let (x, y) = hex.center();
+            assert_eq!(HexCoord::at(x, y), hex);
+            assert_eq!(HexCoord::at(x + 2., y - 1.), hex);
+        }
+    }
+    #[test]
+    fn bounds_around_points() {
+        assert_eq!(Bounds::around([]), None);
+        let bounds = Bounds::around([(1., 2.), (-3., 5.), (0., -1.)]).unwrap();
+        assert_eq!((bounds.min, bounds.max), ((-3., -1.), (1., 5.)));
+        assert_eq!((bounds.width(), bounds.height()), (4., 6.));
+        assert!(close(bounds.center(), (-1., 2.)));
+        assert_eq!(bounds.clamp((7., 0.)), (1., 0.));
+    }
+}
@@ -1,23 +1,208 @@
-use super::matrix::{Mat4, Vec3};
+use super::board::picking::Picker;
+use super::board::topology::Bounds;
+use super::matrix::{Mat4, Vec2, Vec3};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
 // Found this which may be useful after I made the camera class: https://github.com/glium/glium/blob/master/examples/support/camera.rs
 
+/// Window pixels a world unit spans when a scene starts, hexes are 5 units wide
+const DEFAULT_SCALE: f32 = 24.;
+/// How far the camera zooms out and in, in pixels per world unit
+pub const MIN_SCALE: f32 = 4.;
+pub const MAX_SCALE: f32 = 110.;
+/// Scale change of one step of the mouse wheel
+pub const ZOOM_STEP: f32 = 1.1;
+/// Keyboard panning speed, in window heights per second
+const PAN_SPEED: f32 = 0.8;
+/// How fast panning speeds up and slows down, the fraction of the way to full speed per second
+const PAN_ACCELERATION: f32 = 12.;
+/// Part of the window the board fills once fitted
+const FIT_MARGIN: f32 = 0.9;
+/// Height of the camera over the board in the flat view, what is drawn lies on z = 0
+const FLAT_HEIGHT: f32 = 10.;
+/// How far the tilted view leans back from looking straight down, in radians
+const TILT: f32 = 0.6;
+/// Vertical field of view of the tilted view, in radians
+const FIELD_OF_VIEW: f32 = 0.8;
+/// A sixth of a turn, the board looks the same from each side of a hex
+pub const ROTATION_STEP: f32 = PI / 3.;
+
+/// How the board is looked at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewMode {
+    /// Straight down, orthographic: a world unit spans the same pixels all over the window
+    Flat,
+    /// Leaning back in perspective, the far side of the board shrinks
+    Tilted,
+}
+
+impl ViewMode {
+    pub fn toggled(self) -> Self {
+        match self {
+            ViewMode::Flat => ViewMode::Tilted,
+            ViewMode::Tilted => ViewMode::Flat,
+        }
+    }
+}
+
+/// Direction the keyboard pans the view, as seen on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pan {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Pan {
+    pub const ALL: [Pan; 4] = [Pan::Up, Pan::Down, Pan::Left, Pan::Right];
+
+    /// Direction in window pixels, y points down
+    fn pixels(&self) -> (f32, f32) {
+        match self {
+            Pan::Up => (0., -1.),
+            Pan::Down => (0., 1.),
+            Pan::Left => (-1., 0.),
+            Pan::Right => (1., 0.),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Camera {
+    /// World position drawn in the middle of the window
     pos: Vec3,
+    /// Window pixels per world unit, in the middle of the window
+    scale: f32,
+    /// Turn of the board on screen, counter-clockwise in radians
+    rotation: f32,
+    mode: ViewMode,
+    /// Window size in pixels
+    viewport: (u32, u32),
+    /// Keyboard panning, in pixels per second
+    velocity: (f32, f32),
+    /// Pan directions held, in the order of `Pan::ALL`
+    held: [bool; 4],
+    /// World rectangle the middle of the window stays in
+    bounds: Option<Bounds>,
 }
 
 impl Camera {
     pub fn new(pos_x: f32, pos_y: f32) -> Self {
         Camera {
             pos: (pos_x, pos_y, 0.0).into(),
+            scale: DEFAULT_SCALE,
+            rotation: 0.,
+            mode: ViewMode::Flat,
+            // Until the scene is told the size of the window
+            viewport: (1, 1),
+            velocity: (0., 0.),
+            held: [false; 4],
+            bounds: None,
         }
     }
 
+    pub fn position(&self) -> Vec3 {
+        self.pos
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn viewport(&self) -> (u32, u32) {
+        self.viewport
+    }
+
+    pub fn set_viewport(&mut self, width: u32, height: u32) {
+        self.viewport = (width.max(1), height.max(1));
+    }
+
+    pub fn mode(&self) -> ViewMode {
+        self.mode
+    }
+
+    /// Look at the board another way, the middle of the window stays over the same spot
+    pub fn set_mode(&mut self, mode: ViewMode) {
+        self.mode = mode;
+        self.clamp();
+    }
+
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Turn the board on screen to `angle` radians counter-clockwise, around the middle of the
+    /// window
+    pub fn set_rotation(&mut self, angle: f32) {
+        self.rotation = angle.rem_euclid(TAU);
+        self.clamp();
+    }
+
+    /// Turn the board by `steps` sixths of a turn counter-clockwise, from the nearest sixth
+    pub fn turn(&mut self, steps: i32) {
+        let nearest = (self.rotation / ROTATION_STEP).round();
+        self.set_rotation((nearest + steps as f32) * ROTATION_STEP);
+    }
+
+    /// Turn the board with the cursor, by the angle it swept around the middle of the window
+    pub fn twist(&mut self, from: (f32, f32), to: (f32, f32)) {
+        let middle = self.middle();
+        // Window pixels point down, angles are counter-clockwise on screen
+        let angle = |(x, y): (f32, f32)| (middle.1 - y).atan2(x - middle.0);
+        self.set_rotation(self.rotation + angle(to) - angle(from));
+    }
+
+    /// Distance from the tilted camera to the middle of the window, where a world unit still
+    /// spans `scale` pixels
+    fn distance(&self) -> f32 {
+        self.viewport.1 as f32 / (2. * self.scale * (FIELD_OF_VIEW / 2.).tan())
+    }
+
     pub fn view_matrix(&self) -> Mat4 {
-        Mat4::look_at(
-            self.pos,
-            self.pos - Vec3::new(0., 0., 1.),
-            Vec3::new(0., 1., 0.),
-        )
+        // Up the screen on the board, turned the other way from the board
+        let up = Vec3::from(Vec2::new(0., 1.).rotate(-self.rotation));
+        let eye = match self.mode {
+            ViewMode::Flat => Vec3::new(0., 0., FLAT_HEIGHT),
+            ViewMode::Tilted => {
+                (-up * TILT.sin() + Vec3::new(0., 0., TILT.cos())) * self.distance()
+            }
+        };
+        Mat4::look_at(self.pos + eye, self.pos, up)
+    }
+
+    pub fn projection_matrix(&self) -> Mat4 {
+        let (width, height) = (self.viewport.0 as f32, self.viewport.1 as f32);
+        match self.mode {
+            ViewMode::Flat => {
+                let (right, top) = (width / 2. / self.scale, height / 2. / self.scale);
+                Mat4::orthographic(-right, right, -top, top, 0., 2. * FLAT_HEIGHT)
+            }
+            ViewMode::Tilted => {
+                let distance = self.distance();
+                Mat4::perspective(
+                    FIELD_OF_VIEW,
+                    width / height,
+                    distance / 10.,
+                    distance * 10.,
+                )
+            }
+        }
+    }
+
+    /// Matrix the board is drawn with. Hexes are given in world units, so there is no model
+    /// transform.
+    pub fn mvp(&self) -> Mat4 {
+        self.projection_matrix() * self.view_matrix()
+    }
+
+    /// Maps window pixels to the board, `None` while the view can't be inverted
+    pub fn picker(&self) -> Option<Picker> {
+        Picker::new(&self.mvp(), self.viewport.0, self.viewport.1)
+    }
+
+    /// World position drawn at the window pixel
+    pub fn world_at(&self, x: f32, y: f32) -> Option<(f32, f32)> {
+        self.picker()?.world(x, y)
     }
 
     /// Move camera to a new position. Closure parameters are the camera's current position.
@@ -26,4 +211,346 @@ impl Camera {
         self.pos = new_pos(pos.0, pos.1, pos.2).into();
         // println!("Updated camera position: {:?}", self.pos);
     }
+
+    /// Move the camera so the world position is drawn at the window pixel. Where a pixel lands
+    /// on the board moves linearly with the camera, so seeing how far it moves for one step
+    /// along each axis is enough to solve for the whole move.
+    fn show_at(&mut self, (world_x, world_y): (f32, f32), x: f32, y: f32) {
+        let moved = |dx: f32, dy: f32| {
+            let mut camera = self.clone();
+            camera.pos = camera.pos + Vec3::new(dx, dy, 0.);
+            camera.world_at(x, y)
+        };
+        let steps = (moved(0., 0.), moved(1., 0.), moved(0., 1.));
+        let (Some(now), Some(step_x), Some(step_y)) = steps else {
+            return;
+        };
+        let (a, c) = (step_x.0 - now.0, step_x.1 - now.1);
+        let (b, d) = (step_y.0 - now.0, step_y.1 - now.1);
+        let determinant = a * d - b * c;
+        if determinant.abs() < f32::EPSILON {
+            return;
+        }
+        let (ex, ey) = (world_x - now.0, world_y - now.1);
+        let dx = (ex * d - b * ey) / determinant;
+        let dy = (a * ey - c * ex) / determinant;
+        self.pos = self.pos + Vec3::new(dx, dy, 0.);
+    }
+
+    /// Follow a drag of the mouse, the point grabbed stays under the cursor
+    pub fn drag(&mut self, from: (f32, f32), to: (f32, f32)) {
+        if let Some(grabbed) = self.world_at(from.0, from.1) {
+            self.show_at(grabbed, to.0, to.1);
+            self.clamp();
+        }
+    }
+
+    /// Zoom in (`steps` above 0) or out around the window pixel, which stays over the same spot
+    /// of the board
+    pub fn zoom_at(&mut self, steps: f32, x: f32, y: f32) {
+        let anchor = self.world_at(x, y);
+        self.scale = (self.scale * ZOOM_STEP.powf(steps)).clamp(MIN_SCALE, MAX_SCALE);
+        if let Some(anchor) = anchor {
+            self.show_at(anchor, x, y);
+        }
+        self.clamp();
+    }
+
+    /// Start or stop panning in a direction
+    pub fn pan(&mut self, direction: Pan, held: bool) {
+        self.held[direction as usize] = held;
+    }
+
+    /// Pan for `delta` seconds, speeding up towards the directions held and slowing down once
+    /// they are released
+    pub fn update(&mut self, delta: f32) {
+        let speed = PAN_SPEED * self.viewport.1 as f32;
+        let target = Pan::ALL
+            .iter()
+            .filter(|direction| self.held[**direction as usize])
+            .map(|direction| direction.pixels())
+            .fold((0., 0.), |(x, y), (dx, dy)| {
+                (x + dx * speed, y + dy * speed)
+            });
+        let blend = (PAN_ACCELERATION * delta).min(1.);
+        self.velocity = (
+            self.velocity.0 + (target.0 - self.velocity.0) * blend,
+            self.velocity.1 + (target.1 - self.velocity.1) * blend,
+        );
+        if self.velocity.0.abs() < 0.5 && self.velocity.1.abs() < 0.5 {
+            self.velocity = (0., 0.);
+            return;
+        }
+        // Panning right shows what is right of the middle, as dragging the board left would
+        let middle = self.middle();
+        let shown = (
+            middle.0 + self.velocity.0 * delta,
+            middle.1 + self.velocity.1 * delta,
+        );
+        self.drag(shown, middle);
+    }
+
+    /// Keep the middle of the window over `bounds`, e.g. the board
+    pub fn set_bounds(&mut self, bounds: Option<Bounds>) {
+        self.bounds = bounds;
+        self.clamp();
+    }
+
+    /// Zoom and move so `bounds` fill the window
+    pub fn fit(&mut self, bounds: Bounds) {
+        let (width, height) = (self.viewport.0 as f32, self.viewport.1 as f32);
+        let sides = (
+            self.world_at(0., height / 2.),
+            self.world_at(width, height / 2.),
+            self.world_at(width / 2., 0.),
+            self.world_at(width / 2., height),
+        );
+        if let (Some(left), Some(right), Some(top), Some(bottom)) = sides {
+            // What is seen across and up the window shrinks as the scale grows
+            let distance = |a: (f32, f32), b: (f32, f32)| (b.0 - a.0).hypot(b.1 - a.1);
+            let seen = (distance(left, right), distance(top, bottom));
+            // Size of the bounds across and up the turned window
+            let (sin, cos) = (self.rotation.sin().abs(), self.rotation.cos().abs());
+            let size = (
+                bounds.width() * cos + bounds.height() * sin,
+                bounds.width() * sin + bounds.height() * cos,
+            );
+            let fit = (seen.0 / size.0.max(f32::EPSILON)).min(seen.1 / size.1.max(f32::EPSILON));
+            self.scale = (self.scale * fit * FIT_MARGIN).clamp(MIN_SCALE, MAX_SCALE);
+        }
+        let middle = self.middle();
+        self.show_at(bounds.center(), middle.0, middle.1);
+    }
+
+    /// Middle of the window, in pixels
+    fn middle(&self) -> (f32, f32) {
+        (self.viewport.0 as f32 / 2., self.viewport.1 as f32 / 2.)
+    }
+
+    /// Bring the middle of the window back over the bounds
+    fn clamp(&mut self) {
+        let Some(bounds) = self.bounds else { return };
+        let middle = self.middle();
+        let Some(seen) = self.world_at(middle.0, middle.1) else {
+            return;
+        };
+        let clamped = bounds.clamp(seen);
+        if clamped != seen {
+            self.show_at(clamped, middle.0, middle.1);
+        }
+    }
+}
+
+/// Rotation, in whole `ROTATION_STEP`s, that brings `points` below `center` on screen: the side
+/// of the table a player sits at, seen from their settlements. `None` without points, or when
+/// they surround `center` evenly.
+pub fn facing(center: (f32, f32), points: &[(f32, f32)]) -> Option<f32> {
+    if points.is_empty() {
+        return None;
+    }
+    let count = points.len() as f32;
+    let (x, y) = points
+        .iter()
+        .fold((0., 0.), |(x, y), point| (x + point.0, y + point.1));
+    let (dx, dy) = (x / count - center.0, y / count - center.1);
+    if dx.hypot(dy) < f32::EPSILON {
+        return None;
+    }
+    let angle = -FRAC_PI_2 - dy.atan2(dx);
+    Some(((angle / ROTATION_STEP).round() * ROTATION_STEP).rem_euclid(TAU))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-2 && (a.1 - b.1).abs() < 1e-2
+    }
+
+    fn camera() -> Camera {
+        let mut camera = Camera::new(8., 0.);
+        camera.set_viewport(800, 600);
+        camera
+    }
+
+    fn board() -> Bounds {
+        Bounds {
+            min: (0., 0.),
+            max: (40., 30.),
+        }
+    }
+
+    #[test]
+    fn flat_view_has_world_units() {
+        let mut camera = camera();
+        let world = |camera: &Camera, x, y| camera.world_at(x, y).unwrap();
+        assert!(close(world(&camera, 400., 300.), (8., 0.)));
+        // y points up the screen
+        let scale = camera.scale();
+        assert!(close(world(&camera, 400. + scale, 300. - scale), (9., 1.)));
+        // Resizing the window shows more of the board, at the same size
+        camera.set_viewport(1600, 600);
+        assert!(close(world(&camera, 800. + scale, 300.), (9., 0.)));
+    }
+
+    #[test]
+    fn tilted_view_keeps_the_controls() {
+        let mut camera = camera();
+        camera.set_mode(ViewMode::Tilted);
+        let (x, y) = camera.world_at(400., 300.).unwrap();
+        assert!(close((x, y), (8., 0.)));
+        // The far side of the board shrinks
+        let bottom = camera.world_at(400., 590.).unwrap();
+        let top = camera.world_at(400., 10.).unwrap();
+        assert!(top.1 - y > y - bottom.1);
+
+        let anchor = camera.world_at(600., 100.).unwrap();
+        camera.zoom_at(2., 600., 100.);
+        assert!(close(camera.world_at(600., 100.).unwrap(), anchor));
+        let grabbed = camera.world_at(100., 500.).unwrap();
+        camera.drag((100., 500.), (300., 200.));
+        assert!(close(camera.world_at(300., 200.).unwrap(), grabbed));
+    }
+
+    /// Window pixel the world position is drawn at
+    fn screen(camera: &Camera, (x, y): (f32, f32)) -> (f32, f32) {
+        let ndc = camera.mvp().transform_point(Vec3::new(x, y, 0.)).unwrap();
+        let (width, height) = camera.viewport();
+        (
+            (ndc.x() + 1.) * width as f32 / 2.,
+            (1. - ndc.y()) * height as f32 / 2.,
+        )
+    }
+
+    fn same_angle(a: f32, b: f32) -> bool {
+        let difference = (a - b).rem_euclid(TAU);
+        difference < 1e-4 || TAU - difference < 1e-4
+    }
+
+    #[test]
+    fn turns_around_the_middle() {
+        for mode in [ViewMode::Flat, ViewMode::Tilted] {
+            let mut camera = camera();
+            camera.set_mode(mode);
+            camera.set_rotation(FRAC_PI_2);
+            assert!(close(camera.world_at(400., 300.).unwrap(), (8., 0.)));
+            // Turned a quarter counter-clockwise, what was below the middle is now right of it
+            let (x, y) = screen(&camera, (8., -1.));
+            assert!(x > 400. && (y - 300.).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn turns_by_steps_and_freely() {
+        let mut camera = camera();
+        camera.set_rotation(0.3);
+        camera.turn(1);
+        assert!(same_angle(camera.rotation(), ROTATION_STEP));
+        camera.turn(-2);
+        assert!(same_angle(camera.rotation(), 5. * ROTATION_STEP));
+        // A quarter turn of the cursor around the middle, from its right to above it
+        camera.set_rotation(0.);
+        camera.twist((500., 300.), (400., 200.));
+        assert!(same_angle(camera.rotation(), FRAC_PI_2));
+    }
+
+    #[test]
+    fn fits_when_turned() {
+        let mut camera = camera();
+        camera.set_rotation(FRAC_PI_2);
+        camera.fit(board());
+        let corners = [(0., 0.), (40., 0.), (0., 30.), (40., 30.)];
+        let drawn: Vec<(f32, f32)> = corners.iter().map(|c| screen(&camera, *c)).collect();
+        assert!(drawn
+            .iter()
+            .all(|(x, y)| (-1e-2..=800.01).contains(x) && (-1e-2..=600.01).contains(y)));
+        // Standing on its side, the board is as high as the window allows
+        let high = drawn.iter().map(|(_, y)| *y).fold(f32::MAX, f32::min);
+        assert!(high < 600. * 0.1);
+    }
+
+    #[test]
+    fn faces_the_settlements() {
+        let center = (10., 10.);
+        assert_eq!(facing(center, &[]), None);
+        assert_eq!(facing(center, &[(8., 10.), (12., 10.)]), None);
+        // Already below the middle
+        assert!(same_angle(
+            facing(center, &[(10., 2.), (12., 4.)]).unwrap(),
+            0.
+        ));
+        // Above the middle, the board goes half a turn around
+        assert!(same_angle(facing(center, &[(10., 18.)]).unwrap(), PI));
+        // Down to the right, a sixth of a turn clockwise
+        let (sin, cos) = (-PI / 6.).sin_cos();
+        let point = (center.0 + 5. * cos, center.1 + 5. * sin);
+        assert!(same_angle(
+            facing(center, &[point]).unwrap(),
+            -ROTATION_STEP
+        ));
+    }
+
+    #[test]
+    fn zooms_around_the_cursor() {
+        let mut camera = camera();
+        let anchor = camera.world_at(200., 150.).unwrap();
+        camera.zoom_at(3., 200., 150.);
+        assert!(camera.scale() > DEFAULT_SCALE);
+        assert!(close(camera.world_at(200., 150.).unwrap(), anchor));
+        camera.zoom_at(-100., 200., 150.);
+        assert_eq!(camera.scale(), MIN_SCALE);
+        camera.zoom_at(100., 200., 150.);
+        assert_eq!(camera.scale(), MAX_SCALE);
+    }
+
+    #[test]
+    fn drag_keeps_the_point_grabbed() {
+        let mut camera = camera();
+        let grabbed = camera.world_at(100., 100.).unwrap();
+        camera.drag((100., 100.), (300., 250.));
+        assert!(close(camera.world_at(300., 250.).unwrap(), grabbed));
+    }
+
+    #[test]
+    fn fits_and_stays_over_the_board() {
+        let mut camera = camera();
+        camera.fit(board());
+        assert!(close(
+            camera.world_at(400., 300.).unwrap(),
+            board().center()
+        ));
+        let top_left = camera.world_at(0., 0.).unwrap();
+        let bottom_right = camera.world_at(800., 600.).unwrap();
+        let seen = Bounds::around([top_left, bottom_right]).unwrap();
+        assert!(seen.width() >= board().width() && seen.height() >= board().height());
+        assert!(seen.width() < board().width() * 1.2 || seen.height() < board().height() * 1.2);
+
+        camera.set_bounds(Some(board()));
+        camera.drag((400., 300.), (-5000., 300.));
+        let middle = camera.world_at(400., 300.).unwrap();
+        assert!(close(board().clamp(middle), middle));
+    }
+
+    #[test]
+    fn pans_smoothly() {
+        let mut camera = camera();
+        let start = camera.world_at(400., 300.).unwrap();
+        camera.pan(Pan::Right, true);
+        camera.update(0.01);
+        let first = camera.world_at(400., 300.).unwrap();
+        camera.update(0.01);
+        let second = camera.world_at(400., 300.).unwrap();
+        // Speeding up, towards what was right of the middle
+        let screen_right = camera.world_at(500., 300.).unwrap();
+        let right = (screen_right.0 - second.0, screen_right.1 - second.1);
+        let moved = |a: (f32, f32), b: (f32, f32)| (b.0 - a.0) * right.0 + (b.1 - a.1) * right.1;
+        assert!(moved(start, first) > 0.);
+        assert!(moved(first, second) > moved(start, first));
+        camera.pan(Pan::Right, false);
+        for _ in 0..100 {
+            camera.update(0.01);
+        }
+        assert_eq!(camera.velocity, (0., 0.));
+    }
 }
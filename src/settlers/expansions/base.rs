@@ -1,22 +1,29 @@
-use crate::settlers::board::background::quad;
-use crate::settlers::board::hex;
-use crate::settlers::camera::Camera;
-use crate::settlers::game::{DeltaTime, Scene};
-use crate::settlers::matrix::Mat4;
-use crate::settlers::shader::{ProgramManager, TextureManager};
-use crate::settlers::Board;
+use crate::settlers::board::building::Structure;
+use crate::settlers::board::picking::Pick;
+use crate::settlers::board::topology::HexCoord;
+use crate::settlers::camera::{facing, Camera, Pan};
+use crate::settlers::game::{DeltaTime, Scene, Transition};
+use crate::settlers::palette::{Palette, Preset};
+use crate::settlers::render::{BoardRenderer, BoardView};
+use crate::settlers::rules::player::PlayerId;
+use crate::settlers::rules::{bot, Action, Build, Game, Phase};
+use crate::settlers::ui::debug::{DebugCommand, DebugInfo, DebugOverlay};
+use crate::settlers::ui::hud::{Command, Hud};
+use crate::settlers::ui::menu::{Pause, Results};
 use glium::backend::Facade;
-use glium::index::NoIndices;
-use glium::uniforms::UniformBuffer;
-use glium::{Frame, IndexBuffer, Surface, VertexBuffer};
+use glium::Frame;
+use std::collections::BTreeMap;
 use std::time::Instant;
 use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, TouchPhase};
+use winit::keyboard::{KeyCode, PhysicalKey};
 
-const MOUSE_SPEED: f32 = 10.;
+/// Seconds between two bot actions
+const BOT_DELAY: f32 = 0.4;
 
 pub struct Mouse {
     left_click_pressed: bool,
+    middle_click_pressed: bool,
     last_mouse_pos: PhysicalPosition<f64>,
 }
 
@@ -24,22 +31,17 @@ impl Mouse {
     pub fn new() -> Self {
         Mouse {
             left_click_pressed: false,
+            middle_click_pressed: false,
             last_mouse_pos: PhysicalPosition::new(0., 0.),
         }
     }
 
     pub fn update_buttons(&mut self, state: ElementState, button: MouseButton) {
-        match state {
-            ElementState::Pressed => {
-                if button == MouseButton::Left {
-                    self.left_click_pressed = true;
-                }
-            }
-            ElementState::Released => {
-                if button == MouseButton::Left {
-                    self.left_click_pressed = false;
-                }
-            }
+        let pressed = state == ElementState::Pressed;
+        match button {
+            MouseButton::Left => self.left_click_pressed = pressed,
+            MouseButton::Middle => self.middle_click_pressed = pressed,
+            _ => (),
         }
     }
 
@@ -51,287 +53,492 @@ impl Mouse {
         self.left_click_pressed
     }
 
+    pub fn middle_pressed(&self) -> bool {
+        self.middle_click_pressed
+    }
+
     pub fn last_pos(&self) -> PhysicalPosition<f64> {
         self.last_mouse_pos
     }
 }
 
+/// Direction a key pans the camera, WASD and the arrows
+pub fn pan_direction(key: KeyCode) -> Option<Pan> {
+    match key {
+        KeyCode::KeyW | KeyCode::ArrowUp => Some(Pan::Up),
+        KeyCode::KeyS | KeyCode::ArrowDown => Some(Pan::Down),
+        KeyCode::KeyA | KeyCode::ArrowLeft => Some(Pan::Left),
+        KeyCode::KeyD | KeyCode::ArrowRight => Some(Pan::Right),
+        _ => None,
+    }
+}
+
 pub struct BaseGame<'p> {
-    window_dim: PhysicalSize<u32>,
-    // Keep track of the program time
-    time: Instant,
-    board: Board,
-    program_manager: ProgramManager<'p>,
-    // Texture for the hex's
-    texture_manager: TextureManager<'p>,
+    game: Game,
+    renderer: BoardRenderer<'p>,
     camera: Camera,
     delta_time: DeltaTime,
     mouse: Mouse,
-    scale: f32,
+    // Human player the board is shown to
+    viewer: PlayerId,
+    // Acting player the view last followed
+    last_acting: PlayerId,
+    // Board rotation each human player turned the board to
+    rotations: BTreeMap<PlayerId, f32>,
+    // Turn the board towards the viewer's settlements, unless they turned it themselves
+    auto_orient: bool,
+    // What the last click landed on
+    selected: Option<Pick>,
+    // Last two spots clicked while not placing, knights and progress cards are played on them
+    targets: Vec<Pick>,
+    // What the cursor is over
+    hovered: Option<Pick>,
+    // Kind of structure being placed, its legal locations are previewed
+    placing: Option<Build>,
+    palette: Palette,
+    hud: Hud,
+    // Only with `settings::DEBUG`
+    debug: Option<DebugOverlay>,
+    // Players the computer plays for
+    bots: Vec<PlayerId>,
+    last_bot_action: Instant,
+    // Bot that couldn't even end its turn, it waits until the game changes
+    stuck_bot: Option<PlayerId>,
+    transition: Option<Transition>,
+    // Whether the results were shown
+    finished: bool,
 }
 
 impl<'p> BaseGame<'p> {
-    pub fn new<F>(facade: &F) -> Self
-    where
-        F: Sized + Facade,
-    {
-        // Generate board
-        let mut board: Board = Board::from_file("src/settlers/board/maps/chungus.focm").unwrap();
-        board.randomize();
-        // Manage textures
-        let mut texture_manager = TextureManager::new();
-        // Generate texture for hex tiles
-        texture_manager
-            .add_texture(facade, "hex_tm", "assets/hex/hex_tilemap.png")
-            .expect("hex texture should be found");
-        // Generate texture for chances
-        texture_manager
-            .add_texture(
-                facade,
-                "chance_tm",
-                "assets/hex/chances_tilemap.png",
-            )
-            .expect("chances texture should be found");
-        // Generate texture for structures
-        texture_manager
-            .add_texture(
-                facade,
-                "building_tm",
-                "assets/structures/settlement.png",
-            )
-            .expect("structure texture should be found");
-        // Create shader program
-        use crate::settings::WINDOW_DEFAULT_SIZE;
-        let mut program_manager = ProgramManager::new();
-        program_manager
-            .add_program(
-                facade,
-                "hex",
-                "glsl/hex/hex.v.glsl",
-                "glsl/hex/hex.f.glsl",
-                Some("glsl/hex/hex.g.glsl"),
-            )
-            .expect("Hex shaders properly compiling");
-        program_manager
-            .add_program(facade, "bg", "glsl/bg/bg.v.glsl", "glsl/bg/bg.f.glsl", None)
-            .expect("Background shaders properly compiling");
-        program_manager
-            .add_program(
-                facade,
-                "structures",
-                "glsl/structure/str.v.glsl",
-                "glsl/structure/str.f.glsl",
-                Some("glsl/structure/str.g.glsl"),
-            )
-            .expect("Structure shaders properly compiling");
-
+    /// Scene playing `game`, the computer plays for the `bots`
+    pub fn new(game: Game, debug: bool, bots: Vec<PlayerId>) -> Self {
+        use crate::settings::{PALETTE_FILE, WINDOW_DEFAULT_SIZE};
+        let mut camera = Camera::new(8., 0.);
+        camera.set_viewport(WINDOW_DEFAULT_SIZE.width, WINDOW_DEFAULT_SIZE.height);
+        camera.set_bounds(game.board().extents());
+        if let Some(bounds) = game.board().extents() {
+            camera.fit(bounds);
+        }
+        let acting = game.state().acting();
+        // A broken palette file is shown in the HUD and the classic colours used instead
+        let mut hud = Hud::new();
+        let palette = load_palette(PALETTE_FILE).unwrap_or_else(|message| {
+            hud.report(Some(message));
+            Palette::default()
+        });
         Self {
-            window_dim: WINDOW_DEFAULT_SIZE,
-            time: Instant::now(),
-            board,
-            program_manager,
-            texture_manager,
-            camera: Camera::new(8., 0.),
+            game,
+            renderer: BoardRenderer::new(),
+            camera,
             delta_time: DeltaTime::new(),
             mouse: Mouse::new(),
-            scale: 0.13,
+            viewer: acting,
+            last_acting: acting,
+            rotations: BTreeMap::new(),
+            auto_orient: false,
+            selected: None,
+            targets: Vec::new(),
+            hovered: None,
+            placing: None,
+            palette,
+            hud,
+            debug: debug.then(DebugOverlay::new),
+            bots,
+            last_bot_action: Instant::now(),
+            stuck_bot: None,
+            transition: None,
+            finished: false,
         }
     }
 
-    fn mvp(&self) -> [[f32; 4]; 4] {
-        let mut projection = Mat4::projection(
-            self.window_dim.width as f32 / self.window_dim.height as f32,
-            90.,
-            1.0,
-            -1.0,
-        );
-        let mut transform = Mat4::identity();
-        transform
-            .translate(-0.4, -0.6, 0.)
-            .scale_uniformly(self.scale);
-        let view = self.camera.view_matrix();
-        projection.multiply_by(&view).multiply_by(&transform);
-        // println!("{}", projection);
-        projection.to_array()
+    fn view(&self) -> BoardView {
+        BoardView {
+            hovered: self.hovered,
+            ..BoardView::new(&self.camera)
+        }
+    }
+
+    /// Hex, intersection or edge under the cursor
+    fn pick(&self, position: PhysicalPosition<f64>) -> Option<Pick> {
+        self.camera
+            .picker()?
+            .pick(self.game.board(), position.x as f32, position.y as f32)
+    }
+
+    /// Zoom and move the camera so the whole board is seen
+    fn fit(&mut self) {
+        if let Some(bounds) = self.game.board().extents() {
+            self.camera.fit(bounds);
+        }
+    }
+
+    /// Turn the board the way the viewer turned it, otherwise towards their settlements
+    fn orient(&mut self) {
+        if let Some(rotation) = self.rotations.get(&self.viewer) {
+            self.camera.set_rotation(*rotation);
+            return;
+        }
+        if !self.auto_orient {
+            return;
+        }
+        let board = self.game.board();
+        let settlements: Vec<(f32, f32)> = board
+            .buildings()
+            .iter()
+            .filter(|structure| structure.is_building() && structure.owner() == self.viewer)
+            .map(|structure| structure.position())
+            .collect();
+        let rotation = board
+            .extents()
+            .and_then(|bounds| facing(bounds.center(), &settlements));
+        if let Some(rotation) = rotation {
+            self.camera.set_rotation(rotation);
+        }
+    }
+
+    /// Keep the rotation the viewer turned the board to
+    fn turned(&mut self) {
+        self.rotations.insert(self.viewer, self.camera.rotation());
+    }
+
+    /// Show the board to the acting player, each time a human's turn comes
+    fn follow_turn(&mut self) {
+        let acting = self.game.state().acting();
+        if acting == self.last_acting {
+            return;
+        }
+        self.last_acting = acting;
+        if !self.bots.contains(&acting) {
+            self.viewer = acting;
+            self.orient();
+        }
+    }
+
+    /// Where the acting player could place the structure being placed
+    fn previews(&self) -> Vec<Structure> {
+        let state = self.game.state();
+        self.placing
+            .map(|build| self.game.rules().legal_builds(state, state.acting(), build))
+            .unwrap_or_default()
+    }
+
+    /// Build the previewed structure under the cursor, or move the robber to the clicked hex
+    fn place(&mut self) {
+        let Some(pick) = self.selected else { return };
+        if let (Phase::Robber, Pick::Hex(hex)) = (self.game.state().phase(), pick) {
+            self.rob(hex);
+            return;
+        }
+        let Some(structure) = self.previews().into_iter().find(|s| pick.covers(s)) else {
+            return;
+        };
+        if self.act(&Action::build(&structure)) {
+            self.placing = None;
+        }
+    }
+
+    /// What the debug overlay shows that the game doesn't know
+    fn debug_info(&self) -> DebugInfo {
+        let board = self.game.board();
+        DebugInfo {
+            frame_time: self.delta_time.frame_time(),
+            camera: self.camera.position(),
+            scale: self.camera.scale(),
+            hovered: self.hovered,
+            selected: self.selected,
+            hex_meta: board
+                .hex_buffers(self.hovered)
+                .iter()
+                .find(|vertex| vertex.is_hovered())
+                .map(|vertex| vertex.meta()),
+            structure_meta: board
+                .building_buffers(self.hovered, &self.previews())
+                .iter()
+                .filter(|vertex| vertex.is_hovered())
+                .map(|vertex| vertex.meta())
+                .collect(),
+        }
+    }
+
+    /// Let a bot play, waiting a little between actions so they can be followed
+    fn play_bots(&mut self) {
+        if self.stuck_bot.is_some() || self.last_bot_action.elapsed().as_secs_f32() < BOT_DELAY {
+            return;
+        }
+        let Some((player, action)) = bot::next_action(&self.game, &self.bots) else {
+            return;
+        };
+        self.last_bot_action = Instant::now();
+        // The bot doesn't know every expansion's rules, it gives up on its turn instead
+        if let Err(error) = self.game.apply(player, &action) {
+            self.hud.report(Some(format!(
+                "Bot {} can't {:?}: {}",
+                player + 1,
+                action,
+                error
+            )));
+            if let Err(error) = self.game.apply(player, &Action::EndTurn) {
+                self.hud
+                    .report(Some(format!("Bot {} is stuck: {}", player + 1, error)));
+                self.stuck_bot = Some(player);
+            }
+        }
+    }
+
+    /// Move the robber to a land hex, or the pirate to a sea hex when the rules have one. The
+    /// HUD asks whom to rob when there is a choice.
+    fn rob(&mut self, hex: HexCoord) {
+        let state = self.game.state();
+        let thief = state.acting();
+        let rules = self.game.rules();
+        let pirate = rules.has_pirate() && !state.board.is_land(hex);
+        let victims = if pirate {
+            rules.pirate_victims(state, thief, hex)
+        } else {
+            state.robbable(thief, hex)
+        };
+        let robbery = |victim| match pirate {
+            true => Action::MovePirate { hex, victim },
+            false => Action::MoveRobber { hex, victim },
+        };
+        if victims.len() > 1 {
+            let robberies = victims
+                .into_iter()
+                .map(|victim| (victim, robbery(Some(victim))))
+                .collect();
+            self.hud.offer_robberies(robberies);
+        } else {
+            self.act(&robbery(victims.first().copied()));
+        }
+    }
+
+    /// Change the game behind the rules' back
+    fn apply_debug(&mut self, command: DebugCommand) {
+        let state = self.game.state_mut();
+        match command {
+            DebugCommand::RerollBoard => state.board.randomize(),
+            DebugCommand::ForceDice(dice) => state.force_dice(dice),
+            DebugCommand::Give(card, amount) => {
+                let player = state.acting();
+                state.player_mut(player).hand.add(card, amount);
+            }
+            DebugCommand::MoveRobber(hex) => state.board.move_robber(hex),
+        }
+        self.stuck_bot = None;
+    }
+
+    /// Apply an action for the acting player, telling the HUD why it was refused.
+    /// Returns whether it was applied.
+    fn act(&mut self, action: &Action) -> bool {
+        self.act_as(self.game.state().acting(), action)
+    }
+
+    /// Apply an action for a human player, who need not be the acting one
+    fn act_as(&mut self, player: PlayerId, action: &Action) -> bool {
+        if self.bots.contains(&player) {
+            self.hud
+                .report(Some(format!("Player {} is played by a bot", player + 1)));
+            return false;
+        }
+        match self.game.apply(player, action) {
+            Ok(_) => {
+                self.stuck_bot = None;
+                self.hud.report(None);
+                true
+            }
+            Err(error) => {
+                self.hud.report(Some(format!("Can't do that: {}", error)));
+                false
+            }
+        }
     }
 }
 
 impl<'p> Scene for BaseGame<'p> {
+    fn enter(&mut self, facade: &dyn Facade) {
+        // The expansion decides which textures are needed
+        self.renderer.load(facade, self.game.rules().textures());
+    }
+
+    fn exit(&mut self) {
+        self.renderer.unload();
+    }
+
     // Called on mouse move
     fn mouse_move(&mut self, position: PhysicalPosition<f64>) {
         let last_pos = self.mouse.last_pos();
         if self.mouse.left_pressed() {
-            self.camera.move_to(|x, y, z| {
-                (
-                    x - (last_pos.x - position.x) as f32 * MOUSE_SPEED
-                        / self.window_dim.width as f32,
-                    y - (last_pos.y - position.y) as f32 * MOUSE_SPEED
-                        / self.window_dim.height as f32,
-                    z,
-                )
-            })
+            self.camera.drag(
+                (last_pos.x as f32, last_pos.y as f32),
+                (position.x as f32, position.y as f32),
+            );
+        }
+        if self.mouse.middle_pressed() {
+            self.camera.twist(
+                (last_pos.x as f32, last_pos.y as f32),
+                (position.x as f32, position.y as f32),
+            );
+            self.turned();
         }
         self.mouse.update_cursor(position);
+        self.hovered = self.pick(position);
     }
 
     // Called on recieving mouse input
     fn mouse_input(&mut self, state: ElementState, button: MouseButton) {
         self.mouse.update_buttons(state, button);
+        if state == ElementState::Pressed && button == MouseButton::Left {
+            self.selected = self.pick(self.mouse.last_pos());
+            if let (Some(pick), None) = (self.selected, self.placing) {
+                self.targets.push(pick);
+                if self.targets.len() > 2 {
+                    self.targets.remove(0);
+                }
+            }
+            self.place();
+        }
     }
 
     fn scroll_input(&mut self, delta: MouseScrollDelta, _phase: TouchPhase) {
-        match delta {
-            MouseScrollDelta::LineDelta(_, scroll) => self.scale += scroll / 200.,
-            _ => (),
+        // Zoom towards the cursor
+        if let MouseScrollDelta::LineDelta(_, scroll) = delta {
+            let cursor = self.mouse.last_pos();
+            self.camera
+                .zoom_at(scroll, cursor.x as f32, cursor.y as f32);
         }
     }
 
     // Called on recieving keyboard input
     fn keyboard_input(&mut self, event: KeyEvent) {
-        use winit::keyboard::{KeyCode, PhysicalKey};
-        let move_const = 100.;
+        // Pan for as long as the key is held
+        if let PhysicalKey::Code(code) = event.physical_key {
+            if let Some(direction) = pan_direction(code) {
+                self.camera.pan(direction, event.state.is_pressed());
+                return;
+            }
+        }
         match &event.physical_key {
-            PhysicalKey::Code(KeyCode::KeyW) => self
-                .camera
-                .move_to(|x, y, z| (x, y + move_const * self.delta_time.delta(), z)),
+            // Pick what to build, previewing where it can go
+            PhysicalKey::Code(code) if event.state.is_pressed() => match code {
+                KeyCode::Digit1 => self.placing = Some(Build::Road),
+                KeyCode::Digit2 => self.placing = Some(Build::Settlement),
+                KeyCode::Digit3 => self.placing = Some(Build::City),
+                KeyCode::Digit4 => self.placing = Some(Build::Ship),
+                KeyCode::Digit5 => self.placing = Some(Build::Knight),
+                KeyCode::Escape if self.placing.is_some() => self.placing = None,
+                KeyCode::Escape => {
+                    let pause = Pause::new(self.debug.is_some());
+                    self.transition = Some(Transition::Push(Box::new(pause)));
+                }
+                // Next colour preset, then patterns on or off
+                KeyCode::KeyP => {
+                    let next = Preset::ALL
+                        .iter()
+                        .position(|preset| *preset == self.palette.base())
+                        .map_or(0, |i| (i + 1) % Preset::ALL.len());
+                    let patterns = self.palette.show_patterns();
+                    self.palette = Palette::preset(Preset::ALL[next]);
+                    self.palette.set_show_patterns(patterns);
+                }
+                KeyCode::KeyO => self
+                    .palette
+                    .set_show_patterns(!self.palette.show_patterns()),
+                KeyCode::KeyF => self.fit(),
+                // Between the flat and the tilted view
+                KeyCode::KeyV => self.camera.set_mode(self.camera.mode().toggled()),
+                // A sixth of a turn counter-clockwise or clockwise
+                KeyCode::KeyQ => {
+                    self.camera.turn(1);
+                    self.turned();
+                }
+                KeyCode::KeyE => {
+                    self.camera.turn(-1);
+                    self.turned();
+                }
+                // Turning the board towards the viewer's settlements on or off
+                KeyCode::KeyT => {
+                    self.auto_orient = !self.auto_orient;
+                    if self.auto_orient {
+                        self.rotations.remove(&self.viewer);
+                        self.orient();
+                    }
+                }
+                _ => (),
+            },
             _ => (),
         }
     }
 
     fn window_size(&mut self, new_size: PhysicalSize<u32>) {
-        println!("Scale [{}, {}]", new_size.width, new_size.height);
-        self.window_dim = new_size;
+        self.camera.set_viewport(new_size.width, new_size.height);
+    }
+
+    fn ui(&mut self, ui: &imgui::Ui) {
+        match self.hud.draw(
+            ui,
+            &self.game,
+            &self.palette,
+            self.placing,
+            &self.bots,
+            &self.targets,
+        ) {
+            Some(Command::Act(action)) => {
+                self.act(&action);
+            }
+            Some(Command::ActAs(player, action)) => {
+                self.act_as(player, &action);
+            }
+            Some(Command::Place(build)) => self.placing = Some(build),
+            Some(Command::CancelPlace) => self.placing = None,
+            None => (),
+        }
+        let info = self.debug.is_some().then(|| self.debug_info());
+        if let (Some(overlay), Some(info)) = (&mut self.debug, info) {
+            if let Some(command) = overlay.draw(ui, &self.game, &info) {
+                self.apply_debug(command);
+            }
+        }
     }
 
     // Called every time before draw
     fn update(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         self.delta_time.update();
+        self.camera.update(self.delta_time.frame_time());
+        self.play_bots();
+        self.follow_turn();
         Ok(())
     }
 
-    fn draw<F>(&self, facade: &F, mut frame: Frame) -> Frame
-    where
-        F: ?Sized + Facade,
-    {
-        use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter};
-        use glium::BlendingFunction::Addition;
-        use glium::LinearBlendingFactor::{OneMinusSourceAlpha, SourceAlpha};
-        let mvp = self.mvp();
-        // ============== Background ===============
-        let mut total_hex: u32 = 0;
-        // The board must have less than 64 total hex tiles
-        let hex_positions: [(f32, f32); hex::MAX_HEX as usize] = {
-            let mut arr = [(0.0, 0.0); hex::MAX_HEX as usize];
-            self.board
-                .hex_buffers()
-                .iter()
-                .map(|vert| vert.position())
-                .enumerate()
-                .for_each(|(i, (x, y))| {
-                    // Matrix transformation with camera position (Can't explain the +1 but its needed)
-                    arr[i] = (
-                        x * mvp[0][0] + y * mvp[0][1] + mvp[0][3] + mvp[3][0] + 1.,
-                        x * mvp[1][0] + y * mvp[1][1] + mvp[1][3] + mvp[3][1] + 1.,
-                    );
-                    total_hex += 1;
-                });
-            arr
-        };
-        let hex_pos_buffer = UniformBuffer::new(facade, hex_positions).unwrap();
-        let bg_vbo = VertexBuffer::new(facade, &quad::VERTICES).unwrap();
-        let bg_ebo = IndexBuffer::new(
+    fn transition(&mut self) -> Option<Transition> {
+        if !self.finished && matches!(self.game.state().phase(), Phase::Finished(_)) {
+            self.finished = true;
+            let results = Results::new(&self.game, self.debug.is_some());
+            return Some(Transition::Push(Box::new(results)));
+        }
+        self.transition.take()
+    }
+
+    fn draw(&self, facade: &dyn Facade, mut frame: Frame) -> Frame {
+        let view = self.view();
+        let board = self.game.board();
+        self.renderer.draw_hexes(facade, &mut frame, board, &view);
+        self.renderer.draw_structures(
             facade,
-            glium::index::PrimitiveType::TrianglesList,
-            &quad::INDICES,
-        )
-        .unwrap();
-        frame
-            .draw(
-                &bg_vbo,
-                &bg_ebo,
-                &self
-                    .program_manager
-                    .program("bg")
-                    .expect("Background program exists"),
-                &uniform! {
-                    mvp: mvp,
-                    total_hex: total_hex,
-                    hex_positions: &hex_pos_buffer,
-                    u_scale: self.scale,
-                    u_resolution: (self.window_dim.width, self.window_dim.height),
-                    u_time: self.time.elapsed().as_secs_f32(),
-                },
-                &Default::default(),
-            )
-            .unwrap();
-
-        // ============== Hex tiles ================
-        let vertices = self.board.hex_buffers();
-        let vertex_buffer = VertexBuffer::new(facade, &vertices).unwrap();
-        let index_buffer = NoIndices(glium::index::PrimitiveType::Points);
-        
-        // For png transparency
-        let params = glium::DrawParameters {
-            blend: glium::Blend {
-                color: Addition {
-                    source: SourceAlpha,
-                    destination: OneMinusSourceAlpha,
-                },
-                alpha: Addition {
-                    source: SourceAlpha,
-                    destination: OneMinusSourceAlpha,
-                },
-                constant_value: (0.0, 0.0, 0.0, 0.0),
-            },
-            ..Default::default()
-        };
-        frame
-            .draw(
-                &vertex_buffer,
-                &index_buffer,
-                &self
-                    .program_manager
-                    .program("hex")
-                    .expect("Hex program exists"),
-                &uniform! { u_mvp: mvp,
-                    u_resolution: (self.window_dim.width, self.window_dim.height),
-                    u_time: self.time.elapsed().as_secs_f32(),
-                    texture_map_hex: self.texture_manager.texture("hex_tm").unwrap().sampled()
-                        .wrap_function(glium::uniforms::SamplerWrapFunction::BorderClamp)
-                        .magnify_filter(MagnifySamplerFilter::Nearest)
-                        .minify_filter(MinifySamplerFilter::Nearest),
-                    texture_map_chances: self.texture_manager.texture("chance_tm").unwrap().sampled()
-                        .wrap_function(glium::uniforms::SamplerWrapFunction::BorderClamp)
-                        .magnify_filter(MagnifySamplerFilter::Nearest)
-                        .minify_filter(MinifySamplerFilter::Nearest),
-                },
-                &params,
-            )
-            .unwrap();
-
-        // =============== Settlements / Cities / Roads ==================
-        let vertices = self.board.building_buffers();
-        let vertex_buffer = VertexBuffer::new(facade, &vertices).unwrap();
-        let index_buffer = NoIndices(glium::index::PrimitiveType::Points);
-        frame
-            .draw(
-                &vertex_buffer,
-                &index_buffer,
-                &self
-                    .program_manager
-                    .program("structures")
-                    .expect("structure program exists"),
-                &uniform! { u_mvp: mvp,
-                    u_resolution: (self.window_dim.width, self.window_dim.height),
-                    u_time: self.time.elapsed().as_secs_f32(),
-                    texture_map_structures: self.texture_manager.texture("building_tm").unwrap().sampled()
-                        .wrap_function(glium::uniforms::SamplerWrapFunction::BorderClamp)
-                        .magnify_filter(MagnifySamplerFilter::Nearest)
-                        .minify_filter(MinifySamplerFilter::Nearest),
-                },
-                &params,
-            )
-            .unwrap();
+            &mut frame,
+            board,
+            &view,
+            &self.previews(),
+            &self.palette,
+        );
         frame
     }
 }
+
+/// Palette from the file if there is one, otherwise the classic colours. Errs with why the
+/// file can't be used.
+fn load_palette(file: &str) -> Result<Palette, String> {
+    if !std::path::Path::new(file).exists() {
+        return Ok(Palette::default());
+    }
+    Palette::from_file(file).map_err(|error| format!("Palette {}: {}", file, error))
+}
@@ -0,0 +1,1335 @@
+//! Cities & Knights. Cities on forests, pastures and mountains also produce commodities, which
+//! buy levels on the three city improvement tracks. Knights hold off the barbarians, whose ship
+//! sails closer whenever the event die shows it. City gates on the event die hand out the
+//! progress cards of `progress`.
+
+pub mod progress;
+
+use super::{Expansion, ExpansionAction, ExpansionEvent, Ruleset};
+use crate::settlers::board::building::Structure;
+use crate::settlers::board::card::{Resource, ResourceCard};
+use crate::settlers::board::topology::{Edge, HexCoord, Intersection};
+use crate::settlers::rules::player::{Hand, PlayerId};
+use crate::settlers::rules::{Action, Build, Event, GameState, Phase, RuleConfig, RuleError};
+use progress::{Progress, ProgressCard};
+use rand::Rng;
+use std::collections::BTreeMap;
+
+/// Cities & Knights plays to 13 points
+const KINGDOM_VICTORY_POINTS: u32 = 13;
+/// Last level of an improvement track
+pub const MAX_LEVEL: u8 = 5;
+/// Level unlocking the special ability of a track
+pub const ABILITY_LEVEL: u8 = 3;
+/// First level to reach it earns the metropolis of a track
+pub const METROPOLIS_LEVEL: u8 = 4;
+/// Points a metropolis adds to its city
+const METROPOLIS_POINTS: u32 = 2;
+/// Cards given for one with a trading house, the merchant or a merchant fleet
+const PREFERRED_RATE: u32 = 2;
+/// Steps the barbarian ship sails before it lands
+pub const BARBARIAN_TRACK: u8 = 7;
+/// Strength of a mighty knight, promoting to it needs the fortress
+pub const MIGHTY: u8 = 3;
+/// Knights of every strength each player has
+const KNIGHTS_PER_STRENGTH: u32 = 2;
+/// City walls each player has
+const WALLS: u32 = 3;
+/// Cards a city wall adds to the discard limit
+const WALL_HAND_LIMIT: u32 = 2;
+
+/// Faces of the event die, three of the six show the barbarian ship
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventDie {
+    Barbarians,
+    /// City gate of a track
+    Gate(Improvement),
+}
+
+impl EventDie {
+    pub fn from_face(face: u8) -> Self {
+        match face % 6 {
+            0..=2 => EventDie::Barbarians,
+            3 => EventDie::Gate(Improvement::Trade),
+            4 => EventDie::Gate(Improvement::Politics),
+            _ => EventDie::Gate(Improvement::Science),
+        }
+    }
+}
+
+/// City improvement tracks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Improvement {
+    Trade,
+    Politics,
+    Science,
+}
+
+impl Improvement {
+    pub const ALL: [Improvement; 3] = [
+        Improvement::Trade,
+        Improvement::Politics,
+        Improvement::Science,
+    ];
+
+    /// Commodity the levels of the track are paid with
+    pub fn commodity(&self) -> ResourceCard {
+        match self {
+            Improvement::Trade => ResourceCard::Cloth,
+            Improvement::Politics => ResourceCard::Coin,
+            Improvement::Science => ResourceCard::Paper,
+        }
+    }
+
+    /// Special ability unlocked at `ABILITY_LEVEL`
+    pub fn ability(&self) -> &'static str {
+        match self {
+            Improvement::Trade => "Trading House",
+            Improvement::Politics => "Fortress",
+            Improvement::Science => "Aqueduct",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+pub struct KingdomRules {
+    config: RuleConfig,
+    /// Level of every track, by player
+    levels: BTreeMap<PlayerId, [u8; 3]>,
+    /// Holder of each track's metropolis and the city it stands on
+    metropolises: BTreeMap<Improvement, (PlayerId, Intersection)>,
+    /// Position of the barbarian ship, it lands at `BARBARIAN_TRACK`
+    barbarians: u8,
+    /// Defender of Catan points
+    defenders: BTreeMap<PlayerId, u32>,
+    /// Knights promoted this turn, a knight is promoted once per turn
+    promoted: Vec<Intersection>,
+    /// Knights activated this turn, they act from the next turn on
+    activated: Vec<Intersection>,
+    /// Progress cards left in the deck of every track
+    decks: BTreeMap<Improvement, Vec<ProgressCard>>,
+    /// Progress cards in every player's hand
+    progress: BTreeMap<PlayerId, Vec<ProgressCard>>,
+    /// Points of the victory point progress cards played
+    progress_points: BTreeMap<PlayerId, u32>,
+    /// Players owed a progress card of their choice
+    owed_progress: Vec<PlayerId>,
+    /// Cities with a wall, by owner
+    walls: Vec<(PlayerId, Intersection)>,
+    /// Holder of the merchant and the hex it stands on
+    merchant: Option<(PlayerId, HexCoord)>,
+    /// Whether the next improvement this turn costs a commodity less
+    crane: bool,
+    /// Resource the current player trades at 2:1 this turn
+    fleet: Option<ResourceCard>,
+}
+
+impl KingdomRules {
+    pub fn new() -> Self {
+        let mut config = RuleConfig {
+            victory_points: KINGDOM_VICTORY_POINTS,
+            ..RuleConfig::base()
+        };
+        config.costs.push((
+            Build::Knight,
+            Hand::of(&[(ResourceCard::Sheep, 1), (ResourceCard::Ore, 1)]),
+        ));
+        config
+            .costs
+            .push((Build::Wall, Hand::of(&[(ResourceCard::Brick, 2)])));
+        config.pieces.push((Build::Wall, WALLS));
+        KingdomRules {
+            config,
+            levels: BTreeMap::new(),
+            metropolises: BTreeMap::new(),
+            barbarians: 0,
+            defenders: BTreeMap::new(),
+            promoted: Vec::new(),
+            activated: Vec::new(),
+            decks: Improvement::ALL
+                .into_iter()
+                .map(|improvement| (improvement, progress::deck(improvement)))
+                .collect(),
+            progress: BTreeMap::new(),
+            progress_points: BTreeMap::new(),
+            owed_progress: Vec::new(),
+            walls: Vec::new(),
+            merchant: None,
+            crane: false,
+            fleet: None,
+        }
+    }
+
+    pub fn level(&self, player: PlayerId, improvement: Improvement) -> u8 {
+        self.levels
+            .get(&player)
+            .map_or(0, |levels| levels[improvement.index()])
+    }
+
+    /// Whether the player has the Trading House, Fortress or Aqueduct of the track
+    pub fn has_ability(&self, player: PlayerId, improvement: Improvement) -> bool {
+        self.level(player, improvement) >= ABILITY_LEVEL
+    }
+
+    pub fn metropolis(&self, improvement: Improvement) -> Option<(PlayerId, Intersection)> {
+        self.metropolises.get(&improvement).copied()
+    }
+
+    pub fn barbarians(&self) -> u8 {
+        self.barbarians
+    }
+
+    /// City walls the player has built
+    pub fn walls(&self, player: PlayerId) -> u32 {
+        self.walls
+            .iter()
+            .filter(|(owner, _)| *owner == player)
+            .count() as u32
+    }
+
+    /// Roll the dice, the event die first. A barbarian face sails the ship closer, a city gate
+    /// hands a progress card to the players far enough along its track for the red die (the
+    /// first of `dice`). Cities collect commodities and aqueducts make up for an empty roll.
+    pub fn roll(&mut self, state: &mut GameState, dice: (u8, u8), event: EventDie) -> Vec<Event> {
+        let mut events = vec![Event::Expansion(ExpansionEvent::RolledEventDie(event))];
+        match event {
+            EventDie::Barbarians => events.extend(self.advance_barbarians(state)),
+            EventDie::Gate(improvement) => {
+                let players = state.players.len() as PlayerId;
+                for turn in 0..players {
+                    let player = (state.current() + turn) % players;
+                    let level = self.level(player, improvement);
+                    if level > 0 && dice.0 <= level + 1 {
+                        events.extend(self.draw_progress(state, player, improvement));
+                    }
+                }
+            }
+        }
+        events.extend(state.roll_with(&self.config, dice, |state, total| {
+            let mut events = state.produce_with(total, city_cards);
+            let owed = (0..state.players.len() as PlayerId)
+                .filter(|player| self.has_ability(*player, Improvement::Science))
+                .filter(|player| {
+                    !events.iter().any(
+                        |event| matches!(event, Event::Produced { player: p, .. } if p == player),
+                    )
+                })
+                .map(|player| (player, 1))
+                .collect();
+            events.extend(state.owe_resources(owed));
+            events
+        }));
+        self.spare_walled(state, &mut events);
+        events
+    }
+
+    /// Players whose walls raise their discard limit above their hand don't discard on a 7
+    fn spare_walled(&self, state: &mut GameState, events: &mut Vec<Event>) {
+        let Phase::Discard(owing) = state.phase() else {
+            return;
+        };
+        let mut owing = owing.clone();
+        owing.retain(|player, _| {
+            state.player(*player).hand.total()
+                > self.config.hand_limit + self.walls(*player) * WALL_HAND_LIMIT
+        });
+        events.retain(|event| match event {
+            Event::MustDiscard { player, .. } => owing.contains_key(player),
+            _ => true,
+        });
+        state.set_phase(if owing.is_empty() {
+            Phase::Robber
+        } else {
+            Phase::Discard(owing)
+        });
+    }
+
+    fn advance_barbarians(&mut self, state: &mut GameState) -> Vec<Event> {
+        self.barbarians += 1;
+        let mut events = vec![Event::BarbariansAdvanced {
+            position: self.barbarians,
+        }];
+        if self.barbarians >= BARBARIAN_TRACK {
+            events.extend(self.barbarian_attack(state));
+            self.barbarians = 0;
+        }
+        events
+    }
+
+    /// The barbarians are as strong as there are cities. Beaten, the player with the most
+    /// active knights becomes Defender of Catan. Winning, they pillage a city of the weakest
+    /// defenders among the players with a city that isn't a metropolis. Knights then go home.
+    fn barbarian_attack(&mut self, state: &mut GameState) -> Vec<Event> {
+        let players = 0..state.players.len() as PlayerId;
+        let cities = |player: PlayerId| -> Vec<Intersection> {
+            state
+                .board
+                .buildings()
+                .iter()
+                .filter_map(|building| match building {
+                    Structure::City {
+                        intersection,
+                        owner,
+                    } if *owner == player => Some(*intersection),
+                    _ => None,
+                })
+                .collect()
+        };
+        let barbarians: u32 = players
+            .clone()
+            .map(|player| cities(player).len() as u32)
+            .sum();
+        let defense: BTreeMap<PlayerId, u32> = players
+            .clone()
+            .map(|player| (player, knight_strength(state, player)))
+            .collect();
+        let knights = defense.values().sum();
+        let mut events = vec![Event::BarbariansAttacked {
+            barbarians,
+            knights,
+        }];
+        if knights >= barbarians {
+            let best = defense.values().copied().max().unwrap_or(0);
+            let defenders: Vec<PlayerId> = players.filter(|p| defense[p] == best).collect();
+            // Tied defenders each draw a progress card of their choice instead
+            if let [player] = defenders[..] {
+                *self.defenders.entry(player).or_insert(0) += 1;
+                events.push(Event::DefenderOfCatan { player });
+            } else {
+                self.owed_progress.extend(defenders);
+            }
+        } else {
+            let exposed: Vec<(PlayerId, Intersection)> = players
+                .filter_map(|player| {
+                    cities(player)
+                        .into_iter()
+                        .find(|city| !self.metropolises.values().any(|(_, m)| m == city))
+                        .map(|city| (player, city))
+                })
+                .collect();
+            let weakest = exposed.iter().map(|(player, _)| defense[player]).min();
+            for (player, intersection) in exposed {
+                if Some(defense[&player]) != weakest {
+                    continue;
+                }
+                state.board.remove(&Structure::City {
+                    intersection,
+                    owner: player,
+                });
+                state.board.build(Structure::Settlement {
+                    intersection,
+                    owner: player,
+                });
+                events.push(Event::Pillaged {
+                    player,
+                    intersection,
+                });
+                if self.walls.contains(&(player, intersection)) {
+                    self.walls.retain(|wall| *wall != (player, intersection));
+                    events.push(Event::CityWall {
+                        player,
+                        intersection,
+                        built: false,
+                    });
+                }
+            }
+        }
+        let knights: Vec<Structure> = state.board.buildings().to_vec();
+        for structure in knights {
+            if let Structure::Knight {
+                intersection,
+                owner,
+                strength,
+                active: true,
+            } = structure
+            {
+                state
+                    .board
+                    .build(knight(intersection, owner, strength, false));
+            }
+        }
+        events
+    }
+
+    /// Hire a basic knight next to one of the player's roads
+    pub fn build_knight(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        intersection: Intersection,
+    ) -> Result<Vec<Event>, RuleError> {
+        if !is_free(state, intersection) {
+            return Err(RuleError::Occupied);
+        }
+        if !intersection
+            .edges()
+            .iter()
+            .any(|edge| owns_road(state, player, *edge))
+        {
+            return Err(RuleError::NotConnected);
+        }
+        check_knights(state, player, 1)?;
+        state.pay(player, &self.config.cost(Build::Knight))?;
+        let hired = knight(intersection, player, 1, false);
+        state.board.build(hired);
+        Ok(vec![Event::Built(hired)])
+    }
+
+    /// Make a knight one stronger, mighty knights need the fortress
+    pub fn promote_knight(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        intersection: Intersection,
+    ) -> Result<Vec<Event>, RuleError> {
+        let cost = self.config.cost(Build::Knight);
+        self.promote(state, player, intersection, &cost)
+    }
+
+    fn promote(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        intersection: Intersection,
+        cost: &Hand,
+    ) -> Result<Vec<Event>, RuleError> {
+        let (strength, active) = own_knight(state, player, intersection)?;
+        if self.promoted.contains(&intersection) {
+            return Err(RuleError::OncePerTurn);
+        }
+        if strength >= MIGHTY {
+            return Err(RuleError::InvalidTarget);
+        }
+        if strength + 1 == MIGHTY && !self.has_ability(player, Improvement::Politics) {
+            return Err(RuleError::NeedsImprovement);
+        }
+        check_knights(state, player, strength + 1)?;
+        state.pay(player, cost)?;
+        let promoted = knight(intersection, player, strength + 1, active);
+        state.board.build(promoted);
+        self.promoted.push(intersection);
+        Ok(vec![Event::KnightChanged(promoted)])
+    }
+
+    /// Feed a knight a grain so it defends and acts
+    pub fn activate_knight(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        intersection: Intersection,
+    ) -> Result<Vec<Event>, RuleError> {
+        let (strength, active) = own_knight(state, player, intersection)?;
+        if active {
+            return Err(RuleError::InvalidTarget);
+        }
+        state.pay(player, &Hand::of(&[(ResourceCard::Wheat, 1)]))?;
+        let activated = knight(intersection, player, strength, true);
+        state.board.build(activated);
+        self.activated.push(intersection);
+        Ok(vec![Event::KnightChanged(activated)])
+    }
+
+    /// Strength of an active knight that wasn't activated this turn
+    fn ready_knight(
+        &self,
+        state: &GameState,
+        player: PlayerId,
+        intersection: Intersection,
+    ) -> Result<u8, RuleError> {
+        let (strength, active) = own_knight(state, player, intersection)?;
+        if !active {
+            return Err(RuleError::InvalidTarget);
+        }
+        if self.activated.contains(&intersection) {
+            return Err(RuleError::OncePerTurn);
+        }
+        Ok(strength)
+    }
+
+    /// Move a knight along the player's roads. A weaker opponent knight at the end is pushed
+    /// to a free intersection along its owner's roads, or off the board.
+    pub fn move_knight(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        from: Intersection,
+        to: Intersection,
+    ) -> Result<Vec<Event>, RuleError> {
+        let strength = self.ready_knight(state, player, from)?;
+        if !reachable(state, player, from).contains(&to) {
+            return Err(RuleError::NotConnected);
+        }
+        if state.board.building_at(to).is_some() {
+            return Err(RuleError::Occupied);
+        }
+        let displaced = match state.board.knight_at(to) {
+            Some(
+                other @ Structure::Knight {
+                    owner,
+                    strength: weaker,
+                    ..
+                },
+            ) if *owner != player && *weaker < strength => Some(*other),
+            Some(_) => return Err(RuleError::Occupied),
+            None => None,
+        };
+        state.board.remove(&knight(from, player, strength, true));
+        let mut events = vec![Event::KnightMoved { player, from, to }];
+        if let Some(other) = displaced {
+            state.board.remove(&other);
+        }
+        state.board.build(knight(to, player, strength, false));
+        events.extend(displaced.and_then(|other| displace(state, other)));
+        Ok(events)
+    }
+
+    /// A knight on a corner of the robber's hex chases it away
+    pub fn chase_robber(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        intersection: Intersection,
+        hex: HexCoord,
+        victim: Option<PlayerId>,
+    ) -> Result<Vec<Event>, RuleError> {
+        let strength = self.ready_knight(state, player, intersection)?;
+        if !state
+            .board
+            .robber()
+            .is_some_and(|robber| intersection.hexes().contains(&robber))
+        {
+            return Err(RuleError::InvalidTarget);
+        }
+        let mut events = state.move_robber(player, hex, victim)?;
+        let resting = knight(intersection, player, strength, false);
+        state.board.build(resting);
+        events.push(Event::KnightChanged(resting));
+        Ok(events)
+    }
+
+    /// Buy the next level of a track. Levels past the ability need a city to hold the
+    /// metropolis, which the first to reach `METROPOLIS_LEVEL` gets and the first to reach
+    /// `MAX_LEVEL` keeps.
+    pub fn improve(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        improvement: Improvement,
+    ) -> Result<Vec<Event>, RuleError> {
+        let cities: Vec<Intersection> = state
+            .board
+            .buildings()
+            .iter()
+            .filter_map(|building| match building {
+                Structure::City {
+                    intersection,
+                    owner,
+                } if *owner == player => Some(*intersection),
+                _ => None,
+            })
+            .collect();
+        if cities.is_empty() {
+            return Err(RuleError::NeedsCity);
+        }
+        let level = self.level(player, improvement) + 1;
+        if level > MAX_LEVEL {
+            return Err(RuleError::InvalidTarget);
+        }
+        let holder = self.metropolis(improvement);
+        let held = holder.is_some_and(|(owner, _)| owner == player);
+        let free_city = cities.into_iter().find(|city| {
+            !self
+                .metropolises
+                .values()
+                .any(|(_, metropolis)| metropolis == city)
+        });
+        if level >= METROPOLIS_LEVEL && !held && free_city.is_none() {
+            return Err(RuleError::NeedsCity);
+        }
+        // A crane played this turn takes one off the price
+        let price = level as u32 - self.crane as u32;
+        state.pay(player, &Hand::of(&[(improvement.commodity(), price)]))?;
+        self.crane = false;
+        self.levels.entry(player).or_insert([0; 3])[improvement.index()] = level;
+        let mut events = vec![Event::Expansion(ExpansionEvent::Improved {
+            player,
+            improvement,
+            level,
+        })];
+        let claims = match holder {
+            _ if held || level < METROPOLIS_LEVEL => false,
+            None => true,
+            Some((owner, _)) => level == MAX_LEVEL && self.level(owner, improvement) < MAX_LEVEL,
+        };
+        if let (true, Some(city)) = (claims, free_city) {
+            self.metropolises.insert(improvement, (player, city));
+            events.push(Event::Expansion(ExpansionEvent::Metropolis {
+                player,
+                improvement,
+            }));
+        }
+        Ok(events)
+    }
+
+    /// Wall one of the player's cities
+    pub fn build_wall(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        intersection: Intersection,
+        cost: &Hand,
+    ) -> Result<Vec<Event>, RuleError> {
+        match state.board.building_at(intersection) {
+            Some(Structure::City { owner, .. }) if *owner == player => (),
+            _ => return Err(RuleError::NeedsCity),
+        }
+        if self.walls.contains(&(player, intersection)) {
+            return Err(RuleError::Occupied);
+        }
+        if self.walls(player) >= self.config.pieces(Build::Wall) {
+            return Err(RuleError::NoPiecesLeft);
+        }
+        state.pay(player, cost)?;
+        self.walls.push((player, intersection));
+        Ok(vec![Event::CityWall {
+            player,
+            intersection,
+            built: true,
+        }])
+    }
+
+    /// Cards given to the bank for one of `give`. Commodities go at 2:1 with a trading house,
+    /// the merchant's resource for its holder and the merchant fleet's for this turn.
+    pub fn trade_rate(&self, state: &GameState, player: PlayerId, give: ResourceCard) -> u32 {
+        let trading_house = give.is_commodity() && self.has_ability(player, Improvement::Trade);
+        let merchant = self.merchant.is_some_and(|(holder, hex)| {
+            holder == player
+                && state.board.hex(hex).and_then(|hex| hex.resource().card()) == Some(give)
+        });
+        let fleet = player == state.current() && self.fleet == Some(give);
+        if trading_house || merchant || fleet {
+            PREFERRED_RATE.min(state.trade_rate(&self.config, player))
+        } else {
+            state.trade_rate(&self.config, player)
+        }
+    }
+
+    fn trade(
+        &self,
+        state: &mut GameState,
+        player: PlayerId,
+        give: ResourceCard,
+        get: ResourceCard,
+    ) -> Result<Vec<Event>, RuleError> {
+        if give == get {
+            return Err(RuleError::InvalidTrade);
+        }
+        let gave = Hand::of(&[(give, self.trade_rate(state, player, give))]);
+        let got = Hand::of(&[(get, 1)]);
+        state.pay(player, &gave)?;
+        state.player_mut(player).hand.add_hand(&got);
+        Ok(vec![Event::Traded { player, gave, got }])
+    }
+}
+
+impl Ruleset for KingdomRules {
+    fn expansion(&self) -> Expansion {
+        Expansion::CitiesAndKnights
+    }
+
+    fn config(&self) -> &RuleConfig {
+        &self.config
+    }
+
+    fn apply(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        action: &Action,
+    ) -> Result<Vec<Event>, RuleError> {
+        if let Action::Expansion(ExpansionAction::DrawProgress(deck)) = action {
+            return self.draw_owed_progress(state, player, *deck);
+        }
+        if player != state.acting() {
+            return state.apply(&self.config, player, action);
+        }
+        match (state.phase(), action) {
+            (Phase::Roll, Action::Roll) => {
+                let dice = state.roll_dice();
+                let event = EventDie::from_face(state.rng().gen_range(0..6));
+                Ok(self.roll(state, dice, event))
+            }
+            (
+                Phase::Roll,
+                Action::Expansion(ExpansionAction::PlayProgress(
+                    progress @ Progress::Alchemist { .. },
+                )),
+            )
+            | (Phase::Main, Action::Expansion(ExpansionAction::PlayProgress(progress))) => {
+                self.play_progress(state, player, progress)
+            }
+            (Phase::Main, Action::BuildWall(intersection)) => {
+                let cost = self.config.cost(Build::Wall);
+                self.build_wall(state, player, *intersection, &cost)
+            }
+            (_, Action::BuildSettlement(intersection))
+                if state.board.knight_at(*intersection).is_some() =>
+            {
+                Err(RuleError::Occupied)
+            }
+            (Phase::Main, Action::Expansion(ExpansionAction::Improve(improvement))) => {
+                self.improve(state, player, *improvement)
+            }
+            (Phase::Main, Action::BuildKnight(intersection)) => {
+                self.build_knight(state, player, *intersection)
+            }
+            (Phase::Main, Action::PromoteKnight(intersection)) => {
+                self.promote_knight(state, player, *intersection)
+            }
+            (Phase::Main, Action::ActivateKnight(intersection)) => {
+                self.activate_knight(state, player, *intersection)
+            }
+            (Phase::Main, Action::MoveKnight { from, to }) => {
+                self.move_knight(state, player, *from, *to)
+            }
+            (
+                Phase::Main,
+                Action::ChaseRobber {
+                    knight,
+                    hex,
+                    victim,
+                },
+            ) => self.chase_robber(state, player, *knight, *hex, *victim),
+            (
+                _,
+                Action::BuildKnight(_)
+                | Action::PromoteKnight(_)
+                | Action::ActivateKnight(_)
+                | Action::MoveKnight { .. }
+                | Action::ChaseRobber { .. }
+                | Action::BuildWall(_)
+                | Action::Expansion(_),
+            ) => Err(RuleError::WrongPhase),
+            (Phase::Main, Action::EndTurn) => {
+                self.promoted.clear();
+                self.activated.clear();
+                self.crane = false;
+                self.fleet = None;
+                state.apply(&self.config, player, action)
+            }
+            (Phase::Main, Action::TradeBank { give, get }) => {
+                self.trade(state, player, *give, *get)
+            }
+            _ => state.apply(&self.config, player, action),
+        }
+    }
+
+    fn legal_builds(&self, state: &GameState, player: PlayerId, build: Build) -> Vec<Structure> {
+        match build {
+            // Knights keep settlements off their intersection
+            Build::Settlement => state
+                .legal_builds(&self.config, player, build)
+                .into_iter()
+                .filter(|settlement| {
+                    settlement
+                        .intersection()
+                        .is_some_and(|spot| state.board.knight_at(spot).is_none())
+                })
+                .collect(),
+            Build::Knight => {
+                if player != state.acting()
+                    || !matches!(state.phase(), Phase::Main)
+                    || check_knights(state, player, 1).is_err()
+                {
+                    return Vec::new();
+                }
+                state
+                    .board
+                    .intersections()
+                    .into_iter()
+                    .filter(|spot| {
+                        is_free(state, *spot)
+                            && spot
+                                .edges()
+                                .iter()
+                                .any(|edge| owns_road(state, player, *edge))
+                    })
+                    .map(|spot| knight(spot, player, 1, false))
+                    .collect()
+            }
+            _ => state.legal_builds(&self.config, player, build),
+        }
+    }
+
+    fn victory_points(&self, state: &GameState, player: PlayerId) -> u32 {
+        let metropolises = self
+            .metropolises
+            .values()
+            .filter(|(owner, _)| *owner == player)
+            .count() as u32;
+        let defender = self.defenders.get(&player).copied().unwrap_or(0);
+        let progress = self.progress_points.get(&player).copied().unwrap_or(0);
+        let merchant = self.merchant.is_some_and(|(holder, _)| holder == player) as u32;
+        state.victory_points(player)
+            + metropolises * METROPOLIS_POINTS
+            + defender
+            + progress
+            + merchant
+    }
+
+    fn progress_cards(&self, player: PlayerId) -> &[ProgressCard] {
+        self.progress
+            .get(&player)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn owed_progress(&self) -> &[PlayerId] {
+        &self.owed_progress
+    }
+}
+
+fn owns_road(state: &GameState, player: PlayerId, edge: Edge) -> bool {
+    matches!(
+        state.board.road_at(edge),
+        Some(Structure::Road { owner, .. }) if *owner == player
+    )
+}
+
+/// Strength of the player's knight on the intersection and whether it is active
+fn own_knight(
+    state: &GameState,
+    player: PlayerId,
+    intersection: Intersection,
+) -> Result<(u8, bool), RuleError> {
+    match state.board.knight_at(intersection) {
+        Some(Structure::Knight {
+            owner,
+            strength,
+            active,
+            ..
+        }) if *owner == player => Ok((*strength, *active)),
+        _ => Err(RuleError::InvalidTarget),
+    }
+}
+
+/// Put a knight taken off its intersection back at a free intersection along its owner's
+/// roads, or leave it off the board
+fn displace(state: &mut GameState, displaced: Structure) -> Option<Event> {
+    let Structure::Knight {
+        intersection,
+        owner,
+        strength,
+        active,
+    } = displaced
+    else {
+        return None;
+    };
+    let retreat = reachable(state, owner, intersection)
+        .into_iter()
+        .find(|spot| is_free(state, *spot));
+    if let Some(retreat) = retreat {
+        state.board.build(knight(retreat, owner, strength, active));
+    }
+    Some(Event::KnightDisplaced {
+        player: owner,
+        from: intersection,
+        to: retreat,
+    })
+}
+
+/// Whether nothing stands on the intersection
+fn is_free(state: &GameState, intersection: Intersection) -> bool {
+    state.board.building_at(intersection).is_none() && state.board.knight_at(intersection).is_none()
+}
+
+fn knight(intersection: Intersection, owner: PlayerId, strength: u8, active: bool) -> Structure {
+    Structure::Knight {
+        intersection,
+        owner,
+        strength,
+        active,
+    }
+}
+
+/// Strength of the player's active knights
+fn knight_strength(state: &GameState, player: PlayerId) -> u32 {
+    state
+        .board
+        .buildings()
+        .iter()
+        .filter_map(|knight| match knight {
+            Structure::Knight {
+                owner,
+                strength,
+                active: true,
+                ..
+            } if *owner == player => Some(*strength as u32),
+            _ => None,
+        })
+        .sum()
+}
+
+fn check_knights(state: &GameState, player: PlayerId, level: u8) -> Result<(), RuleError> {
+    let hired = state
+        .board
+        .buildings()
+        .iter()
+        .filter(|structure| {
+            matches!(structure, Structure::Knight { owner, strength, .. }
+                if *owner == player && *strength == level)
+        })
+        .count() as u32;
+    if hired < KNIGHTS_PER_STRENGTH {
+        Ok(())
+    } else {
+        Err(RuleError::NoPiecesLeft)
+    }
+}
+
+/// Intersections a knight can go to along the player's roads. Opponent buildings and knights
+/// stop the way, knights can still be displaced from where they stand.
+fn reachable(state: &GameState, player: PlayerId, from: Intersection) -> Vec<Intersection> {
+    let mut reached = vec![from];
+    let mut frontier = vec![from];
+    while let Some(at) = frontier.pop() {
+        for edge in at.edges() {
+            let next = edge.other_end(&at);
+            if !owns_road(state, player, edge) || reached.contains(&next) {
+                continue;
+            }
+            reached.push(next);
+            let blocked = state.board.knight_at(next).is_some()
+                || state
+                    .board
+                    .building_at(next)
+                    .is_some_and(|building| building.owner() != player);
+            if !blocked {
+                frontier.push(next);
+            }
+        }
+    }
+    reached.retain(|spot| *spot != from);
+    reached
+}
+
+/// Cities on forests, pastures and mountains take a commodity in place of their second
+/// resource
+fn city_cards(structure: &Structure, resource: &Resource) -> Hand {
+    match (structure, resource.card(), resource.commodity()) {
+        (Structure::City { .. }, Some(card), Some(commodity)) => {
+            Hand::of(&[(card, 1), (commodity, 1)])
+        }
+        (_, Some(card), _) => Hand::of(&[(card, resource.chance() as u32)]),
+        _ => Hand::new(),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::settlers::board::map::Board;
+    use crate::settlers::board::topology::HexCoord;
+    use ResourceCard::*;
+
+    /// One row of forest, pasture and mountains above fields, hills and the desert
+    pub(crate) const KINGDOM: &str = "__FOCM__
+[EXPANSION]
+KINGDOM
+[RESOURCES]
+WO:1, SH:1, OR:1, WH:1, BR:1, DE:1
+[CHANCES]
+8, 8, 9, 8, 6
+[MAP]
+4x5
+WWWWW
+W111W
+W111W
+WWWWW
+[LAYOUT]
+-- -- -- -- --
+-- WO8 SH8 OR9 --
+-- WH8 BR6 DE --
+-- -- -- -- --
+";
+
+    /// Touches the forest, pasture and hills
+    pub(crate) const CITY: Intersection = Intersection::bottom(HexCoord::new(3, 3));
+    /// Touches the pasture, mountains and desert
+    pub(crate) const COAST: Intersection = Intersection::bottom(HexCoord::new(3, 4));
+
+    /// Event die face leaving the barbarians where they are
+    pub(crate) const GATE: EventDie = EventDie::Gate(Improvement::Trade);
+
+    /// Player 0 has a city on the forest, player 1 a settlement by the mountains
+    pub(crate) fn kingdom() -> (KingdomRules, GameState) {
+        let board: Board = KINGDOM.parse().unwrap();
+        let mut state = GameState::new(board, 2, 3);
+        state.board.build(Structure::City {
+            intersection: CITY,
+            owner: 0,
+        });
+        state.board.build(Structure::Settlement {
+            intersection: COAST,
+            owner: 1,
+        });
+        state.set_phase(Phase::Roll);
+        (KingdomRules::new(), state)
+    }
+
+    #[test]
+    fn cities_produce_commodities() {
+        let (mut rules, mut state) = kingdom();
+        rules.roll(&mut state, (4, 4), GATE);
+        assert_eq!(
+            state.player(0).hand,
+            Hand::of(&[(Wood, 1), (Paper, 1), (Sheep, 1), (Cloth, 1)])
+        );
+        assert_eq!(state.player(1).hand, Hand::of(&[(Sheep, 1)]));
+        // Hills give a city two bricks, as in the base game
+        state.player_mut(0).hand = Hand::new();
+        state.set_phase(Phase::Roll);
+        rules.roll(&mut state, (3, 3), GATE);
+        assert_eq!(state.player(0).hand, Hand::of(&[(Brick, 2)]));
+    }
+
+    #[test]
+    fn aqueduct_makes_up_for_nothing() {
+        let (mut rules, mut state) = kingdom();
+        rules.levels.insert(0, [0, 0, ABILITY_LEVEL]);
+        let events = rules.roll(&mut state, (4, 5), GATE);
+        assert!(events.contains(&Event::ResourcesOwed {
+            player: 0,
+            amount: 1
+        }));
+        assert_eq!(
+            rules.apply(
+                &mut state,
+                0,
+                &Action::PickResources(Hand::of(&[(Coin, 1)]))
+            ),
+            Err(RuleError::InvalidPick)
+        );
+        rules
+            .apply(&mut state, 0, &Action::PickResources(Hand::of(&[(Ore, 1)])))
+            .unwrap();
+        assert_eq!(state.phase(), &Phase::Main);
+        // Nothing is owed when the roll produced something
+        state.set_phase(Phase::Roll);
+        rules.roll(&mut state, (4, 4), GATE);
+        assert_eq!(state.phase(), &Phase::Main);
+    }
+
+    #[test]
+    fn improvements_cost_their_level() {
+        let (mut rules, mut state) = kingdom();
+        state.set_phase(Phase::Main);
+        state.player_mut(0).hand = Hand::of(&[(Coin, 3)]);
+        state.player_mut(1).hand = Hand::of(&[(Coin, 3)]);
+        state.end_turn();
+        assert_eq!(
+            rules.apply(
+                &mut state,
+                1,
+                &Action::Expansion(ExpansionAction::Improve(Improvement::Politics))
+            ),
+            Err(RuleError::WrongPhase)
+        );
+        state.set_phase(Phase::Main);
+        assert_eq!(
+            rules.apply(
+                &mut state,
+                1,
+                &Action::Expansion(ExpansionAction::Improve(Improvement::Politics))
+            ),
+            Err(RuleError::NeedsCity)
+        );
+        state.end_turn();
+        state.set_phase(Phase::Main);
+        let improve = Action::Expansion(ExpansionAction::Improve(Improvement::Politics));
+        rules.apply(&mut state, 0, &improve).unwrap();
+        rules.apply(&mut state, 0, &improve).unwrap();
+        assert_eq!(rules.level(0, Improvement::Politics), 2);
+        assert_eq!(state.player(0).hand, Hand::new());
+        assert_eq!(
+            rules.apply(&mut state, 0, &improve),
+            Err(RuleError::CannotAfford)
+        );
+        assert!(!rules.has_ability(0, Improvement::Politics));
+    }
+
+    #[test]
+    fn metropolis_goes_to_the_first_at_the_top() {
+        let (mut rules, mut state) = kingdom();
+        state.set_phase(Phase::Main);
+        state.board.build(Structure::City {
+            intersection: COAST,
+            owner: 1,
+        });
+        state.player_mut(0).hand = Hand::of(&[(Paper, 15)]);
+        rules.levels.insert(1, [0, 0, ABILITY_LEVEL]);
+        let improve = Action::Expansion(ExpansionAction::Improve(Improvement::Science));
+        for _ in 0..ABILITY_LEVEL {
+            rules.apply(&mut state, 0, &improve).unwrap();
+        }
+        let events = rules.apply(&mut state, 0, &improve).unwrap();
+        assert!(
+            events.contains(&Event::Expansion(ExpansionEvent::Metropolis {
+                player: 0,
+                improvement: Improvement::Science
+            }))
+        );
+        assert_eq!(rules.metropolis(Improvement::Science), Some((0, CITY)));
+        assert_eq!(
+            rules.victory_points(&state, 0),
+            state.victory_points(0) + METROPOLIS_POINTS
+        );
+        // Player 1 catches up and takes it by reaching the last level first
+        state.end_turn();
+        state.set_phase(Phase::Main);
+        state.player_mut(1).hand = Hand::of(&[(Paper, 9)]);
+        let events = rules.apply(&mut state, 1, &improve).unwrap();
+        assert_eq!(events.len(), 1);
+        rules.apply(&mut state, 1, &improve).unwrap();
+        assert_eq!(rules.metropolis(Improvement::Science), Some((1, COAST)));
+        assert_eq!(rules.victory_points(&state, 0), state.victory_points(0));
+        assert_eq!(
+            rules.apply(&mut state, 1, &improve),
+            Err(RuleError::InvalidTarget)
+        );
+        // Without another city the player can't go past the ability level elsewhere
+        state.player_mut(1).hand = Hand::of(&[(Cloth, 10)]);
+        rules.levels.insert(1, [ABILITY_LEVEL, 0, MAX_LEVEL]);
+        assert_eq!(
+            rules.apply(
+                &mut state,
+                1,
+                &Action::Expansion(ExpansionAction::Improve(Improvement::Trade))
+            ),
+            Err(RuleError::NeedsCity)
+        );
+    }
+
+    #[test]
+    fn trading_house_trades_commodities() {
+        let (mut rules, mut state) = kingdom();
+        state.set_phase(Phase::Main);
+        state.player_mut(0).hand = Hand::of(&[(Cloth, 4)]);
+        let trade = Action::TradeBank {
+            give: Cloth,
+            get: Wood,
+        };
+        rules.apply(&mut state, 0, &trade).unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Wood, 1)]));
+        rules.levels.insert(0, [ABILITY_LEVEL, 0, 0]);
+        state.player_mut(0).hand = Hand::of(&[(Cloth, 2)]);
+        rules.apply(&mut state, 0, &trade).unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Wood, 1)]));
+    }
+
+    /// Player 0's road from the city and the intersection at its end
+    pub(crate) fn road(state: &mut GameState, from: Intersection, index: usize) -> Intersection {
+        let edge = from.edges()[index];
+        state.board.build(Structure::Road { edge, owner: 0 });
+        edge.other_end(&from)
+    }
+
+    #[test]
+    fn knights_take_up_building_spots() {
+        let (rules, mut state) = kingdom();
+        state.set_phase(Phase::Main);
+        let spot = road(&mut state, CITY, 0);
+        let index = (0..3)
+            .find(|i| spot.edges()[*i].other_end(&spot) != CITY)
+            .unwrap();
+        let far = road(&mut state, spot, index);
+        let settlements = |state: &GameState| -> Vec<Intersection> {
+            rules
+                .legal_builds(state, 0, Build::Settlement)
+                .iter()
+                .filter_map(Structure::intersection)
+                .collect()
+        };
+        let knights: Vec<Intersection> = rules
+            .legal_builds(&state, 0, Build::Knight)
+            .iter()
+            .filter_map(Structure::intersection)
+            .collect();
+        assert!(knights.contains(&spot) && knights.contains(&far));
+        assert!(!knights.contains(&CITY));
+        assert!(rules.legal_builds(&state, 1, Build::Knight).is_empty());
+        assert!(settlements(&state).contains(&far));
+        state.board.build(knight(far, 1, 1, false));
+        assert!(!settlements(&state).contains(&far));
+        assert!(!rules
+            .legal_builds(&state, 0, Build::Knight)
+            .iter()
+            .any(|knight| knight.intersection() == Some(far)));
+    }
+
+    #[test]
+    fn knights_are_hired_promoted_and_activated() {
+        let (mut rules, mut state) = kingdom();
+        state.set_phase(Phase::Main);
+        state.player_mut(0).hand = Hand::of(&[(Sheep, 3), (Ore, 3), (Wheat, 1)]);
+        let spot = road(&mut state, CITY, 0);
+        assert_eq!(
+            rules.apply(&mut state, 0, &Action::BuildKnight(COAST)),
+            Err(RuleError::Occupied)
+        );
+        let far = spot
+            .neighbors()
+            .into_iter()
+            .find(|neighbor| *neighbor != CITY)
+            .unwrap();
+        assert_eq!(
+            rules.apply(&mut state, 0, &Action::BuildKnight(far)),
+            Err(RuleError::NotConnected)
+        );
+        rules
+            .apply(&mut state, 0, &Action::BuildKnight(spot))
+            .unwrap();
+        assert_eq!(
+            state.player(0).hand,
+            Hand::of(&[(Sheep, 2), (Ore, 2), (Wheat, 1)])
+        );
+        assert_eq!(
+            rules.apply(&mut state, 0, &Action::BuildSettlement(spot)),
+            Err(RuleError::Occupied)
+        );
+        let promote = Action::PromoteKnight(spot);
+        rules.apply(&mut state, 0, &promote).unwrap();
+        assert_eq!(
+            rules.apply(&mut state, 0, &promote),
+            Err(RuleError::OncePerTurn)
+        );
+        rules.apply(&mut state, 0, &Action::EndTurn).unwrap();
+        state.end_turn();
+        state.set_phase(Phase::Main);
+        assert_eq!(
+            rules.apply(&mut state, 0, &promote),
+            Err(RuleError::NeedsImprovement)
+        );
+        rules.levels.insert(0, [0, ABILITY_LEVEL, 0]);
+        rules.apply(&mut state, 0, &promote).unwrap();
+        rules
+            .apply(&mut state, 0, &Action::ActivateKnight(spot))
+            .unwrap();
+        assert_eq!(
+            state.board.knight_at(spot),
+            Some(&knight(spot, 0, MIGHTY, true))
+        );
+        assert_eq!(state.player(0).hand, Hand::new());
+    }
+
+    #[test]
+    fn barbarians_pillage_the_weakest() {
+        let (mut rules, mut state) = kingdom();
+        state.board.build(Structure::City {
+            intersection: COAST,
+            owner: 1,
+        });
+        let spot = road(&mut state, CITY, 0);
+        state.board.build(knight(spot, 0, 1, true));
+        rules.barbarians = BARBARIAN_TRACK - 1;
+        let events = rules.roll(&mut state, (4, 4), EventDie::Barbarians);
+        assert!(events.contains(&Event::BarbariansAttacked {
+            barbarians: 2,
+            knights: 1
+        }));
+        assert!(events.contains(&Event::Pillaged {
+            player: 1,
+            intersection: COAST
+        }));
+        assert!(matches!(
+            state.board.building_at(COAST),
+            Some(Structure::Settlement { .. })
+        ));
+        assert_eq!(
+            state.board.knight_at(spot),
+            Some(&knight(spot, 0, 1, false))
+        );
+        assert_eq!(rules.barbarians(), 0);
+    }
+
+    #[test]
+    fn knights_defend_catan() {
+        let (mut rules, mut state) = kingdom();
+        let spot = road(&mut state, CITY, 0);
+        state.board.build(knight(spot, 0, 2, true));
+        rules.barbarians = BARBARIAN_TRACK - 1;
+        let events = rules.roll(&mut state, (4, 4), EventDie::Barbarians);
+        assert!(events.contains(&Event::DefenderOfCatan { player: 0 }));
+        assert_eq!(rules.victory_points(&state, 0), state.victory_points(0) + 1);
+        // A rolled gate leaves the ship where it is
+        state.set_phase(Phase::Roll);
+        rules.roll(&mut state, (4, 4), GATE);
+        assert_eq!(rules.barbarians(), 0);
+    }
+
+    #[test]
+    fn knights_move_displace_and_chase() {
+        let (mut rules, mut state) = kingdom();
+        state.set_phase(Phase::Main);
+        let first = road(&mut state, CITY, 0);
+        let index = first
+            .edges()
+            .iter()
+            .position(|edge| !edge.ends().contains(&CITY))
+            .unwrap();
+        let second = road(&mut state, first, index);
+        state.board.build(knight(first, 0, 2, true));
+        state.board.build(knight(second, 1, 1, true));
+        let events = rules
+            .apply(
+                &mut state,
+                0,
+                &Action::MoveKnight {
+                    from: first,
+                    to: second,
+                },
+            )
+            .unwrap();
+        // Player 1 has no roads to retreat along
+        assert!(events.contains(&Event::KnightDisplaced {
+            player: 1,
+            from: second,
+            to: None
+        }));
+        assert_eq!(
+            state.board.knight_at(second),
+            Some(&knight(second, 0, 2, false))
+        );
+        assert!(state.board.knight_at(first).is_none());
+        // A knight activated this turn waits for the next one
+        state.player_mut(0).hand = Hand::of(&[(Wheat, 1)]);
+        rules
+            .apply(&mut state, 0, &Action::ActivateKnight(second))
+            .unwrap();
+        let land: Vec<HexCoord> = second
+            .hexes()
+            .into_iter()
+            .filter(|hex| state.board.is_land(*hex))
+            .collect();
+        state.board.move_robber(land[0]);
+        let chase = Action::ChaseRobber {
+            knight: second,
+            hex: land[1],
+            victim: None,
+        };
+        assert_eq!(
+            rules.apply(&mut state, 0, &chase),
+            Err(RuleError::OncePerTurn)
+        );
+        rules.activated.clear();
+        rules.apply(&mut state, 0, &chase).unwrap();
+        assert_eq!(state.board.robber(), Some(land[1]));
+        assert_eq!(
+            state.board.knight_at(second),
+            Some(&knight(second, 0, 2, false))
+        );
+    }
+}
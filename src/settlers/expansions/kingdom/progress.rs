@@ -0,0 +1,1360 @@
+//! Progress cards. Each improvement track has a deck of 18, a player far enough along a track
+//! draws from its deck when the event die shows its city gate. Cards are played during the
+//! player's turn with the choices they ask for, then go back under their deck.
+
+use super::{
+    check_knights, displace, is_free, knight, own_knight, owns_road, Improvement, KingdomRules,
+    MIGHTY,
+};
+use crate::settlers::board::building::Structure;
+use crate::settlers::board::card::ResourceCard;
+use crate::settlers::board::topology::{Edge, HexCoord, Intersection};
+use crate::settlers::expansions::{ExpansionEvent, Ruleset};
+use crate::settlers::rules::player::{Hand, PlayerId};
+use crate::settlers::rules::{Build, Event, GameState, Phase, RuleError};
+use rand::Rng;
+
+/// Progress cards a player may hold, victory point cards don't count as they are played at once
+pub const HAND_LIMIT: usize = 4;
+/// Resources a resource monopoly takes from each opponent
+const MONOPOLY_RESOURCES: u32 = 2;
+/// Commodities a trade monopoly takes from each opponent
+const MONOPOLY_COMMODITIES: u32 = 1;
+/// Cards irrigation and mining give for each fields or mountains hex
+const HARVEST: u32 = 2;
+/// Cards given by each richer player at a wedding, and taken by a master merchant
+const GIFTS: u32 = 2;
+/// Numbers the inventor can't move
+const FIXED_CHANCES: [u8; 4] = [2, 6, 8, 12];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ProgressCard {
+    Alchemist,
+    Crane,
+    Engineer,
+    Inventor,
+    Irrigation,
+    Medicine,
+    Mining,
+    Printer,
+    RoadBuilding,
+    Smith,
+    CommercialHarbor,
+    MasterMerchant,
+    Merchant,
+    MerchantFleet,
+    ResourceMonopoly,
+    TradeMonopoly,
+    Bishop,
+    Constitution,
+    Deserter,
+    Diplomat,
+    Intrigue,
+    Saboteur,
+    Spy,
+    Warlord,
+    Wedding,
+}
+
+impl ProgressCard {
+    pub const ALL: [ProgressCard; 25] = [
+        ProgressCard::Alchemist,
+        ProgressCard::Crane,
+        ProgressCard::Engineer,
+        ProgressCard::Inventor,
+        ProgressCard::Irrigation,
+        ProgressCard::Medicine,
+        ProgressCard::Mining,
+        ProgressCard::Printer,
+        ProgressCard::RoadBuilding,
+        ProgressCard::Smith,
+        ProgressCard::CommercialHarbor,
+        ProgressCard::MasterMerchant,
+        ProgressCard::Merchant,
+        ProgressCard::MerchantFleet,
+        ProgressCard::ResourceMonopoly,
+        ProgressCard::TradeMonopoly,
+        ProgressCard::Bishop,
+        ProgressCard::Constitution,
+        ProgressCard::Deserter,
+        ProgressCard::Diplomat,
+        ProgressCard::Intrigue,
+        ProgressCard::Saboteur,
+        ProgressCard::Spy,
+        ProgressCard::Warlord,
+        ProgressCard::Wedding,
+    ];
+
+    /// Track whose deck holds the card
+    pub fn deck(&self) -> Improvement {
+        use ProgressCard::*;
+        match self {
+            Alchemist | Crane | Engineer | Inventor | Irrigation | Medicine | Mining | Printer
+            | RoadBuilding | Smith => Improvement::Science,
+            CommercialHarbor | MasterMerchant | Merchant | MerchantFleet | ResourceMonopoly
+            | TradeMonopoly => Improvement::Trade,
+            Bishop | Constitution | Deserter | Diplomat | Intrigue | Saboteur | Spy | Warlord
+            | Wedding => Improvement::Politics,
+        }
+    }
+
+    /// Copies of the card in its deck
+    pub fn copies(&self) -> usize {
+        use ProgressCard::*;
+        match self {
+            Engineer | Printer | Constitution => 1,
+            Spy => 3,
+            ResourceMonopoly => 4,
+            Merchant => 6,
+            _ => 2,
+        }
+    }
+
+    /// Printer and Constitution are worth a victory point and played as soon as drawn
+    pub fn is_victory_point(&self) -> bool {
+        matches!(self, ProgressCard::Printer | ProgressCard::Constitution)
+    }
+}
+
+/// Every card of a track's deck
+pub fn deck(improvement: Improvement) -> Vec<ProgressCard> {
+    ProgressCard::ALL
+        .into_iter()
+        .filter(|card| card.deck() == improvement)
+        .flat_map(|card| vec![card; card.copies()])
+        .collect()
+}
+
+/// A progress card being played with the choices it asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progress {
+    /// Roll the dice as chosen, played instead of rolling
+    Alchemist { dice: (u8, u8) },
+    /// The next improvement this turn costs a commodity less
+    Crane,
+    /// A free city wall
+    Engineer(Intersection),
+    /// Swap the numbers of two hexes, a 2, 6, 8 or 12 stays where it is
+    Inventor(HexCoord, HexCoord),
+    /// Two grain for each fields hex next to the player's buildings
+    Irrigation,
+    /// Upgrade a settlement to a city for two ore and a grain
+    Medicine(Intersection),
+    /// Two ore for each mountains hex next to the player's buildings
+    Mining,
+    /// Two free roads
+    RoadBuilding(Edge, Edge),
+    /// Promote up to two knights for free
+    Smith(Vec<Intersection>),
+    /// Give each opponent holding commodities one of the resource for the commodity they
+    /// hold most of
+    CommercialHarbor(ResourceCard),
+    /// Take two cards of choice from a player with more victory points
+    MasterMerchant { victim: PlayerId, cards: Hand },
+    /// Put the merchant next to one of the player's buildings, its resource trades at 2:1
+    Merchant(HexCoord),
+    /// Trade the resource or commodity at 2:1 this turn
+    MerchantFleet(ResourceCard),
+    /// Up to two of the resource from each opponent
+    ResourceMonopoly(ResourceCard),
+    /// One of the commodity from each opponent
+    TradeMonopoly(ResourceCard),
+    /// Move the robber and rob every player next to it
+    Bishop(HexCoord),
+    /// Swap an opponent's knight for one of the player's of the same strength, placed at `to`
+    Deserter {
+        knight: Intersection,
+        to: Intersection,
+    },
+    /// Remove an open road, the player may rebuild their own at `to`
+    Diplomat { road: Edge, to: Option<Edge> },
+    /// Displace an opponent's knight standing on the player's roads
+    Intrigue(Intersection),
+    /// Players with as many victory points or more discard half their cards
+    Saboteur,
+    /// Take a progress card from another player
+    Spy {
+        victim: PlayerId,
+        card: ProgressCard,
+    },
+    /// Activate all the player's knights for free
+    Warlord,
+    /// Players with more victory points give two cards
+    Wedding,
+}
+
+impl Progress {
+    pub fn card(&self) -> ProgressCard {
+        match self {
+            Progress::Alchemist { .. } => ProgressCard::Alchemist,
+            Progress::Crane => ProgressCard::Crane,
+            Progress::Engineer(_) => ProgressCard::Engineer,
+            Progress::Inventor(..) => ProgressCard::Inventor,
+            Progress::Irrigation => ProgressCard::Irrigation,
+            Progress::Medicine(_) => ProgressCard::Medicine,
+            Progress::Mining => ProgressCard::Mining,
+            Progress::RoadBuilding(..) => ProgressCard::RoadBuilding,
+            Progress::Smith(_) => ProgressCard::Smith,
+            Progress::CommercialHarbor(_) => ProgressCard::CommercialHarbor,
+            Progress::MasterMerchant { .. } => ProgressCard::MasterMerchant,
+            Progress::Merchant(_) => ProgressCard::Merchant,
+            Progress::MerchantFleet(_) => ProgressCard::MerchantFleet,
+            Progress::ResourceMonopoly(_) => ProgressCard::ResourceMonopoly,
+            Progress::TradeMonopoly(_) => ProgressCard::TradeMonopoly,
+            Progress::Bishop(_) => ProgressCard::Bishop,
+            Progress::Deserter { .. } => ProgressCard::Deserter,
+            Progress::Diplomat { .. } => ProgressCard::Diplomat,
+            Progress::Intrigue(_) => ProgressCard::Intrigue,
+            Progress::Saboteur => ProgressCard::Saboteur,
+            Progress::Spy { .. } => ProgressCard::Spy,
+            Progress::Warlord => ProgressCard::Warlord,
+            Progress::Wedding => ProgressCard::Wedding,
+        }
+    }
+}
+
+impl KingdomRules {
+    /// Holder of the merchant and the hex it stands on
+    pub fn merchant(&self) -> Option<(PlayerId, HexCoord)> {
+        self.merchant
+    }
+
+    /// Draw a random card from a track's deck. Victory point cards are played at once, a
+    /// card the player has no room for goes back into the deck.
+    pub(super) fn draw_progress(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        improvement: Improvement,
+    ) -> Vec<Event> {
+        let deck = self.decks.entry(improvement).or_default();
+        if deck.is_empty() {
+            return Vec::new();
+        }
+        let card = deck.swap_remove(state.rng().gen_range(0..deck.len()));
+        let hand = self.progress.entry(player).or_default();
+        let mut events = vec![Event::Expansion(ExpansionEvent::ProgressDrawn {
+            player,
+            deck: improvement,
+        })];
+        if card.is_victory_point() {
+            *self.progress_points.entry(player).or_insert(0) += 1;
+            events.push(Event::Expansion(ExpansionEvent::ProgressPlayed {
+                player,
+                card,
+            }));
+        } else if hand.len() < HAND_LIMIT {
+            hand.push(card);
+        } else {
+            deck.push(card);
+            events.clear();
+        }
+        events
+    }
+
+    /// Draw a card the player was owed from the deck of their choice
+    pub(super) fn draw_owed_progress(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        improvement: Improvement,
+    ) -> Result<Vec<Event>, RuleError> {
+        let Some(index) = self.owed_progress.iter().position(|owed| *owed == player) else {
+            return Err(RuleError::WrongPhase);
+        };
+        self.owed_progress.remove(index);
+        Ok(self.draw_progress(state, player, improvement))
+    }
+
+    /// Play a card from the player's hand. It goes back under its deck once played, or back to
+    /// the hand when it can't be played as asked.
+    pub(super) fn play_progress(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        progress: &Progress,
+    ) -> Result<Vec<Event>, RuleError> {
+        let card = progress.card();
+        let hand = self.progress.entry(player).or_default();
+        let index = hand
+            .iter()
+            .position(|held| *held == card)
+            .ok_or(RuleError::NotInHand)?;
+        hand.remove(index);
+        match self.resolve(state, player, progress) {
+            Ok(mut events) => {
+                self.decks.entry(card.deck()).or_default().push(card);
+                events.insert(
+                    0,
+                    Event::Expansion(ExpansionEvent::ProgressPlayed { player, card }),
+                );
+                Ok(events)
+            }
+            Err(error) => {
+                self.progress.entry(player).or_default().insert(index, card);
+                Err(error)
+            }
+        }
+    }
+
+    fn resolve(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        progress: &Progress,
+    ) -> Result<Vec<Event>, RuleError> {
+        match progress {
+            Progress::Alchemist { .. } if state.phase() != &Phase::Roll => {
+                Err(RuleError::WrongPhase)
+            }
+            Progress::Alchemist { dice } => {
+                if ![dice.0, dice.1].iter().all(|die| (1..=6).contains(die)) {
+                    return Err(RuleError::InvalidTarget);
+                }
+                let event = super::EventDie::from_face(state.rng().gen_range(0..6));
+                Ok(self.roll(state, *dice, event))
+            }
+            Progress::Crane => {
+                self.crane = true;
+                Ok(Vec::new())
+            }
+            Progress::Engineer(intersection) => {
+                self.build_wall(state, player, *intersection, &Hand::new())
+            }
+            Progress::Inventor(first, second) => inventor(state, *first, *second),
+            Progress::Irrigation => Ok(harvest(state, player, ResourceCard::Wheat)),
+            Progress::Mining => Ok(harvest(state, player, ResourceCard::Ore)),
+            Progress::Medicine(intersection) => self.medicine(state, player, *intersection),
+            Progress::RoadBuilding(first, second) => {
+                self.road_building(state, player, [*first, *second])
+            }
+            Progress::Smith(knights) => self.smith(state, player, knights),
+            Progress::CommercialHarbor(resource) => commercial_harbor(state, player, *resource),
+            Progress::MasterMerchant { victim, cards } => {
+                if *victim == player
+                    || self.victory_points(state, *victim) <= self.victory_points(state, player)
+                    || cards.total() != GIFTS
+                {
+                    return Err(RuleError::InvalidTarget);
+                }
+                state.pay(*victim, cards)?;
+                Ok(vec![give(state, *victim, player, cards.clone())])
+            }
+            Progress::Merchant(hex) => {
+                let next_to_building = hex.corners().iter().any(|corner| {
+                    state
+                        .board
+                        .building_at(*corner)
+                        .is_some_and(|building| building.owner() == player)
+                });
+                if !state
+                    .board
+                    .hex(*hex)
+                    .is_some_and(|tile| tile.is_land() && tile.resource().card().is_some())
+                {
+                    return Err(RuleError::InvalidTarget);
+                }
+                if !next_to_building {
+                    return Err(RuleError::NotConnected);
+                }
+                self.merchant = Some((player, *hex));
+                Ok(vec![Event::MerchantMoved { player, hex: *hex }])
+            }
+            Progress::MerchantFleet(card) => {
+                self.fleet = Some(*card);
+                Ok(Vec::new())
+            }
+            Progress::ResourceMonopoly(card) if !card.is_commodity() => {
+                Ok(monopoly(state, player, *card, MONOPOLY_RESOURCES))
+            }
+            Progress::TradeMonopoly(card) if card.is_commodity() => {
+                Ok(monopoly(state, player, *card, MONOPOLY_COMMODITIES))
+            }
+            Progress::ResourceMonopoly(_) | Progress::TradeMonopoly(_) => {
+                Err(RuleError::InvalidTarget)
+            }
+            Progress::Bishop(hex) => {
+                if !state.board.is_land(*hex) || state.board.robber() == Some(*hex) {
+                    return Err(RuleError::InvalidTarget);
+                }
+                let victims = state.robbable(player, *hex);
+                state.board.move_robber(*hex);
+                let mut events = vec![Event::RobberMoved { hex: *hex }];
+                for victim in victims {
+                    events.extend(state.steal(player, victim));
+                }
+                Ok(events)
+            }
+            Progress::Deserter { knight, to } => self.deserter(state, player, *knight, *to),
+            Progress::Diplomat { road, to } => self.diplomat(state, player, *road, *to),
+            Progress::Intrigue(intersection) => {
+                let Some(other) = state
+                    .board
+                    .knight_at(*intersection)
+                    .filter(|other| other.owner() != player)
+                    .copied()
+                else {
+                    return Err(RuleError::InvalidTarget);
+                };
+                if !intersection
+                    .edges()
+                    .iter()
+                    .any(|edge| owns_road(state, player, *edge))
+                {
+                    return Err(RuleError::NotConnected);
+                }
+                state.board.remove(&other);
+                Ok(displace(state, other).into_iter().collect())
+            }
+            Progress::Saboteur => {
+                let points = self.victory_points(state, player);
+                let targets: Vec<PlayerId> = (0..state.players.len() as PlayerId)
+                    .filter(|other| *other != player)
+                    .filter(|other| self.victory_points(state, *other) >= points)
+                    .collect();
+                Ok(targets
+                    .into_iter()
+                    .filter_map(|target| {
+                        let half = state.player(target).hand.total() / 2;
+                        let cards = take_random(state, target, half);
+                        (!cards.is_empty()).then_some(Event::Discarded {
+                            player: target,
+                            cards,
+                        })
+                    })
+                    .collect())
+            }
+            Progress::Spy { victim, card } => {
+                if *victim == player {
+                    return Err(RuleError::InvalidTarget);
+                }
+                let held = self.progress.entry(*victim).or_default();
+                let Some(index) = held.iter().position(|held| held == card) else {
+                    return Err(RuleError::NotInHand);
+                };
+                held.remove(index);
+                self.progress.entry(player).or_default().push(*card);
+                Ok(vec![Event::Expansion(ExpansionEvent::ProgressStolen {
+                    thief: player,
+                    victim: *victim,
+                    card: *card,
+                })])
+            }
+            Progress::Warlord => {
+                let resting: Vec<Structure> = state
+                    .board
+                    .buildings()
+                    .iter()
+                    .filter(|structure| {
+                        matches!(structure, Structure::Knight { owner, active: false, .. }
+                            if *owner == player)
+                    })
+                    .copied()
+                    .collect();
+                let mut events = Vec::new();
+                for resting in resting {
+                    let Structure::Knight {
+                        intersection,
+                        strength,
+                        ..
+                    } = resting
+                    else {
+                        continue;
+                    };
+                    let activated = knight(intersection, player, strength, true);
+                    state.board.build(activated);
+                    self.activated.push(intersection);
+                    events.push(Event::KnightChanged(activated));
+                }
+                Ok(events)
+            }
+            Progress::Wedding => {
+                let points = self.victory_points(state, player);
+                let richer: Vec<PlayerId> = (0..state.players.len() as PlayerId)
+                    .filter(|other| self.victory_points(state, *other) > points)
+                    .collect();
+                Ok(richer
+                    .into_iter()
+                    .filter_map(|other| {
+                        let cards = take_random(state, other, GIFTS);
+                        (!cards.is_empty()).then(|| give(state, other, player, cards))
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    fn medicine(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        intersection: Intersection,
+    ) -> Result<Vec<Event>, RuleError> {
+        match state.board.building_at(intersection) {
+            Some(Structure::Settlement { owner, .. }) if *owner == player => (),
+            _ => return Err(RuleError::InvalidTarget),
+        }
+        state.check_pieces(&self.config, player, Build::City)?;
+        state.pay(
+            player,
+            &Hand::of(&[(ResourceCard::Ore, 2), (ResourceCard::Wheat, 1)]),
+        )?;
+        state.build(Structure::City {
+            intersection,
+            owner: player,
+        })
+    }
+
+    /// Build both roads or neither
+    fn road_building(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        edges: [Edge; 2],
+    ) -> Result<Vec<Event>, RuleError> {
+        let mut built = Vec::new();
+        for edge in edges {
+            let road = Structure::Road {
+                edge,
+                owner: player,
+            };
+            if let Err(error) = state.check_road(&self.config, player, edge) {
+                for road in built {
+                    state.board.remove(&road);
+                }
+                return Err(error);
+            }
+            state.board.build(road);
+            built.push(road);
+        }
+        let mut events: Vec<Event> = built.into_iter().map(Event::Built).collect();
+        events.extend(state.update_longest_road());
+        Ok(events)
+    }
+
+    /// Promote one or two different knights for free
+    fn smith(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        knights: &[Intersection],
+    ) -> Result<Vec<Event>, RuleError> {
+        if knights.is_empty() || knights.len() > 2 || knights.first() == knights.get(1) {
+            return Err(RuleError::InvalidTarget);
+        }
+        // Check both before promoting either
+        for intersection in knights {
+            let (strength, _) = own_knight(state, player, *intersection)?;
+            if strength >= MIGHTY || self.promoted.contains(intersection) {
+                return Err(RuleError::InvalidTarget);
+            }
+        }
+        let mut events = Vec::new();
+        for intersection in knights {
+            events.extend(self.promote(state, player, *intersection, &Hand::new())?);
+        }
+        Ok(events)
+    }
+
+    fn deserter(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        at: Intersection,
+        to: Intersection,
+    ) -> Result<Vec<Event>, RuleError> {
+        let deserting = match state.board.knight_at(at) {
+            Some(other @ Structure::Knight { owner, .. }) if *owner != player => *other,
+            _ => return Err(RuleError::InvalidTarget),
+        };
+        let Structure::Knight { strength, .. } = deserting else {
+            return Err(RuleError::InvalidTarget);
+        };
+        if !is_free(state, to) {
+            return Err(RuleError::Occupied);
+        }
+        if !to
+            .edges()
+            .iter()
+            .any(|edge| owns_road(state, player, *edge))
+        {
+            return Err(RuleError::NotConnected);
+        }
+        check_knights(state, player, strength)?;
+        state.board.remove(&deserting);
+        let joined = knight(to, player, strength, false);
+        state.board.build(joined);
+        Ok(vec![Event::Removed(deserting), Event::Built(joined)])
+    }
+
+    /// Take an open road off the board, one with an end that leads nowhere. The player's own
+    /// road may be rebuilt elsewhere.
+    fn diplomat(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        edge: Edge,
+        to: Option<Edge>,
+    ) -> Result<Vec<Event>, RuleError> {
+        let road = match state.board.road_at(edge) {
+            Some(road @ Structure::Road { .. }) => *road,
+            _ => return Err(RuleError::InvalidTarget),
+        };
+        let owner = road.owner();
+        let leads_on = |end: &Intersection| {
+            state
+                .board
+                .building_at(*end)
+                .is_some_and(|building| building.owner() == owner)
+                || end
+                    .edges()
+                    .iter()
+                    .any(|other| *other != edge && owns_road(state, owner, *other))
+        };
+        let open = !edge.ends().iter().all(leads_on);
+        if !open || (to.is_some() && owner != player) {
+            return Err(RuleError::InvalidTarget);
+        }
+        state.board.remove(&road);
+        let mut events = vec![Event::Removed(road)];
+        if let Some(to) = to {
+            if let Err(error) = state.check_road(&self.config, player, to) {
+                state.board.build(road);
+                return Err(error);
+            }
+            let rebuilt = Structure::Road {
+                edge: to,
+                owner: player,
+            };
+            state.board.build(rebuilt);
+            events.push(Event::Built(rebuilt));
+        }
+        events.extend(state.update_longest_road());
+        Ok(events)
+    }
+}
+
+fn inventor(
+    state: &mut GameState,
+    first: HexCoord,
+    second: HexCoord,
+) -> Result<Vec<Event>, RuleError> {
+    let movable = |hex: HexCoord| {
+        state
+            .board
+            .hex(hex)
+            .filter(|tile| tile.is_land())
+            .map(|tile| tile.resource())
+            .filter(|resource| {
+                resource.card().is_some() && !FIXED_CHANCES.contains(&resource.chance())
+            })
+    };
+    let (Some(a), Some(b)) = (movable(first), movable(second)) else {
+        return Err(RuleError::InvalidTarget);
+    };
+    if first == second {
+        return Err(RuleError::InvalidTarget);
+    }
+    for (hex, resource, chance) in [(first, a, b.chance()), (second, b, a.chance())] {
+        if let Some(tile) = state.board.hex_mut(hex) {
+            tile.set_resource(resource.clone_with_value(chance));
+        }
+    }
+    Ok(vec![Event::ChancesSwapped {
+        hexes: (first, second),
+    }])
+}
+
+/// `HARVEST` cards for each hex of the resource next to the player's buildings
+fn harvest(state: &mut GameState, player: PlayerId, card: ResourceCard) -> Vec<Event> {
+    let mut hexes: Vec<HexCoord> = state
+        .board
+        .buildings()
+        .iter()
+        .filter(|building| building.is_building() && building.owner() == player)
+        .filter_map(|building| building.intersection())
+        .flat_map(|intersection| intersection.hexes())
+        .filter(|hex| {
+            state
+                .board
+                .hex(*hex)
+                .is_some_and(|tile| tile.is_land() && tile.resource().card() == Some(card))
+        })
+        .collect();
+    hexes.sort();
+    hexes.dedup();
+    let cards = Hand::of(&[(card, HARVEST * hexes.len() as u32)]);
+    if cards.is_empty() {
+        return Vec::new();
+    }
+    state.player_mut(player).hand.add_hand(&cards);
+    vec![Event::Produced { player, cards }]
+}
+
+fn commercial_harbor(
+    state: &mut GameState,
+    player: PlayerId,
+    resource: ResourceCard,
+) -> Result<Vec<Event>, RuleError> {
+    if resource.is_commodity() {
+        return Err(RuleError::InvalidTrade);
+    }
+    let mut events = Vec::new();
+    for other in 0..state.players.len() as PlayerId {
+        let hand = &state.player(other).hand;
+        let most = ResourceCard::COMMODITIES
+            .into_iter()
+            .filter(|commodity| hand.count(*commodity) > 0)
+            .max_by_key(|commodity| hand.count(*commodity));
+        let Some(commodity) = most.filter(|_| other != player) else {
+            continue;
+        };
+        if state.player(player).hand.count(resource) == 0 {
+            break;
+        }
+        let offered = Hand::of(&[(resource, 1)]);
+        let taken = Hand::of(&[(commodity, 1)]);
+        state.pay(player, &offered)?;
+        state.pay(other, &taken)?;
+        events.push(give(state, player, other, offered));
+        events.push(give(state, other, player, taken));
+    }
+    Ok(events)
+}
+
+/// Take up to `amount` of the card from every opponent
+fn monopoly(
+    state: &mut GameState,
+    player: PlayerId,
+    card: ResourceCard,
+    amount: u32,
+) -> Vec<Event> {
+    (0..state.players.len() as PlayerId)
+        .filter(|other| *other != player)
+        .filter_map(|other| {
+            let taken = state.player_mut(other).hand.remove(card, amount);
+            (taken > 0).then(|| give(state, other, player, Hand::of(&[(card, taken)])))
+        })
+        .collect()
+}
+
+/// Remove up to `amount` random cards from the player's hand
+fn take_random(state: &mut GameState, player: PlayerId, amount: u32) -> Hand {
+    let mut hand = state.player(player).hand.clone();
+    let mut taken = Hand::new();
+    for _ in 0..amount {
+        if let Some(card) = hand.take_random(state.rng()) {
+            taken.add(card, 1);
+        }
+    }
+    state.player_mut(player).hand = hand;
+    taken
+}
+
+/// Hand cards already taken from `from` to `to`
+fn give(state: &mut GameState, from: PlayerId, to: PlayerId, cards: Hand) -> Event {
+    state.player_mut(to).hand.add_hand(&cards);
+    Event::Gave { from, to, cards }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settlers::board::card::Resource;
+    use crate::settlers::expansions::kingdom::tests::{kingdom, road, CITY, COAST, GATE};
+    use crate::settlers::expansions::kingdom::{EventDie, BARBARIAN_TRACK, PREFERRED_RATE};
+    use crate::settlers::expansions::ExpansionAction;
+    use crate::settlers::rules::Action;
+    use ResourceCard::*;
+
+    const PASTURE: HexCoord = HexCoord::new(2, 3);
+    const MOUNTAINS: HexCoord = HexCoord::new(2, 4);
+    const FIELDS: HexCoord = HexCoord::new(3, 2);
+    const DESERT: HexCoord = HexCoord::new(3, 4);
+
+    /// Player 0 in the main phase holding the card
+    fn holding(card: ProgressCard) -> (KingdomRules, GameState) {
+        let (mut rules, mut state) = kingdom();
+        state.set_phase(Phase::Main);
+        rules.progress.insert(0, vec![card]);
+        (rules, state)
+    }
+
+    fn play(
+        rules: &mut KingdomRules,
+        state: &mut GameState,
+        progress: Progress,
+    ) -> Result<Vec<Event>, RuleError> {
+        rules.apply(
+            state,
+            0,
+            &Action::Expansion(ExpansionAction::PlayProgress(progress)),
+        )
+    }
+
+    /// Player 1 gets a city on the coast and a settlement by the fields, outscoring player 0
+    fn enrich(state: &mut GameState) {
+        state.board.build(Structure::City {
+            intersection: COAST,
+            owner: 1,
+        });
+        state.board.build(Structure::Settlement {
+            intersection: FIELDS.corners()[0],
+            owner: 1,
+        });
+    }
+
+    #[test]
+    fn decks_hold_eighteen_cards() {
+        for improvement in Improvement::ALL {
+            let cards = deck(improvement);
+            assert_eq!(cards.len(), 18);
+            assert!(cards.iter().all(|card| card.deck() == improvement));
+            let points = cards.iter().filter(|card| card.is_victory_point()).count();
+            assert_eq!(points, (improvement != Improvement::Trade) as usize);
+        }
+    }
+
+    #[test]
+    fn gates_hand_out_progress_cards() {
+        let (mut rules, mut state) = kingdom();
+        rules.levels.insert(0, [1, 0, 0]);
+        rules
+            .decks
+            .insert(Improvement::Trade, vec![ProgressCard::Merchant; 2]);
+        let events = rules.roll(&mut state, (2, 6), GATE);
+        assert!(
+            events.contains(&Event::Expansion(ExpansionEvent::ProgressDrawn {
+                player: 0,
+                deck: Improvement::Trade
+            }))
+        );
+        assert_eq!(rules.progress_cards(0), &[ProgressCard::Merchant]);
+        assert!(rules.progress_cards(1).is_empty());
+        // The red die is too high for level 1
+        state.set_phase(Phase::Roll);
+        rules.roll(&mut state, (3, 5), GATE);
+        assert_eq!(rules.progress_cards(0).len(), 1);
+    }
+
+    #[test]
+    fn progress_hand_is_limited() {
+        let (mut rules, mut state) = kingdom();
+        rules
+            .progress
+            .insert(0, vec![ProgressCard::Spy; HAND_LIMIT]);
+        rules
+            .decks
+            .insert(Improvement::Trade, vec![ProgressCard::Merchant]);
+        let events = rules.draw_progress(&mut state, 0, Improvement::Trade);
+        assert!(events.is_empty());
+        assert_eq!(rules.progress_cards(0).len(), HAND_LIMIT);
+        assert_eq!(
+            rules.decks[&Improvement::Trade],
+            vec![ProgressCard::Merchant]
+        );
+        // Victory point cards are played at once, whatever the hand
+        rules
+            .decks
+            .insert(Improvement::Politics, vec![ProgressCard::Constitution]);
+        let events = rules.draw_progress(&mut state, 0, Improvement::Politics);
+        assert!(
+            events.contains(&Event::Expansion(ExpansionEvent::ProgressPlayed {
+                player: 0,
+                card: ProgressCard::Constitution
+            }))
+        );
+        assert_eq!(rules.victory_points(&state, 0), state.victory_points(0) + 1);
+    }
+
+    #[test]
+    fn tied_defenders_draw_progress() {
+        let (mut rules, mut state) = kingdom();
+        state.board.build(Structure::City {
+            intersection: COAST,
+            owner: 1,
+        });
+        let spot = road(&mut state, CITY, 0);
+        state.board.build(knight(spot, 0, 1, true));
+        let other = COAST
+            .neighbors()
+            .into_iter()
+            .find(|neighbor| *neighbor != spot)
+            .unwrap();
+        state.board.build(knight(other, 1, 1, true));
+        rules.barbarians = BARBARIAN_TRACK - 1;
+        rules.roll(&mut state, (4, 4), EventDie::Barbarians);
+        let draw = Action::Expansion(ExpansionAction::DrawProgress(Improvement::Politics));
+        let events = rules.apply(&mut state, 1, &draw).unwrap();
+        assert_eq!(
+            events[0],
+            Event::Expansion(ExpansionEvent::ProgressDrawn {
+                player: 1,
+                deck: Improvement::Politics
+            })
+        );
+        assert_eq!(
+            rules.apply(&mut state, 1, &draw),
+            Err(RuleError::WrongPhase)
+        );
+        rules.apply(&mut state, 0, &draw).unwrap();
+    }
+
+    #[test]
+    fn alchemist_chooses_the_dice() {
+        let (mut rules, mut state) = holding(ProgressCard::Alchemist);
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::Alchemist { dice: (4, 4) }),
+            Err(RuleError::WrongPhase)
+        );
+        state.set_phase(Phase::Roll);
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::Alchemist { dice: (7, 1) }),
+            Err(RuleError::InvalidTarget)
+        );
+        assert_eq!(rules.progress_cards(0), &[ProgressCard::Alchemist]);
+        play(&mut rules, &mut state, Progress::Alchemist { dice: (4, 4) }).unwrap();
+        assert_eq!(state.dice(), Some((4, 4)));
+        assert_eq!(state.phase(), &Phase::Main);
+        assert_eq!(
+            state.player(0).hand,
+            Hand::of(&[(Wood, 1), (Paper, 1), (Sheep, 1), (Cloth, 1)])
+        );
+        assert!(rules.progress_cards(0).is_empty());
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::Alchemist { dice: (4, 4) }),
+            Err(RuleError::NotInHand)
+        );
+    }
+
+    #[test]
+    fn crane_discounts_an_improvement() {
+        let (mut rules, mut state) = holding(ProgressCard::Crane);
+        state.player_mut(0).hand = Hand::of(&[(Coin, 1)]);
+        play(&mut rules, &mut state, Progress::Crane).unwrap();
+        let improve = Action::Expansion(ExpansionAction::Improve(Improvement::Politics));
+        rules.apply(&mut state, 0, &improve).unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Coin, 1)]));
+        assert_eq!(
+            rules.apply(&mut state, 0, &improve),
+            Err(RuleError::CannotAfford)
+        );
+    }
+
+    #[test]
+    fn engineer_walls_a_city() {
+        let (mut rules, mut state) = holding(ProgressCard::Engineer);
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::Engineer(COAST)),
+            Err(RuleError::NeedsCity)
+        );
+        play(&mut rules, &mut state, Progress::Engineer(CITY)).unwrap();
+        assert_eq!(rules.walls(0), 1);
+        // The wall spares nine cards on a 7
+        state.player_mut(0).hand = Hand::of(&[(Brick, 9)]);
+        state.player_mut(1).hand = Hand::of(&[(Ore, 8)]);
+        state.set_phase(Phase::Roll);
+        let events = rules.roll(&mut state, (3, 4), GATE);
+        assert!(!events.contains(&Event::MustDiscard {
+            player: 0,
+            amount: 4
+        }));
+        assert_eq!(state.phase(), &Phase::Discard([(1, 4)].into()));
+    }
+
+    #[test]
+    fn inventor_swaps_numbers() {
+        let (mut rules, mut state) = holding(ProgressCard::Inventor);
+        assert_eq!(
+            play(
+                &mut rules,
+                &mut state,
+                Progress::Inventor(MOUNTAINS, FIELDS)
+            ),
+            Err(RuleError::InvalidTarget)
+        );
+        state
+            .board
+            .hex_mut(FIELDS)
+            .unwrap()
+            .set_resource(Resource::Wheat(4));
+        play(
+            &mut rules,
+            &mut state,
+            Progress::Inventor(MOUNTAINS, FIELDS),
+        )
+        .unwrap();
+        assert_eq!(state.board.hex(MOUNTAINS).unwrap().resource().chance(), 4);
+        assert_eq!(state.board.hex(FIELDS).unwrap().resource().chance(), 9);
+    }
+
+    #[test]
+    fn irrigation_waters_fields() {
+        let (mut rules, mut state) = holding(ProgressCard::Irrigation);
+        state.board.build(Structure::Settlement {
+            intersection: FIELDS.corners()[0],
+            owner: 0,
+        });
+        play(&mut rules, &mut state, Progress::Irrigation).unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Wheat, HARVEST)]));
+    }
+
+    #[test]
+    fn mining_digs_mountains() {
+        let (mut rules, mut state) = holding(ProgressCard::Mining);
+        // Player 0's city isn't by the mountains
+        play(&mut rules, &mut state, Progress::Mining).unwrap();
+        assert!(state.player(0).hand.is_empty());
+        rules.progress.insert(0, vec![ProgressCard::Mining]);
+        let mine = MOUNTAINS
+            .corners()
+            .into_iter()
+            .find(|corner| *corner != COAST)
+            .unwrap();
+        state.board.build(Structure::Settlement {
+            intersection: mine,
+            owner: 0,
+        });
+        play(&mut rules, &mut state, Progress::Mining).unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Ore, HARVEST)]));
+    }
+
+    #[test]
+    fn medicine_makes_cities_cheaper() {
+        let (mut rules, mut state) = holding(ProgressCard::Medicine);
+        let farm = FIELDS.corners()[0];
+        state.board.build(Structure::Settlement {
+            intersection: farm,
+            owner: 0,
+        });
+        state.player_mut(0).hand = Hand::of(&[(Ore, 2), (Wheat, 1)]);
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::Medicine(CITY)),
+            Err(RuleError::InvalidTarget)
+        );
+        play(&mut rules, &mut state, Progress::Medicine(farm)).unwrap();
+        assert!(matches!(
+            state.board.building_at(farm),
+            Some(Structure::City { owner: 0, .. })
+        ));
+        assert!(state.player(0).hand.is_empty());
+    }
+
+    #[test]
+    fn road_building_builds_both_or_neither() {
+        let (mut rules, mut state) = holding(ProgressCard::RoadBuilding);
+        let first = CITY.edges()[0];
+        let end = first.other_end(&CITY);
+        let second = end.edges().into_iter().find(|edge| *edge != first).unwrap();
+        let far = DESERT
+            .edges()
+            .into_iter()
+            .find(|edge| !edge.ends().contains(&end) && !edge.ends().contains(&CITY))
+            .unwrap();
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::RoadBuilding(first, far)),
+            Err(RuleError::NotConnected)
+        );
+        assert!(state.board.road_at(first).is_none());
+        play(
+            &mut rules,
+            &mut state,
+            Progress::RoadBuilding(first, second),
+        )
+        .unwrap();
+        assert!(state.board.road_at(first).is_some());
+        assert!(state.board.road_at(second).is_some());
+    }
+
+    #[test]
+    fn smith_promotes_two_knights() {
+        let (mut rules, mut state) = holding(ProgressCard::Smith);
+        let first = road(&mut state, CITY, 0);
+        let second = road(&mut state, CITY, 1);
+        state.board.build(knight(first, 0, 1, false));
+        state.board.build(knight(second, 0, 1, true));
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::Smith(vec![first, first])),
+            Err(RuleError::InvalidTarget)
+        );
+        play(&mut rules, &mut state, Progress::Smith(vec![first, second])).unwrap();
+        assert_eq!(
+            state.board.knight_at(first),
+            Some(&knight(first, 0, 2, false))
+        );
+        assert_eq!(
+            state.board.knight_at(second),
+            Some(&knight(second, 0, 2, true))
+        );
+    }
+
+    #[test]
+    fn commercial_harbor_swaps_for_commodities() {
+        let (mut rules, mut state) = holding(ProgressCard::CommercialHarbor);
+        state.player_mut(0).hand = Hand::of(&[(Wood, 2)]);
+        state.player_mut(1).hand = Hand::of(&[(Cloth, 2), (Coin, 1)]);
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::CommercialHarbor(Paper)),
+            Err(RuleError::InvalidTrade)
+        );
+        play(&mut rules, &mut state, Progress::CommercialHarbor(Wood)).unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Wood, 1), (Cloth, 1)]));
+        assert_eq!(
+            state.player(1).hand,
+            Hand::of(&[(Wood, 1), (Cloth, 1), (Coin, 1)])
+        );
+    }
+
+    #[test]
+    fn master_merchant_robs_the_richer() {
+        let (mut rules, mut state) = holding(ProgressCard::MasterMerchant);
+        state.player_mut(1).hand = Hand::of(&[(Ore, 2), (Wheat, 1)]);
+        let merchant = Progress::MasterMerchant {
+            victim: 1,
+            cards: Hand::of(&[(Ore, 2)]),
+        };
+        assert_eq!(
+            play(&mut rules, &mut state, merchant.clone()),
+            Err(RuleError::InvalidTarget)
+        );
+        enrich(&mut state);
+        play(&mut rules, &mut state, merchant).unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Ore, 2)]));
+        assert_eq!(state.player(1).hand, Hand::of(&[(Wheat, 1)]));
+    }
+
+    #[test]
+    fn merchant_trades_and_scores() {
+        let (mut rules, mut state) = holding(ProgressCard::Merchant);
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::Merchant(MOUNTAINS)),
+            Err(RuleError::NotConnected)
+        );
+        play(&mut rules, &mut state, Progress::Merchant(PASTURE)).unwrap();
+        assert_eq!(rules.merchant(), Some((0, PASTURE)));
+        assert_eq!(rules.victory_points(&state, 0), state.victory_points(0) + 1);
+        state.player_mut(0).hand = Hand::of(&[(Sheep, 2)]);
+        rules
+            .apply(
+                &mut state,
+                0,
+                &Action::TradeBank {
+                    give: Sheep,
+                    get: Ore,
+                },
+            )
+            .unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Ore, 1)]));
+    }
+
+    #[test]
+    fn merchant_fleet_lasts_a_turn() {
+        let (mut rules, mut state) = holding(ProgressCard::MerchantFleet);
+        play(&mut rules, &mut state, Progress::MerchantFleet(Wheat)).unwrap();
+        assert_eq!(rules.trade_rate(&state, 0, Wheat), PREFERRED_RATE);
+        assert_eq!(rules.trade_rate(&state, 0, Ore), 4);
+        rules.apply(&mut state, 0, &Action::EndTurn).unwrap();
+        assert_eq!(rules.trade_rate(&state, 1, Wheat), 4);
+    }
+
+    #[test]
+    fn resource_monopoly_takes_two_each() {
+        let (mut rules, mut state) = holding(ProgressCard::ResourceMonopoly);
+        state.player_mut(1).hand = Hand::of(&[(Ore, 3), (Coin, 1)]);
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::ResourceMonopoly(Coin)),
+            Err(RuleError::InvalidTarget)
+        );
+        play(&mut rules, &mut state, Progress::ResourceMonopoly(Ore)).unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Ore, 2)]));
+        assert_eq!(state.player(1).hand, Hand::of(&[(Ore, 1), (Coin, 1)]));
+    }
+
+    #[test]
+    fn trade_monopoly_takes_one_each() {
+        let (mut rules, mut state) = holding(ProgressCard::TradeMonopoly);
+        state.player_mut(1).hand = Hand::of(&[(Coin, 2)]);
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::TradeMonopoly(Ore)),
+            Err(RuleError::InvalidTarget)
+        );
+        play(&mut rules, &mut state, Progress::TradeMonopoly(Coin)).unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Coin, 1)]));
+        assert_eq!(state.player(1).hand, Hand::of(&[(Coin, 1)]));
+    }
+
+    #[test]
+    fn bishop_robs_everyone_around() {
+        let (mut rules, mut state) = holding(ProgressCard::Bishop);
+        state.player_mut(1).hand = Hand::of(&[(Sheep, 1)]);
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::Bishop(DESERT)),
+            Err(RuleError::InvalidTarget)
+        );
+        let events = play(&mut rules, &mut state, Progress::Bishop(PASTURE)).unwrap();
+        assert!(events.contains(&Event::Stole {
+            thief: 0,
+            victim: 1,
+            card: Sheep
+        }));
+        assert_eq!(state.board.robber(), Some(PASTURE));
+        assert_eq!(state.phase(), &Phase::Main);
+    }
+
+    #[test]
+    fn deserter_changes_sides() {
+        let (mut rules, mut state) = holding(ProgressCard::Deserter);
+        let to = road(&mut state, CITY, 0);
+        let at = COAST
+            .neighbors()
+            .into_iter()
+            .find(|neighbor| *neighbor != to)
+            .unwrap();
+        state.board.build(knight(at, 1, 2, true));
+        assert_eq!(
+            play(
+                &mut rules,
+                &mut state,
+                Progress::Deserter {
+                    knight: at,
+                    to: CITY
+                }
+            ),
+            Err(RuleError::Occupied)
+        );
+        play(
+            &mut rules,
+            &mut state,
+            Progress::Deserter { knight: at, to },
+        )
+        .unwrap();
+        assert!(state.board.knight_at(at).is_none());
+        assert_eq!(state.board.knight_at(to), Some(&knight(to, 0, 2, false)));
+    }
+
+    #[test]
+    fn diplomat_removes_open_roads() {
+        let (mut rules, mut state) = holding(ProgressCard::Diplomat);
+        let first = CITY.edges()[0];
+        let end = road(&mut state, CITY, 0);
+        let index = end.edges().iter().position(|edge| *edge != first).unwrap();
+        road(&mut state, end, index);
+        let second = end.edges()[index];
+        let elsewhere = CITY.edges()[1];
+        assert_eq!(
+            play(
+                &mut rules,
+                &mut state,
+                Progress::Diplomat {
+                    road: first,
+                    to: None
+                }
+            ),
+            Err(RuleError::InvalidTarget)
+        );
+        let events = play(
+            &mut rules,
+            &mut state,
+            Progress::Diplomat {
+                road: second,
+                to: Some(elsewhere),
+            },
+        )
+        .unwrap();
+        assert!(events.contains(&Event::Removed(Structure::Road {
+            edge: second,
+            owner: 0
+        })));
+        assert!(state.board.road_at(second).is_none());
+        assert!(state.board.road_at(elsewhere).is_some());
+    }
+
+    #[test]
+    fn intrigue_displaces_a_knight() {
+        let (mut rules, mut state) = holding(ProgressCard::Intrigue);
+        let spot = road(&mut state, CITY, 0);
+        state.board.build(knight(spot, 0, 1, false));
+        assert_eq!(
+            play(&mut rules, &mut state, Progress::Intrigue(spot)),
+            Err(RuleError::InvalidTarget)
+        );
+        state.board.build(knight(spot, 1, 1, false));
+        state.board.remove(&knight(spot, 0, 1, false));
+        let events = play(&mut rules, &mut state, Progress::Intrigue(spot)).unwrap();
+        assert!(events.contains(&Event::KnightDisplaced {
+            player: 1,
+            from: spot,
+            to: None
+        }));
+        assert!(state.board.knight_at(spot).is_none());
+    }
+
+    #[test]
+    fn saboteur_halves_the_leaders() {
+        let (mut rules, mut state) = holding(ProgressCard::Saboteur);
+        state.player_mut(0).hand = Hand::of(&[(Ore, 4)]);
+        state.player_mut(1).hand = Hand::of(&[(Wood, 5)]);
+        play(&mut rules, &mut state, Progress::Saboteur).unwrap();
+        // Player 1 has fewer points
+        assert_eq!(state.player(1).hand.total(), 5);
+        rules.progress.insert(0, vec![ProgressCard::Saboteur]);
+        state.board.build(Structure::City {
+            intersection: COAST,
+            owner: 1,
+        });
+        play(&mut rules, &mut state, Progress::Saboteur).unwrap();
+        assert_eq!(state.player(1).hand.total(), 3);
+        assert_eq!(state.player(0).hand.total(), 4);
+    }
+
+    #[test]
+    fn spy_steals_a_progress_card() {
+        let (mut rules, mut state) = holding(ProgressCard::Spy);
+        rules.progress.insert(1, vec![ProgressCard::Warlord]);
+        let spy = |card| Progress::Spy { victim: 1, card };
+        assert_eq!(
+            play(&mut rules, &mut state, spy(ProgressCard::Wedding)),
+            Err(RuleError::NotInHand)
+        );
+        assert_eq!(rules.progress_cards(0), &[ProgressCard::Spy]);
+        let events = play(&mut rules, &mut state, spy(ProgressCard::Warlord)).unwrap();
+        assert!(
+            events.contains(&Event::Expansion(ExpansionEvent::ProgressStolen {
+                thief: 0,
+                victim: 1,
+                card: ProgressCard::Warlord
+            }))
+        );
+        assert_eq!(rules.progress_cards(0), &[ProgressCard::Warlord]);
+        assert!(rules.progress_cards(1).is_empty());
+    }
+
+    #[test]
+    fn warlord_activates_every_knight() {
+        let (mut rules, mut state) = holding(ProgressCard::Warlord);
+        let first = road(&mut state, CITY, 0);
+        let second = road(&mut state, CITY, 1);
+        state.board.build(knight(first, 0, 1, false));
+        state.board.build(knight(second, 0, 2, false));
+        play(&mut rules, &mut state, Progress::Warlord).unwrap();
+        assert_eq!(
+            state.board.knight_at(first),
+            Some(&knight(first, 0, 1, true))
+        );
+        assert_eq!(
+            state.board.knight_at(second),
+            Some(&knight(second, 0, 2, true))
+        );
+        assert!(state.player(0).hand.is_empty());
+    }
+
+    #[test]
+    fn wedding_gifts_from_the_richer() {
+        let (mut rules, mut state) = holding(ProgressCard::Wedding);
+        state.player_mut(1).hand = Hand::of(&[(Ore, 1), (Wheat, 1), (Brick, 1)]);
+        enrich(&mut state);
+        play(&mut rules, &mut state, Progress::Wedding).unwrap();
+        assert_eq!(state.player(0).hand.total(), GIFTS);
+        assert_eq!(state.player(1).hand.total(), 1);
+    }
+}
@@ -1 +1,224 @@
+//! Expansions decide which map cells and resources a board may use, the rules of the game and
+//! the textures its scene loads. The map picks one through its [EXPANSION] section.
+
 pub mod base;
+pub mod kingdom;
+pub mod seafarers;
+
+use crate::settlers::board::building::Structure;
+use crate::settlers::board::hex::Terrain;
+use crate::settlers::board::topology::HexCoord;
+use crate::settlers::rules::player::PlayerId;
+use crate::settlers::rules::{Action, Build, Event, GameState, RuleConfig, RuleError};
+use kingdom::progress::{Progress, ProgressCard};
+use kingdom::{EventDie, Improvement};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expansion {
+    Base,
+    Seafarers,
+    CitiesAndKnights,
+}
+
+impl Expansion {
+    pub const ALL: [Expansion; 3] = [
+        Expansion::Base,
+        Expansion::Seafarers,
+        Expansion::CitiesAndKnights,
+    ];
+
+    /// Name used by the [EXPANSION] section of .focm files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Expansion::Base => "DEFAULT",
+            Expansion::Seafarers => "SEAFARERS",
+            Expansion::CitiesAndKnights => "KINGDOM",
+        }
+    }
+
+    pub fn rules(&self) -> Box<dyn Ruleset> {
+        match self {
+            Expansion::Base => Box::new(BaseRules::new()),
+            Expansion::Seafarers => Box::new(seafarers::SeafarersRules::new()),
+            Expansion::CitiesAndKnights => Box::new(kingdom::KingdomRules::new()),
+        }
+    }
+}
+
+impl Display for Expansion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Expansion {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Expansion::ALL
+            .into_iter()
+            .find(|expansion| expansion.name() == name)
+            .ok_or(())
+    }
+}
+
+/// Map cells every expansion understands
+pub const BASE_TERRAIN: [Terrain; 4] = [
+    Terrain::Land,
+    Terrain::Water,
+    Terrain::Harbor,
+    Terrain::Frame,
+];
+/// Resource codes of the base game
+pub const BASE_RESOURCES: [&str; 6] = ["DE", "WO", "BR", "OR", "WH", "SH"];
+/// Textures of the base game scene, as (name, path)
+pub const BASE_TEXTURES: [(&str, &str); 2] = [
+    ("hex_tm", "assets/hex/hex_tilemap.png"),
+    ("chance_tm", "assets/hex/chances_tilemap.png"),
+];
+
+/// Actions only an expansion's rules take, the base rules refuse them as unsupported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpansionAction {
+    /// Buy the next level of a city improvement track with its commodity
+    Improve(Improvement),
+    /// Play a progress card with the choices it asks for
+    PlayProgress(Progress),
+    /// Draw a progress card the player is owed, e.g. after sharing the defence of Catan
+    DrawProgress(Improvement),
+}
+
+/// What happened under an expansion's own rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpansionEvent {
+    Improved {
+        player: PlayerId,
+        improvement: Improvement,
+        level: u8,
+    },
+    /// The player now holds the metropolis of the track
+    Metropolis {
+        player: PlayerId,
+        improvement: Improvement,
+    },
+    RolledEventDie(EventDie),
+    /// The player drew a progress card from the deck of the track, kept secret
+    ProgressDrawn {
+        player: PlayerId,
+        deck: Improvement,
+    },
+    ProgressPlayed {
+        player: PlayerId,
+        card: ProgressCard,
+    },
+    ProgressStolen {
+        thief: PlayerId,
+        victim: PlayerId,
+        card: ProgressCard,
+    },
+}
+
+/// Everything an expansion contributes to a game. The defaults are the base game.
+pub trait Ruleset {
+    fn expansion(&self) -> Expansion;
+
+    fn config(&self) -> &RuleConfig;
+
+    /// Map cells the expansion's maps may use
+    fn terrain(&self) -> &'static [Terrain] {
+        &BASE_TERRAIN
+    }
+
+    /// Resource codes the expansion's maps may use
+    fn resources(&self) -> &'static [&'static str] {
+        &BASE_RESOURCES
+    }
+
+    /// Textures the scene loads, as (name, path)
+    fn textures(&self) -> &'static [(&'static str, &'static str)] {
+        &BASE_TEXTURES
+    }
+
+    fn apply(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        action: &Action,
+    ) -> Result<Vec<Event>, RuleError> {
+        state.apply(self.config(), player, action)
+    }
+
+    /// Where the player could place a structure of a kind right now, see
+    /// `GameState::legal_builds`
+    fn legal_builds(&self, state: &GameState, player: PlayerId, build: Build) -> Vec<Structure> {
+        state.legal_builds(self.config(), player, build)
+    }
+
+    /// Victory points of a player, including the expansion's awards
+    fn victory_points(&self, state: &GameState, player: PlayerId) -> u32 {
+        state.victory_points(player)
+    }
+
+    /// Whether a 7 may move the pirate to the sea instead of the robber
+    fn has_pirate(&self) -> bool {
+        false
+    }
+
+    /// Players the thief could rob by moving the pirate to the hex
+    fn pirate_victims(
+        &self,
+        _state: &GameState,
+        _thief: PlayerId,
+        _hex: HexCoord,
+    ) -> Vec<PlayerId> {
+        Vec::new()
+    }
+
+    /// Progress cards in the player's hand, only Cities & Knights deals them
+    fn progress_cards(&self, _player: PlayerId) -> &[ProgressCard] {
+        &[]
+    }
+
+    /// Players owed a progress card of their choice, once for every card
+    fn owed_progress(&self) -> &[PlayerId] {
+        &[]
+    }
+}
+
+pub struct BaseRules {
+    config: RuleConfig,
+}
+
+impl BaseRules {
+    pub fn new() -> Self {
+        BaseRules {
+            config: RuleConfig::base(),
+        }
+    }
+}
+
+impl Ruleset for BaseRules {
+    fn expansion(&self) -> Expansion {
+        Expansion::Base
+    }
+
+    fn config(&self) -> &RuleConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expansion_names() {
+        for expansion in Expansion::ALL {
+            assert_eq!(expansion.name().parse(), Ok(expansion));
+            assert_eq!(expansion.rules().expansion(), expansion);
+        }
+        assert!("BASE".parse::<Expansion>().is_err());
+    }
+}
@@ -0,0 +1,759 @@
+//! Seafarers: ships sail along sea edges, gold fields produce any resource and the pirate
+//! blocks ships from the sea.
+
+use super::{Expansion, Ruleset, BASE_RESOURCES, BASE_TERRAIN};
+use crate::settlers::board::building::Structure;
+use crate::settlers::board::card::{Resource, ResourceCard};
+use crate::settlers::board::hex::Terrain;
+use crate::settlers::board::topology::{Edge, HexCoord, Intersection};
+use crate::settlers::rules::player::{Hand, PlayerId};
+use crate::settlers::rules::{Action, Build, Event, GameState, Phase, RuleConfig, RuleError};
+use std::collections::BTreeMap;
+
+/// Seafarers maps may hide parts of the board under fog
+const SEAFARERS_TERRAIN: [Terrain; 5] = [
+    BASE_TERRAIN[0],
+    BASE_TERRAIN[1],
+    BASE_TERRAIN[2],
+    BASE_TERRAIN[3],
+    Terrain::Fog,
+];
+const SEAFARERS_RESOURCES: [&str; 7] = [
+    BASE_RESOURCES[0],
+    BASE_RESOURCES[1],
+    BASE_RESOURCES[2],
+    BASE_RESOURCES[3],
+    BASE_RESOURCES[4],
+    BASE_RESOURCES[5],
+    "GO",
+];
+
+pub struct SeafarersRules {
+    config: RuleConfig,
+    /// Ships built this turn, they can't be moved until the next one
+    built_ships: Vec<Edge>,
+    moved_ship: bool,
+    /// Victory points from settling new islands
+    island_points: BTreeMap<PlayerId, u32>,
+}
+
+impl SeafarersRules {
+    pub fn new() -> Self {
+        let mut config = RuleConfig::base();
+        config.costs.push((
+            Build::Ship,
+            Hand::of(&[(ResourceCard::Wood, 1), (ResourceCard::Sheep, 1)]),
+        ));
+        config.pieces.push((Build::Ship, 15));
+        SeafarersRules {
+            config,
+            built_ships: Vec::new(),
+            moved_ship: false,
+            island_points: BTreeMap::new(),
+        }
+    }
+
+    /// Roll the dice, leaving gold to be picked after the other resources are handed out
+    pub fn roll(&mut self, state: &mut GameState, dice: (u8, u8)) -> Vec<Event> {
+        let mut events = state.roll(&self.config, dice);
+        if state.phase() == &Phase::Main {
+            let owed = gold_owed(state, dice.0 + dice.1);
+            events.extend(state.owe_resources(owed));
+        }
+        events
+    }
+
+    /// A ship may go on an edge along the sea that continues one of the player's shipping routes
+    pub fn check_ship(
+        &self,
+        state: &GameState,
+        player: PlayerId,
+        edge: Edge,
+    ) -> Result<(), RuleError> {
+        if !edge.hexes().iter().any(|hex| is_sea(state, *hex)) {
+            return Err(RuleError::NotSea);
+        }
+        if state.board.road_at(edge).is_some() {
+            return Err(RuleError::Occupied);
+        }
+        if state
+            .board
+            .pirate()
+            .is_some_and(|pirate| edge.hexes().contains(&pirate))
+        {
+            return Err(RuleError::Blocked);
+        }
+        if !state.connects(player, edge, |structure| {
+            matches!(structure, Structure::Ship { .. })
+        }) {
+            return Err(RuleError::NotConnected);
+        }
+        state.check_pieces(&self.config, player, Build::Ship)
+    }
+
+    fn move_ship(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        from: Edge,
+        to: Edge,
+    ) -> Result<Vec<Event>, RuleError> {
+        let ship = Structure::Ship {
+            edge: from,
+            owner: player,
+        };
+        if state.board.road_at(from) != Some(&ship) || from == to {
+            return Err(RuleError::InvalidTarget);
+        }
+        if self.moved_ship || self.built_ships.contains(&from) {
+            return Err(RuleError::OncePerTurn);
+        }
+        if state
+            .board
+            .pirate()
+            .is_some_and(|pirate| from.hexes().contains(&pirate))
+        {
+            return Err(RuleError::Blocked);
+        }
+        // Only the last ship of a route that doesn't end in one of the player's buildings
+        if !from
+            .ends()
+            .iter()
+            .any(|end| is_open_end(state, player, from, *end))
+        {
+            return Err(RuleError::InvalidTarget);
+        }
+        state.board.remove(&ship);
+        if let Err(error) = self.check_ship(state, player, to) {
+            state.board.build(ship);
+            return Err(error);
+        }
+        state.board.build(Structure::Ship {
+            edge: to,
+            owner: player,
+        });
+        self.moved_ship = true;
+        let mut events = vec![Event::ShipMoved { player, from, to }];
+        events.extend(state.update_longest_road());
+        Ok(events)
+    }
+
+    /// Award the map's island bonus if the settlement is the player's first on its island
+    fn settle_island(
+        &mut self,
+        state: &GameState,
+        player: PlayerId,
+        intersection: Intersection,
+    ) -> Option<Event> {
+        let points = state.board.island_bonus() as u32;
+        let land = intersection
+            .hexes()
+            .into_iter()
+            .find(|hex| state.board.is_land(*hex))?;
+        let island = state.board.island(land);
+        let settled = state.board.buildings().iter().any(|building| {
+            building.owner() == player
+                && building.is_building()
+                && building.intersection().is_some_and(|other| {
+                    other != intersection && other.hexes().iter().any(|hex| island.contains(hex))
+                })
+        });
+        if settled || points == 0 {
+            return None;
+        }
+        *self.island_points.entry(player).or_insert(0) += points;
+        Some(Event::IslandSettled { player, points })
+    }
+
+    fn move_pirate(
+        &self,
+        state: &mut GameState,
+        player: PlayerId,
+        hex: HexCoord,
+        victim: Option<PlayerId>,
+    ) -> Result<Vec<Event>, RuleError> {
+        if !is_sea(state, hex) {
+            return Err(RuleError::NotSea);
+        }
+        if state.board.pirate() == Some(hex) {
+            return Err(RuleError::InvalidTarget);
+        }
+        let victims = pirate_victims(state, player, hex);
+        match victim {
+            Some(victim) if !victims.contains(&victim) => return Err(RuleError::InvalidTarget),
+            None if !victims.is_empty() => return Err(RuleError::InvalidTarget),
+            _ => (),
+        }
+        state.board.move_pirate(hex);
+        let mut events = vec![Event::PirateMoved { hex }];
+        events.extend(victim.and_then(|victim| state.steal(player, victim)));
+        state.set_phase(Phase::Main);
+        Ok(events)
+    }
+}
+
+impl Ruleset for SeafarersRules {
+    fn expansion(&self) -> Expansion {
+        Expansion::Seafarers
+    }
+
+    fn config(&self) -> &RuleConfig {
+        &self.config
+    }
+
+    fn terrain(&self) -> &'static [Terrain] {
+        &SEAFARERS_TERRAIN
+    }
+
+    fn resources(&self) -> &'static [&'static str] {
+        &SEAFARERS_RESOURCES
+    }
+
+    fn apply(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        action: &Action,
+    ) -> Result<Vec<Event>, RuleError> {
+        let phase = state.phase().clone();
+        let mut events = self.play(state, player, action)?;
+        match action {
+            Action::BuildRoad(edge)
+            | Action::BuildShip(edge)
+            | Action::MoveShip { to: edge, .. } => events.extend(discover(state, player, *edge)),
+            Action::BuildSettlement(intersection) if phase.is_building() => {
+                events.extend(self.settle_island(state, player, *intersection))
+            }
+            _ => (),
+        }
+        Ok(events)
+    }
+
+    fn legal_builds(&self, state: &GameState, player: PlayerId, build: Build) -> Vec<Structure> {
+        if build != Build::Ship {
+            return state.legal_builds(&self.config, player, build);
+        }
+        if player != state.acting() || !state.phase().is_building() {
+            return Vec::new();
+        }
+        // Ships sail between any two hexes of the board, not only along land
+        let mut edges: Vec<Edge> = state
+            .board
+            .hexes()
+            .flat_map(|(coord, _)| coord.edges())
+            .collect();
+        edges.sort();
+        edges.dedup();
+        edges
+            .into_iter()
+            .filter(|edge| self.check_ship(state, player, *edge).is_ok())
+            .map(|edge| Structure::Ship {
+                edge,
+                owner: player,
+            })
+            .collect()
+    }
+
+    fn victory_points(&self, state: &GameState, player: PlayerId) -> u32 {
+        state.victory_points(player) + self.island_points.get(&player).copied().unwrap_or(0)
+    }
+
+    fn has_pirate(&self) -> bool {
+        true
+    }
+
+    fn pirate_victims(&self, state: &GameState, thief: PlayerId, hex: HexCoord) -> Vec<PlayerId> {
+        pirate_victims(state, thief, hex)
+    }
+}
+
+impl SeafarersRules {
+    fn play(
+        &mut self,
+        state: &mut GameState,
+        player: PlayerId,
+        action: &Action,
+    ) -> Result<Vec<Event>, RuleError> {
+        if player != state.acting() {
+            return state.apply(&self.config, player, action);
+        }
+        match (state.phase().clone(), action) {
+            (
+                Phase::Setup {
+                    round,
+                    settlement: Some(settlement),
+                },
+                Action::BuildShip(edge),
+            ) => {
+                if !edge.ends().contains(&settlement) {
+                    return Err(RuleError::NotConnected);
+                }
+                self.check_ship(state, player, *edge)?;
+                let ship = Structure::Ship {
+                    edge: *edge,
+                    owner: player,
+                };
+                state.board.build(ship);
+                state.advance_setup(round);
+                Ok(vec![Event::Built(ship)])
+            }
+            (
+                Phase::Setup {
+                    round: 1,
+                    settlement: None,
+                },
+                Action::BuildSettlement(intersection),
+            ) => {
+                let mut events = state.apply(&self.config, player, action)?;
+                // The second settlement's gold fields give one resource each
+                let owed = starting_gold(state, *intersection);
+                events.extend(state.owe_resources([(player, owed)].into()));
+                Ok(events)
+            }
+            (Phase::Roll, Action::Roll) => {
+                let dice = state.roll_dice();
+                Ok(self.roll(state, dice))
+            }
+            (phase, Action::BuildShip(edge)) if phase.is_building() => {
+                self.check_ship(state, player, *edge)?;
+                state.pay(player, &self.config.cost(Build::Ship))?;
+                self.built_ships.push(*edge);
+                state.build(Structure::Ship {
+                    edge: *edge,
+                    owner: player,
+                })
+            }
+            (Phase::Main, Action::MoveShip { from, to }) => {
+                self.move_ship(state, player, *from, *to)
+            }
+            (Phase::Robber, Action::MovePirate { hex, victim }) => {
+                self.move_pirate(state, player, *hex, *victim)
+            }
+            (Phase::Main | Phase::SpecialBuilding { .. }, Action::EndTurn) => {
+                self.built_ships.clear();
+                self.moved_ship = false;
+                state.apply(&self.config, player, action)
+            }
+            _ => state.apply(&self.config, player, action),
+        }
+    }
+}
+
+/// Reveal the fog hexes along a newly built edge. Land rewards its discoverer with one of its
+/// resources, gold with a resource of their choice.
+fn discover(state: &mut GameState, player: PlayerId, edge: Edge) -> Vec<Event> {
+    let mut events = Vec::new();
+    for coord in edge.hexes() {
+        let Some(hex) = state.board.reveal(coord) else {
+            continue;
+        };
+        let resource = hex.is_land().then(|| hex.resource());
+        events.push(Event::Discovered { player, hex: coord });
+        match resource {
+            Some(Resource::Gold(_)) => events.extend(state.owe_resources([(player, 1)].into())),
+            Some(resource) => {
+                if let Some(card) = resource.card() {
+                    let cards = Hand::of(&[(card, 1)]);
+                    state.player_mut(player).hand.add_hand(&cards);
+                    events.push(Event::Produced { player, cards });
+                }
+            }
+            None => (),
+        }
+    }
+    events
+}
+
+/// Water the ships sail on, harbors included
+fn is_sea(state: &GameState, hex: HexCoord) -> bool {
+    state
+        .board
+        .hex(hex)
+        .is_some_and(|hex| matches!(hex.terrain(), Terrain::Water | Terrain::Harbor))
+}
+
+/// Whether the route of `ship` stops at `end`: no building and no other ship of the player
+fn is_open_end(state: &GameState, player: PlayerId, ship: Edge, end: Intersection) -> bool {
+    state.board.building_at(end).is_none()
+        && !end.edges().iter().any(|edge| {
+            *edge != ship
+                && matches!(
+                    state.board.road_at(*edge),
+                    Some(Structure::Ship { owner, .. }) if *owner == player
+                )
+        })
+}
+
+fn is_gold(resource: &Resource) -> bool {
+    matches!(resource, Resource::Gold(_))
+}
+
+/// Resources each player picks for the gold fields of a roll
+fn gold_owed(state: &GameState, roll: u8) -> BTreeMap<PlayerId, u32> {
+    let mut owed = BTreeMap::new();
+    for structure in state.board.buildings() {
+        for resource in structure.collect_resources(&state.board, roll) {
+            if is_gold(&resource) {
+                *owed.entry(structure.owner()).or_insert(0) += resource.chance() as u32;
+            }
+        }
+    }
+    owed
+}
+
+fn starting_gold(state: &GameState, intersection: Intersection) -> u32 {
+    intersection
+        .hexes()
+        .iter()
+        .filter_map(|hex| state.board.hex(*hex))
+        .filter(|hex| hex.is_land() && is_gold(&hex.resource()))
+        .count() as u32
+}
+
+/// Players with a ship next to the hex and cards to steal
+fn pirate_victims(state: &GameState, thief: PlayerId, hex: HexCoord) -> Vec<PlayerId> {
+    let mut victims: Vec<PlayerId> = hex
+        .edges()
+        .iter()
+        .filter_map(|edge| match state.board.road_at(*edge) {
+            Some(Structure::Ship { owner, .. }) => Some(*owner),
+            _ => None,
+        })
+        .filter(|owner| *owner != thief && !state.player(*owner).hand.is_empty())
+        .collect();
+    victims.sort();
+    victims.dedup();
+    victims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settlers::board::map::Board;
+    use crate::settlers::board::topology::Direction;
+    use ResourceCard::*;
+
+    /// Two rows of land in the sea, with a gold field on the coast
+    const ISLAND: &str = "__FOCM__
+[EXPANSION]
+SEAFARERS
+[RESOURCES]
+WO:2, SH:2, GO:1, BR:1
+[CHANCES]
+4, 5, 6, 8, 9, 10
+[MAP]
+5x5
+WWWWW
+WWWWW
+W111W
+W111W
+WWWWW
+[LAYOUT]
+-- -- -- -- --
+-- -- -- -- --
+-- GO8 WO4 SH5 --
+-- BR6 WO9 SH10 --
+-- -- -- -- --
+";
+
+    const GOLD: HexCoord = HexCoord::new(3, 2);
+
+    /// Player 0 has a settlement on the coast below the gold field
+    fn island() -> (SeafarersRules, GameState) {
+        let board: Board = ISLAND.parse().unwrap();
+        let mut state = GameState::new(board, 2, 3);
+        state.board.build(Structure::Settlement {
+            intersection: Intersection::bottom(GOLD),
+            owner: 0,
+        });
+        state.set_phase(Phase::Main);
+        (SeafarersRules::new(), state)
+    }
+
+    /// Edge between two sea hexes leading away from the settlement
+    fn open_sea() -> Edge {
+        Intersection::bottom(GOLD).edges()[2]
+    }
+
+    #[test]
+    fn ships_sail_the_sea() {
+        let (mut rules, mut state) = island();
+        let inland = Edge::between(GOLD, Direction::East);
+        state.player_mut(0).hand = Hand::of(&[(Wood, 2), (Sheep, 2)]);
+        assert_eq!(
+            rules.apply(&mut state, 0, &Action::BuildShip(inland)),
+            Err(RuleError::NotSea)
+        );
+        rules
+            .apply(&mut state, 0, &Action::BuildShip(open_sea()))
+            .unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Wood, 1), (Sheep, 1)]));
+        // Roads stay on land
+        state.player_mut(0).hand = Hand::of(&[(Wood, 1), (Brick, 1)]);
+        let next = open_sea()
+            .other_end(&Intersection::bottom(GOLD))
+            .edges()
+            .into_iter()
+            .find(|edge| *edge != open_sea())
+            .unwrap();
+        assert_eq!(
+            rules.apply(&mut state, 0, &Action::BuildRoad(next)),
+            Err(RuleError::NotLand)
+        );
+    }
+
+    #[test]
+    fn base_game_has_no_ships() {
+        let (_, mut state) = island();
+        let mut base = Expansion::Base.rules();
+        assert_eq!(
+            base.apply(&mut state, 0, &Action::BuildShip(open_sea())),
+            Err(RuleError::Unsupported)
+        );
+        assert!(base.legal_builds(&state, 0, Build::Ship).is_empty());
+    }
+
+    #[test]
+    fn ship_previews_leave_the_settlement() {
+        let (rules, state) = island();
+        let ships = rules.legal_builds(&state, 0, Build::Ship);
+        assert!(ships.contains(&Structure::Ship {
+            edge: open_sea(),
+            owner: 0
+        }));
+        assert!(ships.iter().all(|ship| ship
+            .edge()
+            .is_some_and(|edge| edge.ends().contains(&Intersection::bottom(GOLD)))));
+        assert!(rules.legal_builds(&state, 1, Build::Ship).is_empty());
+    }
+
+    #[test]
+    fn gold_fields_let_owner_pick() {
+        let (mut rules, mut state) = island();
+        state.set_phase(Phase::Roll);
+        let events = rules.roll(&mut state, (4, 4));
+        assert!(events.contains(&Event::ResourcesOwed {
+            player: 0,
+            amount: 1
+        }));
+        assert_eq!(
+            rules.apply(&mut state, 0, &Action::EndTurn),
+            Err(RuleError::WrongPhase)
+        );
+        assert_eq!(
+            rules.apply(&mut state, 0, &Action::PickResources(Hand::of(&[(Ore, 2)]))),
+            Err(RuleError::InvalidPick)
+        );
+        rules
+            .apply(&mut state, 0, &Action::PickResources(Hand::of(&[(Ore, 1)])))
+            .unwrap();
+        assert_eq!(state.player(0).hand.count(Ore), 1);
+        assert_eq!(state.phase(), &Phase::Main);
+    }
+
+    #[test]
+    fn last_ship_of_open_route_moves() {
+        let (mut rules, mut state) = island();
+        let start = Intersection::bottom(GOLD);
+        let first = open_sea();
+        let middle = first.other_end(&start);
+        let second = middle
+            .edges()
+            .into_iter()
+            .find(|edge| *edge != first && edge.hexes().iter().all(|h| is_sea(&state, *h)))
+            .unwrap();
+        for edge in [first, second] {
+            state.board.build(Structure::Ship { edge, owner: 0 });
+        }
+        let coast = start.edges()[0];
+        assert_eq!(
+            rules.apply(
+                &mut state,
+                0,
+                &Action::MoveShip {
+                    from: first,
+                    to: coast
+                }
+            ),
+            Err(RuleError::InvalidTarget)
+        );
+        let events = rules
+            .apply(
+                &mut state,
+                0,
+                &Action::MoveShip {
+                    from: second,
+                    to: coast,
+                },
+            )
+            .unwrap();
+        assert!(events.contains(&Event::ShipMoved {
+            player: 0,
+            from: second,
+            to: coast
+        }));
+        assert!(state.board.road_at(second).is_none());
+        assert_eq!(
+            rules.apply(
+                &mut state,
+                0,
+                &Action::MoveShip {
+                    from: coast,
+                    to: second
+                }
+            ),
+            Err(RuleError::OncePerTurn)
+        );
+    }
+
+    #[test]
+    fn trade_route_joins_at_buildings() {
+        let (_, mut state) = island();
+        let start = Intersection::bottom(GOLD);
+        let first = open_sea();
+        let second = first
+            .other_end(&start)
+            .edges()
+            .into_iter()
+            .find(|edge| *edge != first)
+            .unwrap();
+        state.board.build(Structure::Ship {
+            edge: first,
+            owner: 0,
+        });
+        state.board.build(Structure::Ship {
+            edge: second,
+            owner: 0,
+        });
+        state.board.build(Structure::Road {
+            edge: start.edges()[0],
+            owner: 0,
+        });
+        assert_eq!(state.road_length(0), 3);
+        state.board.remove(&Structure::Settlement {
+            intersection: start,
+            owner: 0,
+        });
+        assert_eq!(state.road_length(0), 2);
+    }
+
+    #[test]
+    fn pirate_blocks_and_steals() {
+        let (mut rules, mut state) = island();
+        state.board.build(Structure::Settlement {
+            intersection: Intersection::bottom(HexCoord::new(3, 4)),
+            owner: 1,
+        });
+        let ship = Intersection::bottom(HexCoord::new(3, 4)).edges()[2];
+        state.board.build(Structure::Ship {
+            edge: ship,
+            owner: 1,
+        });
+        state.player_mut(1).hand = Hand::of(&[(Brick, 1)]);
+        state.set_phase(Phase::Robber);
+        assert_eq!(
+            rules.apply(
+                &mut state,
+                0,
+                &Action::MovePirate {
+                    hex: GOLD,
+                    victim: None
+                }
+            ),
+            Err(RuleError::NotSea)
+        );
+        let sea = ship
+            .hexes()
+            .into_iter()
+            .find(|hex| !open_sea().hexes().contains(hex))
+            .unwrap();
+        assert!(rules.has_pirate());
+        assert_eq!(rules.pirate_victims(&state, 0, sea), vec![1]);
+        rules
+            .apply(
+                &mut state,
+                0,
+                &Action::MovePirate {
+                    hex: sea,
+                    victim: Some(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(state.player(0).hand.count(Brick), 1);
+        assert_eq!(state.board.pirate(), Some(sea));
+        // Player 1 can't sail past the pirate
+        let blocked = ship
+            .ends()
+            .iter()
+            .flat_map(|end| end.edges())
+            .find(|edge| *edge != ship && edge.hexes().contains(&sea))
+            .unwrap();
+        state.end_turn();
+        state.set_phase(Phase::Main);
+        state.player_mut(1).hand = Hand::of(&[(Wood, 1), (Sheep, 1)]);
+        assert_eq!(
+            rules.apply(&mut state, 1, &Action::BuildShip(blocked)),
+            Err(RuleError::Blocked)
+        );
+    }
+
+    #[test]
+    fn ships_discover_fog() {
+        let foggy = format!(
+            "{}[FOG]\nBR:1\n3\n",
+            ISLAND.replace("WWWWW\nW111W", "FWWWW\nW111W")
+        );
+        let mut state = GameState::new(foggy.parse().unwrap(), 2, 3);
+        state.board.build(Structure::Settlement {
+            intersection: Intersection::bottom(GOLD),
+            owner: 0,
+        });
+        state.set_phase(Phase::Main);
+        let mut rules = SeafarersRules::new();
+        state.player_mut(0).hand = Hand::of(&[(Wood, 1), (Sheep, 1)]);
+        let events = rules
+            .apply(&mut state, 0, &Action::BuildShip(open_sea()))
+            .unwrap();
+        let fog = HexCoord::new(2, 1);
+        assert!(open_sea().hexes().contains(&fog));
+        assert!(events.contains(&Event::Discovered {
+            player: 0,
+            hex: fog
+        }));
+        assert_eq!(state.board.hex(fog).unwrap().resource().chance(), 3);
+        assert!(state.board.fog().tiles.is_empty());
+        assert_eq!(state.player(0).hand, Hand::of(&[(Brick, 1)]));
+    }
+
+    #[test]
+    fn first_settlement_on_an_island_scores() {
+        let islands = ISLAND
+            .replace("WO:2", "WO:3")
+            .replace("4, 5", "3, 4, 5")
+            .replace("5x5\nWWWWW", "5x5\nWW1WW")
+            .replace("[LAYOUT]\n-- -- -- -- --", "[LAYOUT]\n-- -- WO3 -- --")
+            + "[ISLAND_BONUS]\n2\n";
+        let mut state = GameState::new(islands.parse().unwrap(), 2, 3);
+        let mut rules = SeafarersRules::new();
+        let mut settle = |state: &mut GameState, player, intersection| {
+            state.board.build(Structure::Settlement {
+                intersection,
+                owner: player,
+            });
+            rules.settle_island(state, player, intersection)
+        };
+        assert_eq!(
+            settle(&mut state, 0, Intersection::bottom(GOLD)),
+            Some(Event::IslandSettled {
+                player: 0,
+                points: 2
+            })
+        );
+        let far = Intersection::bottom(HexCoord::new(4, 4));
+        assert_eq!(settle(&mut state, 0, far), None);
+        let islet = Intersection::bottom(HexCoord::new(1, 3));
+        assert!(settle(&mut state, 0, islet).is_some());
+        assert!(settle(&mut state, 1, far).is_some());
+        assert_eq!(rules.victory_points(&state, 0), state.victory_points(0) + 4);
+    }
+}
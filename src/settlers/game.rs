@@ -1,4 +1,6 @@
-use crate::settlers::expansions::base::BaseGame;
+pub use crate::settlers::expansions::Expansion;
+use crate::settlers::ui::menu::MainMenu;
+use crate::settlers::ui::Gui;
 use glium::backend::Facade;
 use glium::{Frame, Surface};
 use std::time::Instant;
@@ -7,64 +9,185 @@ use winit::event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, TouchP
 
 pub struct DeltaTime {
     last: Instant,
+    // Time between the last two updates
+    frame: f32,
 }
 
 impl DeltaTime {
     pub fn new() -> Self {
         DeltaTime {
             last: Instant::now(),
+            frame: 0.,
         }
     }
 
     pub fn update(&mut self) {
+        self.frame = self.delta();
         self.last = Instant::now();
     }
 
+    /// Seconds the last frame took
+    pub fn frame_time(&self) -> f32 {
+        self.frame
+    }
+
     pub fn delta(&self) -> f32 {
         self.last.elapsed().as_secs_f32()
     }
 }
 
 pub trait Scene {
+    // Called when the scene is pushed on the stack, load GPU resources here
+    fn enter(&mut self, _facade: &dyn Facade) {}
+    // Called when the scene leaves the stack, release GPU resources here
+    fn exit(&mut self) {}
+
     // Called on mouse move
-    fn mouse_move(&mut self, position: PhysicalPosition<f64>);
+    fn mouse_move(&mut self, _position: PhysicalPosition<f64>) {}
     // Called on recieving mouse input
-    fn mouse_input(&mut self, state: ElementState, button: MouseButton);
+    fn mouse_input(&mut self, _state: ElementState, _button: MouseButton) {}
     // Called on recieving keyboard input
-    fn keyboard_input(&mut self, event: KeyEvent);
+    fn keyboard_input(&mut self, _event: KeyEvent) {}
     // Called on recieving mouse scroll input
-    fn scroll_input(&mut self, delta: MouseScrollDelta, phase: TouchPhase);
+    fn scroll_input(&mut self, _delta: MouseScrollDelta, _phase: TouchPhase) {}
     // Called on recieving window size update
-    fn window_size(&mut self, new_size: PhysicalSize<u32>);
+    fn window_size(&mut self, _new_size: PhysicalSize<u32>) {}
+
+    // Called every frame to build the scene's imgui windows
+    fn ui(&mut self, _ui: &imgui::Ui) {}
 
     // Called every time before draw
-    fn update(&mut self) -> Result<(), Box<dyn std::error::Error>>;
+    fn update(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+    // Called after every frame, where the scene asks to move on to another one
+    fn transition(&mut self) -> Option<Transition> {
+        None
+    }
+    // Whether the scene below is drawn under this one, e.g. for a pause menu
+    fn is_overlay(&self) -> bool {
+        false
+    }
     // Draw to the screen with frame
-    fn draw<F>(&self, facade: &F, frame: Frame) -> Frame
-    where
-        F: ?Sized + Facade;
+    fn draw(&self, facade: &dyn Facade, frame: Frame) -> Frame;
+}
+
+/// Change to the scene stack a scene asks for
+pub enum Transition {
+    Push(Box<dyn Scene>),
+    Pop,
+    /// Pop the scene, then push another
+    Replace(Box<dyn Scene>),
+    /// Empty the stack, then push a scene, e.g. going back to the main menu
+    Reset(Box<dyn Scene>),
+    Quit,
 }
 
-#[allow(dead_code)]
-pub enum Expansion {
-    Base,
-    Seafarers,
-    CitiesAndKnights,
+/// Stack of scenes, the top one gets the input and the ones under overlays are drawn too.
+/// The game ends once the stack is empty.
+pub struct SceneManager {
+    stack: Vec<Box<dyn Scene>>,
+    window_size: PhysicalSize<u32>,
 }
 
-#[allow(dead_code)]
-pub struct Settlers {
-    scene: Expansion,
+impl SceneManager {
+    pub fn new(window_size: PhysicalSize<u32>) -> Self {
+        SceneManager {
+            stack: Vec::new(),
+            window_size,
+        }
+    }
+
+    pub fn push(&mut self, facade: &dyn Facade, mut scene: Box<dyn Scene>) {
+        scene.enter(facade);
+        scene.window_size(self.window_size);
+        self.stack.push(scene);
+    }
+
+    pub fn pop(&mut self) {
+        if let Some(mut scene) = self.stack.pop() {
+            scene.exit();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Scene receiving the input
+    pub fn top(&mut self) -> Option<&mut dyn Scene> {
+        Some(self.stack.last_mut()?.as_mut())
+    }
+
+    /// Every scene keeps track of the window size, not only the top one
+    pub fn window_size(&mut self, new_size: PhysicalSize<u32>) {
+        self.window_size = new_size;
+        for scene in self.stack.iter_mut() {
+            scene.window_size(new_size);
+        }
+    }
+
+    pub fn update(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        match self.top() {
+            Some(scene) => scene.update(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &imgui::Ui) {
+        if let Some(scene) = self.top() {
+            scene.ui(ui);
+        }
+    }
+
+    /// Apply the transition the top scene asks for, if any
+    pub fn transition(&mut self, facade: &dyn Facade) {
+        let Some(transition) = self.top().and_then(|scene| scene.transition()) else {
+            return;
+        };
+        match transition {
+            Transition::Push(scene) => self.push(facade, scene),
+            Transition::Pop => self.pop(),
+            Transition::Replace(scene) => {
+                self.pop();
+                self.push(facade, scene);
+            }
+            Transition::Reset(scene) => {
+                self.clear();
+                self.push(facade, scene);
+            }
+            Transition::Quit => self.clear(),
+        }
+    }
+
+    /// Draw the top scene, over the scenes under it while it is an overlay
+    pub fn draw(&self, facade: &dyn Facade, mut frame: Frame) -> Frame {
+        let bottom = self
+            .stack
+            .iter()
+            .rposition(|scene| !scene.is_overlay())
+            .unwrap_or(0);
+        for scene in self.stack.iter().skip(bottom) {
+            frame = scene.draw(facade, frame);
+        }
+        frame
+    }
+
+    fn clear(&mut self) {
+        while !self.stack.is_empty() {
+            self.pop();
+        }
+    }
 }
 
+pub struct Settlers {}
+
 impl Settlers {
     pub fn new() -> Self {
-        Self {
-            scene: Expansion::Base,
-        }
+        Self {}
     }
 
-    pub fn run(&mut self, _imgui_debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn run(&mut self, imgui_debug: bool) -> Result<(), Box<dyn std::error::Error>> {
         // =======================================
         let event_loop = winit::event_loop::EventLoopBuilder::new()
             .build()
@@ -78,44 +201,70 @@ impl Settlers {
             .build(&event_loop);
 
         // ================ IMGUI ========================
+        let mut gui = Gui::new(&display, crate::settings::WINDOW_DEFAULT_SIZE);
 
-        let mut base_game = BaseGame::new(&display);
+        let mut scenes = SceneManager::new(window.inner_size());
+        scenes.push(&display, Box::new(MainMenu::new(imgui_debug)));
         use winit::event::{Event, WindowEvent};
         // Game loop
         let _ = event_loop.run(move |event, window_target| {
             match event {
-                winit::event::Event::WindowEvent { event, .. } => match event {
-                    WindowEvent::CloseRequested => window_target.exit(),
-                    WindowEvent::Resized(window_size) => {
+                winit::event::Event::WindowEvent { event, .. } => {
+                    gui.handle_event(&event);
+                    // The mouse goes to the windows under it, but releases always reach the scene
+                    // so it doesn't keep dragging the camera
+                    let over_ui = gui.wants_mouse();
+                    // Likewise keys go to a focused text field, except releases so a key held
+                    // before typing doesn't keep panning
+                    let typing = gui.wants_keyboard();
+                    if let WindowEvent::Resized(window_size) = event {
                         display.resize(window_size.into());
-                        base_game.window_size(window.inner_size());
-                    }
-                    // Input events
-                    WindowEvent::CursorMoved { position, .. } => {
-                        base_game.mouse_move(position);
+                        scenes.window_size(window.inner_size());
                     }
+                    let Some(scene) = scenes.top() else {
+                        return;
+                    };
+                    match event {
+                        WindowEvent::CloseRequested => window_target.exit(),
+                        // Input events
+                        WindowEvent::CursorMoved { position, .. } => {
+                            scene.mouse_move(position);
+                        }
 
-                    WindowEvent::MouseInput { state, button, .. } => {
-                        base_game.mouse_input(state, button);
-                    }
-                    WindowEvent::KeyboardInput { event, .. } => {
-                        base_game.keyboard_input(event);
-                    }
+                        WindowEvent::MouseInput { state, button, .. }
+                            if !over_ui || state == ElementState::Released =>
+                        {
+                            scene.mouse_input(state, button);
+                        }
+                        WindowEvent::KeyboardInput { event, .. }
+                            if !typing || !event.state.is_pressed() =>
+                        {
+                            scene.keyboard_input(event);
+                        }
 
-                    WindowEvent::MouseWheel { delta, phase, .. } => {
-                        base_game.scroll_input(delta, phase);
-                    }
+                        WindowEvent::MouseWheel { delta, phase, .. } if !over_ui => {
+                            scene.scroll_input(delta, phase);
+                        }
 
-                    WindowEvent::RedrawRequested => {
-                        // Update any logic
-                        base_game.update().unwrap();
-                        // Create frame canvas
-                        let mut target = display.draw();
-                        target.clear_color(0.0, 0.5, 0.6, 1.0);
-                        base_game.draw(&display, target).finish().unwrap();
+                        WindowEvent::RedrawRequested => {
+                            // Update any logic
+                            scenes.update().unwrap();
+                            gui.frame(|ui| scenes.ui(ui));
+                            scenes.transition(&display);
+                            if scenes.is_empty() {
+                                window_target.exit();
+                                return;
+                            }
+                            // Create frame canvas
+                            let mut target = display.draw();
+                            target.clear_color(0.0, 0.5, 0.6, 1.0);
+                            let mut frame = scenes.draw(&display, target);
+                            gui.draw(&display, &mut frame);
+                            frame.finish().unwrap();
+                        }
+                        _ => (),
                     }
-                    _ => (),
-                },
+                }
                 Event::AboutToWait => {
                     window.request_redraw();
                 }
@@ -1,8 +1,93 @@
+//! Small vector and matrix types for the board's transforms.
+//!
+//! A `Mat4` is stored the way glium uploads it: each inner array is a column of the matrix the
+//! shaders see. `m * v` and `a * b` read as they do in glsl, so in `projection * view * model`
+//! the model transform is applied first. `multiply_by` is the plain product of the stored
+//! arrays, `a.multiply_by(b)` is `b * a`.
+
 use std::fmt::{Display, Formatter, Result};
-use std::ops::Sub;
+use std::ops::{Add, Mul, Neg, Sub};
 use std::ops::{Index, IndexMut};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vec2(f32, f32);
+
+#[allow(dead_code)]
+impl Vec2 {
+    pub const fn new(x: f32, y: f32) -> Vec2 {
+        Vec2(x, y)
+    }
+
+    pub fn x(&self) -> f32 {
+        self.0
+    }
+
+    pub fn y(&self) -> f32 {
+        self.1
+    }
+
+    pub fn magnitude(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Vec2 {
+        *self * (1. / self.magnitude())
+    }
+
+    pub fn dot(&self, other: &Self) -> f32 {
+        self.0 * other.0 + self.1 * other.1
+    }
+
+    /// The vector turned by `angle` radians, counter-clockwise
+    pub fn rotate(&self, angle: f32) -> Vec2 {
+        let (sin, cos) = angle.sin_cos();
+        Vec2::new(self.0 * cos - self.1 * sin, self.0 * sin + self.1 * cos)
+    }
+
+    pub fn as_tuple(&self) -> (f32, f32) {
+        (self.0, self.1)
+    }
+}
+
+impl From<(f32, f32)> for Vec2 {
+    fn from(tuple: (f32, f32)) -> Self {
+        Vec2::new(tuple.0, tuple.1)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Self;
+
+    fn add(self, other: Vec2) -> Self {
+        Vec2::new(self.0 + other.0, self.1 + other.1)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Self;
+
+    fn sub(self, other: Vec2) -> Self {
+        Vec2::new(self.0 - other.0, self.1 - other.1)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Vec2::new(-self.0, -self.1)
+    }
+}
+
+impl Mul<f32> for Vec2 {
+    type Output = Self;
+
+    fn mul(self, s: f32) -> Self {
+        Vec2::new(self.0 * s, self.1 * s)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Vec3(f32, f32, f32);
 
 #[allow(dead_code)]
@@ -47,6 +132,23 @@ impl Vec3 {
     pub fn as_tuple(&self) -> (f32, f32, f32) {
         (self.0, self.1, self.2)
     }
+
+    /// x and y, dropping z
+    pub fn truncate(&self) -> Vec2 {
+        Vec2::new(self.0, self.1)
+    }
+
+    /// Homogeneous coordinates, `w` is 1 for a point and 0 for a direction
+    pub fn extend(&self, w: f32) -> Vec4 {
+        Vec4::new(self.0, self.1, self.2, w)
+    }
+}
+
+impl From<Vec2> for Vec3 {
+    /// A point of the board plane, z = 0
+    fn from(v: Vec2) -> Self {
+        Vec3::new(v.0, v.1, 0.)
+    }
 }
 
 impl From<(f32, f32, f32)> for Vec3 {
@@ -55,6 +157,14 @@ impl From<(f32, f32, f32)> for Vec3 {
     }
 }
 
+impl Add for Vec3 {
+    type Output = Self;
+
+    fn add(self, other: Vec3) -> Self {
+        Vec3::new(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+    }
+}
+
 impl Sub for Vec3 {
     type Output = Self;
 
@@ -63,7 +173,111 @@ impl Sub for Vec3 {
     }
 }
 
-#[derive(PartialEq, Debug)]
+impl Neg for Vec3 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Vec3::new(-self.0, -self.1, -self.2)
+    }
+}
+
+impl Mul<f32> for Vec3 {
+    type Output = Self;
+
+    fn mul(self, s: f32) -> Self {
+        Vec3::new(self.0 * s, self.1 * s, self.2 * s)
+    }
+}
+
+/// Homogeneous coordinates, what the shaders multiply matrices with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vec4(f32, f32, f32, f32);
+
+#[allow(dead_code)]
+impl Vec4 {
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Vec4 {
+        Vec4(x, y, z, w)
+    }
+
+    pub fn x(&self) -> f32 {
+        self.0
+    }
+
+    pub fn y(&self) -> f32 {
+        self.1
+    }
+
+    pub fn z(&self) -> f32 {
+        self.2
+    }
+
+    pub fn w(&self) -> f32 {
+        self.3
+    }
+
+    pub fn dot(&self, other: &Self) -> f32 {
+        self.0 * other.0 + self.1 * other.1 + self.2 * other.2 + self.3 * other.3
+    }
+
+    /// x, y and z, dropping w
+    pub fn truncate(&self) -> Vec3 {
+        Vec3::new(self.0, self.1, self.2)
+    }
+
+    /// The point divided by w, `None` for a point at infinity
+    pub fn project(&self) -> Option<Vec3> {
+        if self.3.abs() < f32::EPSILON {
+            return None;
+        }
+        Some(self.truncate() * (1. / self.3))
+    }
+
+    pub fn to_array(self) -> [f32; 4] {
+        [self.0, self.1, self.2, self.3]
+    }
+}
+
+impl From<[f32; 4]> for Vec4 {
+    fn from(array: [f32; 4]) -> Self {
+        Vec4::new(array[0], array[1], array[2], array[3])
+    }
+}
+
+impl Add for Vec4 {
+    type Output = Self;
+
+    fn add(self, other: Vec4) -> Self {
+        Vec4::new(
+            self.0 + other.0,
+            self.1 + other.1,
+            self.2 + other.2,
+            self.3 + other.3,
+        )
+    }
+}
+
+impl Sub for Vec4 {
+    type Output = Self;
+
+    fn sub(self, other: Vec4) -> Self {
+        Vec4::new(
+            self.0 - other.0,
+            self.1 - other.1,
+            self.2 - other.2,
+            self.3 - other.3,
+        )
+    }
+}
+
+impl Mul<f32> for Vec4 {
+    type Output = Self;
+
+    fn mul(self, s: f32) -> Self {
+        Vec4::new(self.0 * s, self.1 * s, self.2 * s, self.3 * s)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Mat4 {
     inner: [[f32; 4]; 4],
 }
@@ -140,72 +354,102 @@ impl Mat4 {
         }
     }
 
-    // Returns a projection matrix
-    pub fn projection(aspect_ratio: f32, fov: f32, far: f32, near: f32) -> Self {
+    /// Perspective projection, OpenGL style: the camera looks down -z and the depth between
+    /// `near` and `far` is mapped to -1..1. `fov_y` is the vertical field of view in radians.
+    pub fn perspective(fov_y: f32, aspect_ratio: f32, near: f32, far: f32) -> Self {
+        let f = 1. / (fov_y / 2.).tan();
+        Mat4 {
+            inner: [
+                [f / aspect_ratio, 0., 0., 0.],
+                [0., f, 0., 0.],
+                [0., 0., (far + near) / (near - far), -1.],
+                [0., 0., 2. * far * near / (near - far), 0.],
+            ],
+        }
+    }
+
+    /// Orthographic projection of the box between the planes, OpenGL style: the camera looks
+    /// down -z and the box is mapped to -1..1 on every axis
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
+        let (width, height, depth) = (right - left, top - bottom, far - near);
         Mat4 {
             inner: [
-                [1.0 / (aspect_ratio * f32::tan(fov / 2.0)), 0.0, 0.0, 0.0],
-                [0.0, 1.0 / f32::tan(fov / 2.0), 0.0, 0.0],
+                [2. / width, 0., 0., 0.],
+                [0., 2. / height, 0., 0.],
+                [0., 0., -2. / depth, 0.],
                 [
-                    0.0,
-                    0.0,
-                    -(far + near) / (far - near),
-                    -2.0 * far * near / (far - near),
+                    -(right + left) / width,
+                    -(top + bottom) / height,
+                    -(far + near) / depth,
+                    1.,
                 ],
-                [0.0, 0.0, -1.0, 1.],
             ],
         }
     }
 
+    /// View from `eye` towards `center`, OpenGL style: the camera looks down -z with `up`
+    /// pointing up the screen and x to the right
     pub fn look_at(eye: Vec3, center: Vec3, up: Vec3) -> Mat4 {
-        // https://learn.microsoft.com/en-us/windows/win32/direct3d9/d3dxmatrixlookatlh
-        let z_axis = (center - eye).normalize();
-        let x_axis = up.cross(&z_axis).normalize();
-        let y_axis = z_axis.cross(&x_axis);
-
-        let mut view = Self::new();
-        view[0][0] = x_axis.0;
-        view[1][0] = x_axis.1;
-        view[2][0] = x_axis.2;
-        view[0][1] = y_axis.0;
-        view[1][1] = y_axis.1;
-        view[2][1] = y_axis.2;
-        view[0][2] = z_axis.0;
-        view[1][2] = z_axis.1;
-        view[2][2] = z_axis.2;
-        view[3][0] = -x_axis.dot(&eye);
-        view[3][1] = -y_axis.dot(&eye);
-        view[3][2] = -z_axis.dot(&eye);
-        view[3][3] = 1.;
+        let forward = (center - eye).normalize();
+        let right = forward.cross(&up).normalize();
+        let up = right.cross(&forward);
+
+        let mut view = Self::identity();
+        for (column, axis) in [right, up, -forward].iter().enumerate() {
+            view[0][column] = axis.0;
+            view[1][column] = axis.1;
+            view[2][column] = axis.2;
+            view[3][column] = -axis.dot(&eye);
+        }
         view
     }
 
-    pub fn to_array(&self) -> [[f32; 4]; 4] {
+    pub fn to_array(self) -> [[f32; 4]; 4] {
         self.inner
     }
 
-    /// Rotation around the z-axis by specified angle in radians.
+    /// Rotation around the z-axis by `angle` radians, counter-clockwise
+    pub fn rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let mut rotation = Self::identity();
+        rotation[0][0] = cos;
+        rotation[0][1] = sin;
+        rotation[1][0] = -sin;
+        rotation[1][1] = cos;
+        rotation
+    }
+
+    pub fn translation(x: f32, y: f32, z: f32) -> Self {
+        let mut translation = Self::identity();
+        translation[3] = [x, y, z, 1.];
+        translation
+    }
+
+    pub fn scaling(x: f32, y: f32, z: f32) -> Self {
+        let mut scaling = Self::identity();
+        scaling[0][0] = x;
+        scaling[1][1] = y;
+        scaling[2][2] = z;
+        scaling
+    }
+
+    /// Rotate around the z-axis by `angle` radians, counter-clockwise. Like `translate` and
+    /// `scale`, this is `self * rotation`: the rotation happens before what the matrix already
+    /// does, so `m.translate(..).rotate(..)` rotates first and translates after.
     pub fn rotate(&mut self, angle: f32) -> &mut Self {
-        self.inner[0][0] *= f32::cos(angle);
-        self.inner[0][1] *= -f32::sin(angle);
-        self.inner[1][0] *= f32::sin(angle);
-        self.inner[1][1] *= f32::cos(angle);
+        *self = *self * Self::rotation(angle);
         self
     }
 
-    /// Moves the position to specified coordinates.
+    /// Move by (x, y, z), before what the matrix already does
     pub fn translate(&mut self, x: f32, y: f32, z: f32) -> &mut Self {
-        self.inner[3][0] = x;
-        self.inner[3][1] = y;
-        self.inner[3][2] = z;
+        *self = *self * Self::translation(x, y, z);
         self
     }
 
-    /// Scale individal axis.
+    /// Scale each axis, before what the matrix already does
     pub fn scale(&mut self, x: f32, y: f32, z: f32) -> &mut Self {
-        self.inner[0][0] *= x;
-        self.inner[1][1] *= y;
-        self.inner[2][2] *= z;
+        *self = *self * Self::scaling(x, y, z);
         self
     }
 
@@ -214,6 +458,72 @@ impl Mat4 {
         self.scale(s, s, s)
     }
 
+    /// Transforms a homogeneous point the way the shaders do, `u_mvp * v` in glsl. The inner
+    /// arrays are uploaded as columns, so the point is multiplied as a row vector.
+    pub fn transform(&self, v: [f32; 4]) -> [f32; 4] {
+        let mut result = [0.0; 4];
+        for (j, value) in result.iter_mut().enumerate() {
+            *value = (0..4).map(|i| v[i] * self.inner[i][j]).sum();
+        }
+        result
+    }
+
+    /// Where the point is drawn, after the perspective divide. `None` for a point sent to
+    /// infinity.
+    pub fn transform_point(&self, point: Vec3) -> Option<Vec3> {
+        (*self * point.extend(1.)).project()
+    }
+
+    /// The direction transformed, translations don't move it
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3 {
+        (*self * vector.extend(0.)).truncate()
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut transpose = Self::new();
+        for i in 0..4 {
+            for j in 0..4 {
+                transpose[j][i] = self[i][j];
+            }
+        }
+        transpose
+    }
+
+    /// Inverse of the matrix, `None` if it is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        // Gauss-Jordan elimination with partial pivoting
+        let mut m = self.inner;
+        let mut inverse = Self::identity().inner;
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|&a, &b| m[a][col].abs().total_cmp(&m[b][col].abs()))?;
+            if m[pivot][col].abs() < f32::EPSILON {
+                return None;
+            }
+            m.swap(col, pivot);
+            inverse.swap(col, pivot);
+            let scale = m[col][col];
+            for j in 0..4 {
+                m[col][j] /= scale;
+                inverse[col][j] /= scale;
+            }
+            for row in (0..4).filter(|&row| row != col) {
+                let factor = m[row][col];
+                for j in 0..4 {
+                    m[row][j] -= factor * m[col][j];
+                    inverse[row][j] -= factor * inverse[col][j];
+                }
+            }
+        }
+        Some(inverse.into())
+    }
+
+    /// Maps normalized device coordinates back through the matrix, the opposite of
+    /// `transform` followed by the perspective divide. `None` if the matrix is singular or the
+    /// point lies at infinity.
+    pub fn unproject(&self, x: f32, y: f32, depth: f32) -> Option<Vec3> {
+        self.inverse()?.transform_point(Vec3::new(x, y, depth))
+    }
+
     pub fn multiply_by(&mut self, other: &Self) -> &mut Self {
         let mut result = [[0.0; 4]; 4];
         for i in 0..4 {
@@ -228,6 +538,25 @@ impl Mat4 {
     }
 }
 
+impl Mul for Mat4 {
+    type Output = Self;
+
+    /// `self * other` as glsl reads it, `other` is applied first
+    fn mul(self, other: Mat4) -> Self {
+        let mut product = other;
+        product.multiply_by(&self);
+        product
+    }
+}
+
+impl Mul<Vec4> for Mat4 {
+    type Output = Vec4;
+
+    fn mul(self, v: Vec4) -> Vec4 {
+        self.transform(v.to_array()).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -267,4 +596,204 @@ mod tests {
 
         println!("{}", mat1);
     }
+
+    fn close(a: &Mat4, b: &Mat4) -> bool {
+        (0..4).all(|i| (0..4).all(|j| (a[i][j] - b[i][j]).abs() < 1e-4))
+    }
+
+    #[test]
+    fn inverse_undoes_multiplication() {
+        let mut mat = Mat4::perspective(1.2, 1.5, 0.5, 20.);
+        let mut transform = Mat4::identity();
+        transform.translate(-0.4, -0.6, 0.).scale_uniformly(0.13);
+        mat.multiply_by(&transform);
+        let inverse = mat.inverse().expect("projection is invertible");
+        assert!(close(mat.multiply_by(&inverse), &Mat4::identity()));
+        assert_eq!(Mat4::new().inverse(), None);
+    }
+
+    fn close_vec(a: Vec3, b: Vec3) -> bool {
+        (a - b).magnitude() < 1e-4
+    }
+
+    #[test]
+    fn rotations_compose() {
+        use std::f32::consts::{FRAC_PI_2, PI};
+        let mut quarter = Mat4::identity();
+        quarter.rotate(FRAC_PI_2);
+        let x = Vec3::new(1., 0., 0.);
+        assert!(close_vec(
+            quarter.transform_vector(x),
+            Vec3::new(0., 1., 0.)
+        ));
+        quarter.rotate(FRAC_PI_2);
+        assert!(close_vec(
+            quarter.transform_vector(x),
+            Vec3::new(-1., 0., 0.)
+        ));
+        assert!(close(&quarter, &Mat4::rotation(PI)));
+        quarter.rotate(-PI);
+        assert!(close(&quarter, &Mat4::identity()));
+        assert!(close(
+            &(Mat4::rotation(0.3) * Mat4::rotation(0.5)),
+            &Mat4::rotation(0.8)
+        ));
+        let turned = Vec2::new(1., 0.).rotate(FRAC_PI_2);
+        assert!(close_vec(turned.into(), Vec3::new(0., 1., 0.)));
+    }
+
+    #[test]
+    fn transforms_apply_last_first() {
+        let mut moved = Mat4::identity();
+        moved.translate(1., 2., 3.).translate(4., 5., 6.);
+        let origin = Vec3::new(0., 0., 0.);
+        assert!(close_vec(
+            moved.transform_point(origin).unwrap(),
+            Vec3::new(5., 7., 9.)
+        ));
+
+        // Scaled first, then moved
+        let mut m = Mat4::identity();
+        m.translate(10., 0., 0.).scale(2., 3., 1.);
+        let point = Vec3::new(1., 1., 0.);
+        assert!(close_vec(
+            m.transform_point(point).unwrap(),
+            Vec3::new(12., 3., 0.)
+        ));
+        // Rotated first, then moved
+        let mut m = Mat4::identity();
+        m.translate(10., 0., 0.).rotate(std::f32::consts::FRAC_PI_2);
+        assert!(close_vec(
+            m.transform_point(Vec3::new(1., 0., 0.)).unwrap(),
+            Vec3::new(10., 1., 0.)
+        ));
+
+        // The layout the camera's board transform has always had
+        let mut board = Mat4::identity();
+        board.translate(-0.4, -0.6, 0.).scale_uniformly(0.13);
+        assert_eq!(
+            board,
+            [
+                [0.13, 0., 0., 0.],
+                [0., 0.13, 0., 0.],
+                [0., 0., 0.13, 0.],
+                [-0.4, -0.6, 0., 1.],
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn vectors_ignore_translation() {
+        let mut m = Mat4::identity();
+        m.translate(5., -2., 1.).scale(2., 2., 2.);
+        let v = Vec3::new(1., 2., 3.);
+        assert!(close_vec(m.transform_vector(v), v * 2.));
+        assert_eq!(Vec4::new(1., 2., 3., 0.).project(), None);
+    }
+
+    #[test]
+    fn operators_read_like_glsl() {
+        let mut a = Mat4::rotation(0.4);
+        a.translate(1., 2., 0.);
+        let b = Mat4::orthographic(-3., 5., -2., 4., 0.5, 10.);
+        let v = Vec4::new(0.5, -1., 2., 1.);
+        let (left, right) = ((a * b) * v, a * (b * v));
+        assert!(close_vec(left.truncate(), right.truncate()));
+        assert!((left.w() - right.w()).abs() < 1e-4);
+        let mut product = b;
+        product.multiply_by(&a);
+        assert_eq!(a * b, product);
+        assert_eq!(Mat4::identity() * v, v);
+    }
+
+    #[test]
+    fn transpose() {
+        let m = Mat4::translation(1., 2., 3.);
+        let t = m.transpose();
+        assert_eq!([t[0][3], t[1][3], t[2][3]], [1., 2., 3.]);
+        assert_eq!(t.transpose(), m);
+        let (a, b) = (Mat4::rotation(0.7), Mat4::scaling(1., 2., 3.));
+        assert!(close(
+            &(a * b).transpose(),
+            &(b.transpose() * a.transpose())
+        ));
+    }
+
+    #[test]
+    fn look_at_keeps_the_screen_axes() {
+        // From above, the board isn't mirrored
+        let eye = Vec3::new(3., 4., 10.);
+        let above = Mat4::look_at(eye, Vec3::new(3., 4., 0.), Vec3::new(0., 1., 0.));
+        let seen = |view: &Mat4, x, y| view.transform_point(Vec3::new(x, y, 0.)).unwrap();
+        assert!(close_vec(seen(&above, 3., 4.), Vec3::new(0., 0., -10.)));
+        assert!(close_vec(seen(&above, 4., 6.), Vec3::new(1., 2., -10.)));
+        // Leaning back, what is further up the board is further away
+        let tilted = Mat4::look_at(
+            Vec3::new(0., -5., 5.),
+            Vec3::new(0., 0., 0.),
+            Vec3::new(0., 1., 0.),
+        );
+        let (near, far) = (seen(&tilted, 0., -1.), seen(&tilted, 0., 1.));
+        assert!(far.z() < near.z() && far.y() > near.y());
+        assert!(seen(&tilted, 1., 0.).x() > 0.);
+    }
+
+    #[test]
+    fn orthographic_maps_the_box() {
+        let ortho = Mat4::orthographic(-4., 6., -1., 3., 1., 11.);
+        let ndc = |x, y, z| ortho.transform_point(Vec3::new(x, y, z)).unwrap();
+        assert!(close_vec(ndc(-4., -1., -1.), Vec3::new(-1., -1., -1.)));
+        assert!(close_vec(ndc(6., 3., -11.), Vec3::new(1., 1., 1.)));
+        assert!(close_vec(ndc(1., 1., -6.), Vec3::new(0., 0., 0.)));
+    }
+
+    #[test]
+    fn perspective_shrinks_with_distance() {
+        let fov = std::f32::consts::FRAC_PI_2;
+        let perspective = Mat4::perspective(fov, 2., 1., 100.);
+        let ndc = |x, y, z| perspective.transform_point(Vec3::new(x, y, z)).unwrap();
+        // A 90° field of view sees as far up as it is deep
+        assert!(close_vec(ndc(2., 1., -1.), Vec3::new(1., 1., -1.)));
+        assert!((ndc(0., 0., -100.).z() - 1.).abs() < 1e-4);
+        let (near, far) = (ndc(1., 0., -2.), ndc(1., 0., -4.));
+        assert!((near.x() - 2. * far.x()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn inverse_round_trips() {
+        let mut model = Mat4::identity();
+        model.translate(3., -7., 0.5).rotate(1.1).scale(2., 0.5, 4.);
+        let view = Mat4::look_at(
+            Vec3::new(2., -3., 10.),
+            Vec3::new(0., 0., 0.),
+            Vec3::new(0., 1., 0.),
+        );
+        let matrices = [
+            model,
+            view,
+            Mat4::orthographic(-10., 30., -5., 25., -1., 1.),
+            Mat4::perspective(0.8, 1.5, 0.1, 50.),
+            Mat4::perspective(0.8, 1.5, 0.1, 50.) * view * model,
+        ];
+        let point = Vec3::new(1.5, -2., 0.25);
+        for m in matrices {
+            let inverse = m.inverse().expect("transforms are invertible");
+            assert!(close(&(m * inverse), &Mat4::identity()), "{}", m);
+            assert!(close(&(inverse * m), &Mat4::identity()), "{}", m);
+            let there = m.transform_point(point).unwrap();
+            assert!(close_vec(inverse.transform_point(there).unwrap(), point));
+        }
+    }
+
+    #[test]
+    fn unproject_reverses_transform() {
+        let mut mat = Mat4::identity();
+        mat.translate(2., -1., 0.5).scale(3., 2., 1.);
+        let [x, y, z, w] = mat.transform([1., 4., -2., 1.]);
+        let point = mat.unproject(x / w, y / w, z / w).unwrap();
+        assert!((point.x() - 1.).abs() < 1e-4);
+        assert!((point.y() - 4.).abs() < 1e-4);
+        assert!((point.z() + 2.).abs() < 1e-4);
+    }
 }
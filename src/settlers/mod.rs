@@ -4,4 +4,8 @@ use board::map::Board;
 pub mod camera;
 pub mod game;
 pub mod matrix;
+pub mod palette;
+pub mod render;
+pub mod rules;
 pub mod shader;
+pub mod ui;
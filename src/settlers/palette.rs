@@ -0,0 +1,416 @@
+//! Colours of the players, shared by the board renderer and the UI.
+//!
+//! A palette starts from a preset, including colour-blind safe ones, and can be customised
+//! with a palette file:
+//!
+//! ```text
+//! // Comments start with two slashes
+//! preset deuteranopia
+//! patterns on
+//! player 0 #E69F00
+//! player 1 #56B4E9 dots
+//! ```
+//!
+//! Patterns are drawn over the player colour so players can be told apart without it.
+
+use crate::settlers::rules::player::PlayerId;
+use crate::settlers::rules::MAX_PLAYERS;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Red, green and blue, from 0 to 1
+pub type Rgb = [f32; 3];
+
+/// Colour from its `0xRRGGBB` hex code
+pub const fn rgb(hex: u32) -> Rgb {
+    [
+        ((hex >> 16) & 255) as f32 / 255.,
+        ((hex >> 8) & 255) as f32 / 255.,
+        (hex & 255) as f32 / 255.,
+    ]
+}
+
+/// Overlay drawn on a player's pieces, ids match `str.f.glsl`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pattern {
+    Solid,
+    Stripes,
+    Dots,
+    Checks,
+    Crosshatch,
+    Waves,
+}
+
+impl Pattern {
+    pub const ALL: [Pattern; 6] = [
+        Pattern::Solid,
+        Pattern::Stripes,
+        Pattern::Dots,
+        Pattern::Checks,
+        Pattern::Crosshatch,
+        Pattern::Waves,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pattern::Solid => "solid",
+            Pattern::Stripes => "stripes",
+            Pattern::Dots => "dots",
+            Pattern::Checks => "checks",
+            Pattern::Crosshatch => "crosshatch",
+            Pattern::Waves => "waves",
+        }
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Pattern::ALL
+            .into_iter()
+            .find(|pattern| pattern.name() == name)
+            .ok_or(())
+    }
+}
+
+/// Palettes shipped with the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Classic,
+    /// Red-green, missing green cones
+    Deuteranopia,
+    /// Red-green, missing red cones
+    Protanopia,
+    /// Blue-yellow
+    Tritanopia,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [
+        Preset::Classic,
+        Preset::Deuteranopia,
+        Preset::Protanopia,
+        Preset::Tritanopia,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Classic => "classic",
+            Preset::Deuteranopia => "deuteranopia",
+            Preset::Protanopia => "protanopia",
+            Preset::Tritanopia => "tritanopia",
+        }
+    }
+
+    /// One colour per player. The colour-blind presets are picked from the Okabe-Ito palette
+    /// so that they stay apart in hue and brightness for that kind of colour blindness.
+    pub fn colors(&self) -> [Rgb; MAX_PLAYERS] {
+        match self {
+            Preset::Classic => [
+                rgb(0xCC2626),
+                rgb(0x2659CC),
+                rgb(0xF2F2F2),
+                rgb(0xF28C1A),
+                rgb(0x339933),
+                rgb(0x804D26),
+            ],
+            Preset::Deuteranopia => [
+                rgb(0xE69F00),
+                rgb(0x0072B2),
+                rgb(0xF2F2F2),
+                rgb(0x56B4E9),
+                rgb(0xF0E442),
+                rgb(0xCC79A7),
+            ],
+            Preset::Protanopia => [
+                rgb(0x0072B2),
+                rgb(0xF0E442),
+                rgb(0xF2F2F2),
+                rgb(0x56B4E9),
+                rgb(0xE69F00),
+                rgb(0x4D4D4D),
+            ],
+            Preset::Tritanopia => [
+                rgb(0xD55E00),
+                rgb(0x009E73),
+                rgb(0xF2F2F2),
+                rgb(0xCC79A7),
+                rgb(0x4D4D4D),
+                rgb(0x8C1A1A),
+            ],
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Preset::ALL
+            .into_iter()
+            .find(|preset| preset.name() == name)
+            .ok_or(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    preset: Preset,
+    colors: [Rgb; MAX_PLAYERS],
+    patterns: [Pattern; MAX_PLAYERS],
+    /// Whether patterns are drawn, off unless asked for
+    show_patterns: bool,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::preset(Preset::Classic)
+    }
+}
+
+impl Palette {
+    pub fn preset(preset: Preset) -> Self {
+        Palette {
+            preset,
+            colors: preset.colors(),
+            patterns: Pattern::ALL,
+            show_patterns: false,
+        }
+    }
+
+    pub fn from_file(file: impl AsRef<Path>) -> Result<Self, ParsePaletteError> {
+        let content = read_to_string(file).map_err(|e| ParsePaletteError {
+            kind: PaletteErrorKind::FileNotFound(e.to_string()),
+            line: 0,
+        })?;
+        content.parse()
+    }
+
+    /// Preset the palette started from
+    pub fn base(&self) -> Preset {
+        self.preset
+    }
+
+    pub fn color(&self, player: PlayerId) -> Rgb {
+        self.colors[player as usize % MAX_PLAYERS]
+    }
+
+    /// Pattern drawn over the player's pieces, solid while patterns are hidden
+    pub fn pattern(&self, player: PlayerId) -> Pattern {
+        if self.show_patterns {
+            self.patterns[player as usize % MAX_PLAYERS]
+        } else {
+            Pattern::Solid
+        }
+    }
+
+    pub fn show_patterns(&self) -> bool {
+        self.show_patterns
+    }
+
+    pub fn set_show_patterns(&mut self, show: bool) {
+        self.show_patterns = show;
+    }
+
+    /// Colour and pattern id of every player, one texel each for the structure shader
+    pub fn texels(&self) -> Vec<(f32, f32, f32, f32)> {
+        (0..MAX_PLAYERS as PlayerId)
+            .map(|player| {
+                let [r, g, b] = self.color(player);
+                (r, g, b, self.pattern(player) as u8 as f32)
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Palette {
+    type Err = ParsePaletteError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let mut palette = Palette::default();
+        for (i, line) in content.lines().enumerate() {
+            let error = |kind| ParsePaletteError { kind, line: i + 1 };
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens.as_slice() {
+                [] => (),
+                [comment, ..] if comment.starts_with("//") => (),
+                ["preset", name] => {
+                    let preset = name
+                        .parse()
+                        .map_err(|_| error(PaletteErrorKind::UnknownPreset(name.to_string())))?;
+                    // A preset replaces the colours and patterns set before it
+                    palette = Palette {
+                        show_patterns: palette.show_patterns,
+                        ..Palette::preset(preset)
+                    };
+                }
+                ["patterns", "on"] => palette.show_patterns = true,
+                ["patterns", "off"] => palette.show_patterns = false,
+                ["player", player, color, rest @ ..] if rest.len() <= 1 => {
+                    let player = player
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|player| *player < MAX_PLAYERS)
+                        .ok_or_else(|| {
+                            error(PaletteErrorKind::InvalidPlayer(player.to_string()))
+                        })?;
+                    palette.colors[player] = parse_color(color)
+                        .ok_or_else(|| error(PaletteErrorKind::InvalidColor(color.to_string())))?;
+                    if let Some(pattern) = rest.first() {
+                        palette.patterns[player] = pattern.parse().map_err(|_| {
+                            error(PaletteErrorKind::UnknownPattern(pattern.to_string()))
+                        })?;
+                    }
+                }
+                _ => return Err(error(PaletteErrorKind::UnexpectedLine(line.trim().into()))),
+            }
+        }
+        Ok(palette)
+    }
+}
+
+/// Colour written as `#RRGGBB`
+fn parse_color(token: &str) -> Option<Rgb> {
+    let hex = token.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    u32::from_str_radix(hex, 16).ok().map(rgb)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteErrorKind {
+    FileNotFound(String),
+    UnexpectedLine(String),
+    UnknownPreset(String),
+    InvalidPlayer(String),
+    InvalidColor(String),
+    UnknownPattern(String),
+}
+
+impl Display for PaletteErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FileNotFound(reason) => write!(f, "palette file could not be read ({})", reason),
+            Self::UnexpectedLine(line) => write!(
+                f,
+                "expected `preset`, `patterns` or `player`, found `{}`",
+                line
+            ),
+            Self::UnknownPreset(name) => write!(f, "unknown preset `{}`", name),
+            Self::InvalidPlayer(player) => write!(
+                f,
+                "player must be a number below {}, found `{}`",
+                MAX_PLAYERS, player
+            ),
+            Self::InvalidColor(color) => {
+                write!(f, "colours are written #RRGGBB, found `{}`", color)
+            }
+            Self::UnknownPattern(name) => write!(f, "unknown pattern `{}`", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsePaletteError {
+    pub kind: PaletteErrorKind,
+    /// 1-based, 0 when the file couldn't be read at all
+    pub line: usize,
+}
+
+impl Display for ParsePaletteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}: ", self.line)?;
+        }
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl Error for ParsePaletteError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_are_distinct() {
+        for preset in Preset::ALL {
+            let colors = preset.colors();
+            for (i, a) in colors.iter().enumerate() {
+                assert!(
+                    colors[i + 1..].iter().all(|b| a != b),
+                    "{} repeats a colour",
+                    preset.name()
+                );
+            }
+            assert_eq!(preset.name().parse(), Ok(preset));
+        }
+    }
+
+    #[test]
+    fn colors_wrap_around() {
+        let palette = Palette::preset(Preset::Deuteranopia);
+        assert_eq!(palette.color(0), rgb(0xE69F00));
+        assert_eq!(palette.color(MAX_PLAYERS as PlayerId), palette.color(0));
+        assert_eq!(
+            palette.color(1),
+            [0., 0x72 as f32 / 255., 0xB2 as f32 / 255.]
+        );
+    }
+
+    #[test]
+    fn patterns_are_optional() {
+        let mut palette = Palette::default();
+        assert_eq!(palette.pattern(1), Pattern::Solid);
+        palette.set_show_patterns(true);
+        assert_eq!(palette.pattern(1), Pattern::Stripes);
+        assert_eq!(palette.texels()[1].3, 1.);
+    }
+
+    #[test]
+    fn parses_palette_file() {
+        let palette: Palette = "
+            // Deuteranopia with a darker first player
+            preset deuteranopia
+            patterns on
+            player 0 #102030
+            player 2 #FFFFFF waves
+        "
+        .parse()
+        .unwrap();
+        assert_eq!(palette.base(), Preset::Deuteranopia);
+        assert_eq!(palette.color(0), rgb(0x102030));
+        assert_eq!(palette.color(1), Preset::Deuteranopia.colors()[1]);
+        assert_eq!(palette.pattern(0), Pattern::Solid);
+        assert_eq!(palette.pattern(2), Pattern::Waves);
+    }
+
+    #[test]
+    fn palette_errors() {
+        let kind = |content: &str| content.parse::<Palette>().unwrap_err().kind;
+        assert_eq!(
+            kind("preset sepia"),
+            PaletteErrorKind::UnknownPreset("sepia".into())
+        );
+        assert_eq!(
+            kind("player 6 #000000"),
+            PaletteErrorKind::InvalidPlayer("6".into())
+        );
+        assert_eq!(
+            kind("player 0 red"),
+            PaletteErrorKind::InvalidColor("red".into())
+        );
+        assert_eq!(
+            kind("player 0 #000000 plaid"),
+            PaletteErrorKind::UnknownPattern("plaid".into())
+        );
+        let error = "\npatterns maybe".parse::<Palette>().unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected `preset`, `patterns` or `player`, found `patterns maybe`"
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Drawing a board: the sea around it, its hex tiles and the structures built on them. The game
+//! and the map editor draw their boards the same way.
+
+use crate::settlers::board::background::quad;
+use crate::settlers::board::building::Structure;
+use crate::settlers::board::map::Board;
+use crate::settlers::board::picking::Pick;
+use crate::settlers::camera::Camera;
+use crate::settlers::matrix::Mat4;
+use crate::settlers::palette::Palette;
+use crate::settlers::shader::{ProgramManager, TextureManager};
+use glium::backend::Facade;
+use glium::index::NoIndices;
+use glium::texture::{ClientFormat, MipmapsOption, RawImage2d, Texture2d, UncompressedFloatFormat};
+use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter};
+use glium::{DrawParameters, Frame, IndexBuffer, Surface, VertexBuffer};
+use std::borrow::Cow;
+use std::time::Instant;
+use winit::dpi::PhysicalSize;
+
+/// How the board is looked at
+pub struct BoardView {
+    pub mvp: Mat4,
+    /// Turn of the board on screen, counter-clockwise in radians
+    pub rotation: f32,
+    pub window: PhysicalSize<u32>,
+    /// What the cursor is over, highlighted
+    pub hovered: Option<Pick>,
+}
+
+impl BoardView {
+    /// The board seen from the camera, nothing under the cursor
+    pub fn new(camera: &Camera) -> Self {
+        BoardView {
+            mvp: camera.mvp(),
+            rotation: camera.rotation(),
+            window: camera.viewport().into(),
+            hovered: None,
+        }
+    }
+}
+
+pub struct BoardRenderer<'p> {
+    program_manager: ProgramManager<'p>,
+    texture_manager: TextureManager<'p>,
+    // Keep track of the program time
+    time: Instant,
+}
+
+impl<'p> BoardRenderer<'p> {
+    pub fn new() -> Self {
+        BoardRenderer {
+            program_manager: ProgramManager::new(),
+            texture_manager: TextureManager::new(),
+            time: Instant::now(),
+        }
+    }
+
+    /// Compile the board shaders and load the `textures`, given as (name, path)
+    pub fn load(&mut self, facade: &dyn Facade, textures: &[(&'p str, &'static str)]) {
+        for &(name, path) in textures {
+            self.texture_manager
+                .add_texture(facade, name, path)
+                .unwrap_or_else(|_| panic!("{} texture should be found", name));
+        }
+        self.program_manager
+            .add_program(
+                facade,
+                "hex",
+                "glsl/hex/hex.v.glsl",
+                "glsl/hex/hex.f.glsl",
+                Some("glsl/hex/hex.g.glsl"),
+            )
+            .expect("Hex shaders properly compiling");
+        self.program_manager
+            .add_program(facade, "bg", "glsl/bg/bg.v.glsl", "glsl/bg/bg.f.glsl", None)
+            .expect("Background shaders properly compiling");
+        self.program_manager
+            .add_program(
+                facade,
+                "structures",
+                "glsl/structure/str.v.glsl",
+                "glsl/structure/str.f.glsl",
+                Some("glsl/structure/str.g.glsl"),
+            )
+            .expect("Structure shaders properly compiling");
+    }
+
+    /// Release the shaders and textures
+    pub fn unload(&mut self) {
+        self.program_manager = ProgramManager::new();
+        self.texture_manager = TextureManager::new();
+    }
+
+    /// The sea around the land, then the hex tiles
+    pub fn draw_hexes(
+        &self,
+        facade: &dyn Facade,
+        frame: &mut Frame,
+        board: &Board,
+        view: &BoardView,
+    ) {
+        let mvp = view.mvp.to_array();
+        // ============== Background ===============
+        // Land hex positions go in a texture, one texel each, so boards can be of any size. They
+        // stay in world units: the shader maps each pixel back onto the board the way picking
+        // does, with the inverse of the matrix the hexes are drawn with.
+        let mut hex_positions: Vec<(f32, f32)> = board
+            .hex_buffers(None)
+            .iter()
+            .filter(|vert| vert.is_land())
+            .map(|vert| vert.position())
+            .collect();
+        let inverse_mvp = view.mvp.inverse().unwrap_or_else(Mat4::identity);
+        let total_hex = hex_positions.len() as u32;
+        // A texture can't be empty, the shader only reads `total_hex` texels
+        if hex_positions.is_empty() {
+            hex_positions.push((0., 0.));
+        }
+        let width = hex_positions.len() as u32;
+        let hex_pos_texture = Texture2d::with_format(
+            facade,
+            RawImage2d {
+                data: Cow::Owned(hex_positions),
+                width,
+                height: 1,
+                format: ClientFormat::F32F32,
+            },
+            UncompressedFloatFormat::F32F32,
+            MipmapsOption::NoMipmap,
+        )
+        .unwrap();
+        let bg_vbo = VertexBuffer::new(facade, &quad::VERTICES).unwrap();
+        let bg_ebo = IndexBuffer::new(
+            facade,
+            glium::index::PrimitiveType::TrianglesList,
+            &quad::INDICES,
+        )
+        .unwrap();
+        frame
+            .draw(
+                &bg_vbo,
+                &bg_ebo,
+                self.program_manager
+                    .program("bg")
+                    .expect("Background program exists"),
+                &uniform! {
+                    u_inverse_mvp: inverse_mvp.to_array(),
+                    total_hex: total_hex,
+                    hex_positions: hex_pos_texture
+                        .sampled()
+                        .magnify_filter(MagnifySamplerFilter::Nearest)
+                        .minify_filter(MinifySamplerFilter::Nearest),
+                    u_resolution: (view.window.width, view.window.height),
+                    u_time: self.time.elapsed().as_secs_f32(),
+                },
+                &Default::default(),
+            )
+            .unwrap();
+
+        // ============== Hex tiles ================
+        let vertices = board.hex_buffers(view.hovered);
+        let vertex_buffer = VertexBuffer::new(facade, &vertices).unwrap();
+        let index_buffer = NoIndices(glium::index::PrimitiveType::Points);
+        frame
+            .draw(
+                &vertex_buffer,
+                &index_buffer,
+                self.program_manager
+                    .program("hex")
+                    .expect("Hex program exists"),
+                &uniform! { u_mvp: mvp,
+                    u_rotation: view.rotation,
+                    u_resolution: (view.window.width, view.window.height),
+                    u_time: self.time.elapsed().as_secs_f32(),
+                    texture_map_hex: self.texture_manager.texture("hex_tm").unwrap().sampled()
+                        .wrap_function(glium::uniforms::SamplerWrapFunction::BorderClamp)
+                        .magnify_filter(MagnifySamplerFilter::Nearest)
+                        .minify_filter(MinifySamplerFilter::Nearest),
+                    texture_map_chances: self.texture_manager.texture("chance_tm").unwrap().sampled()
+                        .wrap_function(glium::uniforms::SamplerWrapFunction::BorderClamp)
+                        .magnify_filter(MagnifySamplerFilter::Nearest)
+                        .minify_filter(MinifySamplerFilter::Nearest),
+                },
+                &transparency(),
+            )
+            .unwrap();
+    }
+
+    /// Settlements, cities, roads... of the board, then the `ghosts` previewing new ones
+    pub fn draw_structures(
+        &self,
+        facade: &dyn Facade,
+        frame: &mut Frame,
+        board: &Board,
+        view: &BoardView,
+        ghosts: &[Structure],
+        palette: &Palette,
+    ) {
+        // Player colours, one texel each like the hex positions
+        let palette = palette.texels();
+        let width = palette.len() as u32;
+        let palette_texture = Texture2d::with_format(
+            facade,
+            RawImage2d {
+                data: Cow::Owned(palette),
+                width,
+                height: 1,
+                format: ClientFormat::F32F32F32F32,
+            },
+            UncompressedFloatFormat::F32F32F32F32,
+            MipmapsOption::NoMipmap,
+        )
+        .unwrap();
+        let vertices = board.building_buffers(view.hovered, ghosts);
+        let vertex_buffer = VertexBuffer::new(facade, &vertices).unwrap();
+        let index_buffer = NoIndices(glium::index::PrimitiveType::Points);
+        frame
+            .draw(
+                &vertex_buffer,
+                &index_buffer,
+                self.program_manager
+                    .program("structures")
+                    .expect("structure program exists"),
+                &uniform! { u_mvp: view.mvp.to_array(),
+                    u_rotation: view.rotation,
+                    u_resolution: (view.window.width, view.window.height),
+                    u_time: self.time.elapsed().as_secs_f32(),
+                    player_palette: palette_texture
+                        .sampled()
+                        .magnify_filter(MagnifySamplerFilter::Nearest)
+                        .minify_filter(MinifySamplerFilter::Nearest),
+                },
+                &transparency(),
+            )
+            .unwrap();
+    }
+}
+
+/// Blending for png transparency
+fn transparency() -> DrawParameters<'static> {
+    use glium::BlendingFunction::Addition;
+    use glium::LinearBlendingFactor::{OneMinusSourceAlpha, SourceAlpha};
+    DrawParameters {
+        blend: glium::Blend {
+            color: Addition {
+                source: SourceAlpha,
+                destination: OneMinusSourceAlpha,
+            },
+            alpha: Addition {
+                source: SourceAlpha,
+                destination: OneMinusSourceAlpha,
+            },
+            constant_value: (0.0, 0.0, 0.0, 0.0),
+        },
+        ..Default::default()
+    }
+}
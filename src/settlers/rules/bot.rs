@@ -0,0 +1,233 @@
+//! Computer players. A bot plays the first sensible legal move it finds: it settles next to
+//! the most productive hexes, builds whatever it can afford and otherwise ends its turn.
+
+use super::player::{Hand, PlayerId};
+use super::{Action, Build, Game, GameState, Phase};
+use crate::settlers::board::building::Structure;
+use crate::settlers::board::card::ResourceCard;
+use crate::settlers::board::map::Board;
+use crate::settlers::board::topology::{HexCoord, Intersection};
+
+/// Builds a bot tries each turn, the most valuable first
+const BUILD_ORDER: [Build; 4] = [Build::City, Build::Settlement, Build::Road, Build::Ship];
+
+/// The next action one of the `bots` wants to play, and who plays it. `None` while waiting on
+/// a human, or once the game is over.
+pub fn next_action(game: &Game, bots: &[PlayerId]) -> Option<(PlayerId, Action)> {
+    let state = game.state();
+    match state.phase() {
+        // Everyone owing cards plays at once
+        Phase::Discard(owing) => {
+            owing
+                .iter()
+                .find(|(player, _)| bots.contains(player))
+                .map(|(player, amount)| {
+                    let hand = &state.player(*player).hand;
+                    (*player, Action::Discard(discard(hand, *amount)))
+                })
+        }
+        Phase::PickResources { owed, .. } => owed
+            .iter()
+            .find(|(player, _)| bots.contains(player))
+            .map(|(player, amount)| {
+                let hand = &state.player(*player).hand;
+                (*player, Action::PickResources(pick(hand, *amount)))
+            }),
+        Phase::Finished(_) => None,
+        _ => {
+            let player = state.acting();
+            bots.contains(&player)
+                .then(|| (player, turn_action(game, player)))
+        }
+    }
+}
+
+/// What the acting bot does in its turn
+fn turn_action(game: &Game, player: PlayerId) -> Action {
+    let state = game.state();
+    let legal = |build| game.rules().legal_builds(state, player, build);
+    match state.phase() {
+        Phase::Setup {
+            settlement: None, ..
+        } => best(&state.board, legal(Build::Settlement))
+            .map_or(Action::EndTurn, |s| Action::build(&s)),
+        Phase::Setup { .. } => legal(Build::Road)
+            .first()
+            .map_or(Action::EndTurn, Action::build),
+        Phase::Roll => Action::Roll,
+        Phase::Robber => robber_target(state, player),
+        _ => {
+            let hand = &state.player(player).hand;
+            let config = game.rules().config();
+            let builds: Vec<Build> = BUILD_ORDER
+                .into_iter()
+                .filter(|build| config.costs.iter().any(|(b, _)| b == build))
+                .collect();
+            let affordable = builds
+                .iter()
+                .filter(|build| hand.contains(&config.cost(**build)))
+                .find_map(|build| best(&state.board, legal(*build)));
+            if let Some(structure) = affordable {
+                return Action::build(&structure);
+            }
+            // Trade with the bank towards something there is room for, so a bot can't stall
+            // on cards it has no use for
+            let rate = state.trade_rate(config, player);
+            builds
+                .into_iter()
+                .filter(|_| matches!(state.phase(), Phase::Main))
+                .filter(|build| !legal(*build).is_empty())
+                .find_map(|build| trade_for(hand, &config.cost(build), rate))
+                .unwrap_or(Action::EndTurn)
+        }
+    }
+}
+
+/// Structure on the most productive spot, roads and ships go anywhere
+fn best(board: &Board, structures: Vec<Structure>) -> Option<Structure> {
+    structures.into_iter().max_by_key(|structure| {
+        structure
+            .intersection()
+            .map_or(0, |intersection| production(board, intersection))
+    })
+}
+
+/// How often the hexes around an intersection produce, in 36ths of a roll
+fn production(board: &Board, intersection: Intersection) -> u32 {
+    intersection
+        .hexes()
+        .iter()
+        .filter_map(|hex| board.hex(*hex))
+        .filter(|hex| hex.is_land())
+        .map(|hex| pips(hex.resource().chance()))
+        .sum()
+}
+
+/// Ways two dice roll a chance, 0 for hexes without one
+fn pips(chance: u8) -> u32 {
+    match chance {
+        2..=12 => 6 - (7 - chance as i32).unsigned_abs(),
+        _ => 0,
+    }
+}
+
+/// Land hex hurting opponents the most and the bot the least, robbing the first victim
+fn robber_target(state: &GameState, player: PlayerId) -> Action {
+    let board = &state.board;
+    let score = |hex: HexCoord| {
+        hex.corners()
+            .iter()
+            .filter_map(|corner| board.building_at(*corner))
+            .map(|building| match building.owner() == player {
+                true => -2,
+                false => 1,
+            })
+            .sum::<i32>()
+    };
+    let target = board
+        .hexes()
+        .filter(|(coord, hex)| hex.is_land() && board.robber() != Some(*coord))
+        .map(|(coord, _)| coord)
+        .max_by_key(|coord| score(*coord));
+    match target {
+        Some(hex) => Action::MoveRobber {
+            hex,
+            victim: state.robbable(player, hex).first().copied(),
+        },
+        None => Action::EndTurn,
+    }
+}
+
+/// A bank trade getting a card the hand lacks for `cost`, giving away `rate` cards of a kind
+/// the hand holds more of than `cost` needs
+fn trade_for(hand: &Hand, cost: &Hand, rate: u32) -> Option<Action> {
+    let (get, _) = cost
+        .iter()
+        .find(|(card, amount)| hand.count(*card) < *amount)?;
+    let give = ResourceCard::RESOURCES
+        .into_iter()
+        .find(|card| hand.count(*card) >= rate + cost.count(*card))?;
+    Some(Action::TradeBank { give, get })
+}
+
+/// `amount` cards, taken from whatever the hand holds most of
+fn discard(hand: &Hand, amount: u32) -> Hand {
+    let mut left = hand.clone();
+    let mut discarded = Hand::new();
+    for _ in 0..amount {
+        let Some((card, _)) = left.iter().max_by_key(|(_, count)| *count) else {
+            break;
+        };
+        left.remove(card, 1);
+        discarded.add(card, 1);
+    }
+    discarded
+}
+
+/// `amount` resources, each of the kind the hand holds fewest of
+fn pick(hand: &Hand, amount: u32) -> Hand {
+    let mut held = hand.clone();
+    let mut picked = Hand::new();
+    for _ in 0..amount {
+        let card = ResourceCard::RESOURCES
+            .into_iter()
+            .min_by_key(|card| held.count(*card))
+            .expect("there are resources");
+        held.add(card, 1);
+        picked.add(card, 1);
+    }
+    picked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settlers::board::maps;
+    use rand::{rngs::StdRng, SeedableRng};
+    use ResourceCard::*;
+
+    #[test]
+    fn bots_play_a_whole_game() {
+        let mut board: Board = maps::DEFAULT.parse().unwrap();
+        board.randomize_with(&mut StdRng::seed_from_u64(5));
+        let mut game = Game::new(board, 4, 5);
+        let bots: Vec<PlayerId> = (0..4).collect();
+        for _ in 0..20_000 {
+            let Some((player, action)) = next_action(&game, &bots) else {
+                break;
+            };
+            game.apply(player, &action)
+                .unwrap_or_else(|error| panic!("{:?} by {}: {}", action, player, error));
+        }
+        assert!(matches!(game.state().phase(), Phase::Finished(_)));
+    }
+
+    #[test]
+    fn waits_for_humans() {
+        let board: Board = maps::DEFAULT.parse().unwrap();
+        let game = Game::new(board, 3, 1);
+        assert_eq!(next_action(&game, &[1, 2]), None);
+        assert!(next_action(&game, &[0]).is_some());
+    }
+
+    #[test]
+    fn cards_to_give_and_take() {
+        let hand = Hand::of(&[(Wood, 3), (Ore, 1)]);
+        assert_eq!(discard(&hand, 2), Hand::of(&[(Wood, 2)]));
+        assert_eq!(pick(&hand, 2), Hand::of(&[(Brick, 1), (Wheat, 1)]));
+        assert_eq!(pips(6), 5);
+        assert_eq!(pips(2), 1);
+        assert_eq!(pips(0), 0);
+        let city = Hand::of(&[(Ore, 3), (Wheat, 2)]);
+        let hand = Hand::of(&[(Wood, 5), (Ore, 1), (Wheat, 2)]);
+        assert_eq!(
+            trade_for(&hand, &city, 4),
+            Some(Action::TradeBank {
+                give: Wood,
+                get: Ore
+            })
+        );
+        assert_eq!(trade_for(&hand, &city, 6), None);
+        assert_eq!(trade_for(&city, &city, 4), None);
+    }
+}
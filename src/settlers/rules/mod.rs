@@ -0,0 +1,1565 @@
+//! Rules engine. `GameState` holds everything that changes during a game and applies the
+//! base game rules, expansions build on it through `expansions::Ruleset`.
+
+pub mod bot;
+pub mod options;
+pub mod player;
+
+use crate::settlers::board::building::Structure;
+use crate::settlers::board::card::{Resource, ResourceCard};
+use crate::settlers::board::hex::Terrain;
+use crate::settlers::board::map::Board;
+use crate::settlers::board::topology::{Edge, HexCoord, Intersection};
+use crate::settlers::expansions::{ExpansionAction, ExpansionEvent, Ruleset};
+use player::{Hand, Player, PlayerId};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Length a road must reach before it earns Longest Road
+pub const LONGEST_ROAD_MIN: u32 = 5;
+/// Most players a game holds, with the 5–6 player extension
+pub const MAX_PLAYERS: usize = 6;
+/// Games of this many players have a Special Building Phase after every turn
+pub const SPECIAL_BUILDING_PLAYERS: usize = 5;
+
+/// Things a player can pay for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Build {
+    Road,
+    Settlement,
+    City,
+    Ship,
+    /// Hiring a basic knight, or promoting one
+    Knight,
+    /// Cities & Knights city wall, raising the discard limit
+    Wall,
+}
+
+/// Numbers an expansion tweaks without changing how the game plays
+#[derive(Debug, Clone)]
+pub struct RuleConfig {
+    /// Victory points needed to win
+    pub victory_points: u32,
+    /// Players holding more cards than this discard half when a 7 is rolled
+    pub hand_limit: u32,
+    /// Cards given for one card when trading with the bank
+    pub bank_rate: u32,
+    /// Trade rate for players with a building on a harbor
+    pub harbor_rate: u32,
+    pub costs: Vec<(Build, Hand)>,
+    /// Pieces each player has of every kind
+    pub pieces: Vec<(Build, u32)>,
+}
+
+impl RuleConfig {
+    pub fn base() -> Self {
+        use ResourceCard::*;
+        RuleConfig {
+            victory_points: 10,
+            hand_limit: 7,
+            bank_rate: 4,
+            harbor_rate: 3,
+            costs: vec![
+                (Build::Road, Hand::of(&[(Wood, 1), (Brick, 1)])),
+                (
+                    Build::Settlement,
+                    Hand::of(&[(Wood, 1), (Brick, 1), (Wheat, 1), (Sheep, 1)]),
+                ),
+                (Build::City, Hand::of(&[(Wheat, 2), (Ore, 3)])),
+            ],
+            pieces: vec![(Build::Road, 15), (Build::Settlement, 5), (Build::City, 4)],
+        }
+    }
+
+    pub fn cost(&self, build: Build) -> Hand {
+        self.costs
+            .iter()
+            .find(|(b, _)| *b == build)
+            .map(|(_, cost)| cost.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn pieces(&self, build: Build) -> u32 {
+        self.pieces
+            .iter()
+            .find(|(b, _)| *b == build)
+            .map_or(0, |(_, count)| *count)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Phase {
+    /// Initial placement, round 0 goes in turn order and round 1 back. Each player places a
+    /// settlement, then a road next to it.
+    Setup {
+        round: u8,
+        settlement: Option<Intersection>,
+    },
+    Roll,
+    /// A 7 was rolled, players still owing cards
+    Discard(BTreeMap<PlayerId, u32>),
+    /// The current player moves the robber
+    Robber,
+    /// Build and trade until the turn ends
+    Main,
+    /// Players pick resources of their choice, e.g. for gold fields, then play goes on with
+    /// `then`
+    PickResources {
+        owed: BTreeMap<PlayerId, u32>,
+        then: Box<Phase>,
+    },
+    /// 5–6 players, after a turn the others take turns to build without trading, starting
+    /// with the player after the current one
+    SpecialBuilding {
+        builder: PlayerId,
+    },
+    Finished(PlayerId),
+}
+
+impl Phase {
+    /// Whether the acting player may build
+    pub fn is_building(&self) -> bool {
+        matches!(self, Phase::Main | Phase::SpecialBuilding { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Roll,
+    Discard(Hand),
+    /// Move the robber, stealing from `victim` if anyone can be robbed there
+    MoveRobber {
+        hex: HexCoord,
+        victim: Option<PlayerId>,
+    },
+    BuildRoad(Edge),
+    BuildSettlement(Intersection),
+    BuildCity(Intersection),
+    BuildShip(Edge),
+    /// Move the last ship of an open shipping route
+    MoveShip {
+        from: Edge,
+        to: Edge,
+    },
+    /// Move the pirate instead of the robber
+    MovePirate {
+        hex: HexCoord,
+        victim: Option<PlayerId>,
+    },
+    /// Resources picked when the player is owed some of their choice
+    PickResources(Hand),
+    TradeBank {
+        give: ResourceCard,
+        get: ResourceCard,
+    },
+    BuildKnight(Intersection),
+    PromoteKnight(Intersection),
+    ActivateKnight(Intersection),
+    /// Move an active knight along the player's roads, displacing a weaker opponent knight
+    MoveKnight {
+        from: Intersection,
+        to: Intersection,
+    },
+    /// An active knight next to the robber's hex sends it to `hex`
+    ChaseRobber {
+        knight: Intersection,
+        hex: HexCoord,
+        victim: Option<PlayerId>,
+    },
+    BuildWall(Intersection),
+    /// Only the rules of an expansion know what to do with it
+    Expansion(ExpansionAction),
+    /// End the turn, or pass during the Special Building Phase
+    EndTurn,
+}
+
+impl Action {
+    /// Action placing the structure on the board, e.g. from a preview the player clicked
+    pub fn build(structure: &Structure) -> Action {
+        match *structure {
+            Structure::Road { edge, .. } => Action::BuildRoad(edge),
+            Structure::Settlement { intersection, .. } => Action::BuildSettlement(intersection),
+            Structure::City { intersection, .. } => Action::BuildCity(intersection),
+            Structure::Ship { edge, .. } => Action::BuildShip(edge),
+            Structure::Knight { intersection, .. } => Action::BuildKnight(intersection),
+        }
+    }
+}
+
+/// What happened while applying an action, for the UI and logs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Rolled {
+        player: PlayerId,
+        dice: (u8, u8),
+    },
+    Produced {
+        player: PlayerId,
+        cards: Hand,
+    },
+    MustDiscard {
+        player: PlayerId,
+        amount: u32,
+    },
+    Discarded {
+        player: PlayerId,
+        cards: Hand,
+    },
+    RobberMoved {
+        hex: HexCoord,
+    },
+    PirateMoved {
+        hex: HexCoord,
+    },
+    ShipMoved {
+        player: PlayerId,
+        from: Edge,
+        to: Edge,
+    },
+    /// A fog hex was turned face up next to something the player built
+    Discovered {
+        player: PlayerId,
+        hex: HexCoord,
+    },
+    /// First settlement of the player on an island
+    IslandSettled {
+        player: PlayerId,
+        points: u32,
+    },
+    /// The player picks this many resources of their choice
+    ResourcesOwed {
+        player: PlayerId,
+        amount: u32,
+    },
+    Stole {
+        thief: PlayerId,
+        victim: PlayerId,
+        card: ResourceCard,
+    },
+    Built(Structure),
+    Traded {
+        player: PlayerId,
+        gave: Hand,
+        got: Hand,
+    },
+    LongestRoad {
+        player: Option<PlayerId>,
+    },
+    /// The knight after being promoted or activated
+    KnightChanged(Structure),
+    KnightMoved {
+        player: PlayerId,
+        from: Intersection,
+        to: Intersection,
+    },
+    /// A knight pushed off its intersection, `to` is `None` when it had nowhere to go
+    KnightDisplaced {
+        player: PlayerId,
+        from: Intersection,
+        to: Option<Intersection>,
+    },
+    BarbariansAdvanced {
+        position: u8,
+    },
+    /// The barbarians landed, the strength of their ship against that of the active knights
+    BarbariansAttacked {
+        barbarians: u32,
+        knights: u32,
+    },
+    /// The city was reduced to a settlement by the barbarians
+    Pillaged {
+        player: PlayerId,
+        intersection: Intersection,
+    },
+    /// The player defended Catan best and earns a victory point
+    DefenderOfCatan {
+        player: PlayerId,
+    },
+    /// A city wall was built, or pulled down with `built` false
+    CityWall {
+        player: PlayerId,
+        intersection: Intersection,
+        built: bool,
+    },
+    /// Cards handed from one player to another by a progress card
+    Gave {
+        from: PlayerId,
+        to: PlayerId,
+        cards: Hand,
+    },
+    /// A road taken off the board
+    Removed(Structure),
+    ChancesSwapped {
+        hexes: (HexCoord, HexCoord),
+    },
+    MerchantMoved {
+        player: PlayerId,
+        hex: HexCoord,
+    },
+    /// The player may build before the next turn
+    SpecialBuilding {
+        player: PlayerId,
+    },
+    TurnEnded {
+        next: PlayerId,
+    },
+    Won {
+        player: PlayerId,
+    },
+    /// Happened under the rules of an expansion
+    Expansion(ExpansionEvent),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleError {
+    NotYourTurn,
+    WrongPhase,
+    CannotAfford,
+    NoPiecesLeft,
+    Occupied,
+    /// Another building is on a neighbouring intersection
+    TooClose,
+    NotConnected,
+    NotLand,
+    NotSea,
+    /// The pirate is in the way
+    Blocked,
+    InvalidTarget,
+    InvalidDiscard,
+    InvalidPick,
+    InvalidTrade,
+    /// Only allowed once per turn
+    OncePerTurn,
+    /// The player needs a city, or one without a metropolis
+    NeedsCity,
+    /// The player's city improvements aren't far enough along
+    NeedsImprovement,
+    /// The player holds no such progress card
+    NotInHand,
+    /// The action belongs to another expansion
+    Unsupported,
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotYourTurn => write!(f, "it is not your turn"),
+            Self::WrongPhase => write!(f, "that can't be done right now"),
+            Self::CannotAfford => write!(f, "not enough resources"),
+            Self::NoPiecesLeft => write!(f, "no pieces of that kind left"),
+            Self::Occupied => write!(f, "something is already built there"),
+            Self::TooClose => write!(f, "too close to another building"),
+            Self::NotConnected => write!(f, "must connect to your roads or buildings"),
+            Self::NotLand => write!(f, "must be built next to land"),
+            Self::NotSea => write!(f, "must be built next to the sea"),
+            Self::Blocked => write!(f, "the pirate is in the way"),
+            Self::InvalidTarget => write!(f, "invalid target"),
+            Self::InvalidDiscard => write!(f, "wrong number of cards discarded"),
+            Self::InvalidPick => write!(f, "wrong number of resources picked"),
+            Self::OncePerTurn => write!(f, "already done this turn"),
+            Self::NeedsCity => write!(f, "needs a city to improve"),
+            Self::NeedsImprovement => write!(f, "needs a better city improvement"),
+            Self::NotInHand => write!(f, "no such progress card in hand"),
+            Self::InvalidTrade => write!(f, "invalid trade"),
+            Self::Unsupported => write!(f, "not part of this expansion"),
+        }
+    }
+}
+
+impl Error for RuleError {}
+
+#[derive(Debug)]
+pub struct GameState {
+    pub board: Board,
+    pub players: Vec<Player>,
+    current: usize,
+    phase: Phase,
+    dice: Option<(u8, u8)>,
+    /// Dice the next roll gives instead of random ones, for debugging
+    forced_dice: Option<(u8, u8)>,
+    longest_road: Option<PlayerId>,
+    rng: StdRng,
+}
+
+impl GameState {
+    /// Start a game of up to `MAX_PLAYERS`, the robber starts on the first desert
+    pub fn new(mut board: Board, players: usize, seed: u64) -> Self {
+        assert!(players <= MAX_PLAYERS, "at most {} players", MAX_PLAYERS);
+        let desert = board
+            .hexes()
+            .find(|(_, hex)| hex.is_land() && matches!(hex.resource(), Resource::Desert(_)))
+            .map(|(coord, _)| coord);
+        if let Some(desert) = desert {
+            board.move_robber(desert);
+        }
+        GameState {
+            board,
+            players: (0..players as PlayerId).map(Player::new).collect(),
+            current: 0,
+            phase: Phase::Setup {
+                round: 0,
+                settlement: None,
+            },
+            dice: None,
+            forced_dice: None,
+            longest_road: None,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn current(&self) -> PlayerId {
+        self.current as PlayerId
+    }
+
+    /// Player whose actions are played, the builder during the Special Building Phase
+    pub fn acting(&self) -> PlayerId {
+        match self.phase {
+            Phase::SpecialBuilding { builder } => builder,
+            _ => self.current(),
+        }
+    }
+
+    pub fn phase(&self) -> &Phase {
+        &self.phase
+    }
+
+    pub fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    pub fn player(&self, id: PlayerId) -> &Player {
+        &self.players[id as usize]
+    }
+
+    pub fn player_mut(&mut self, id: PlayerId) -> &mut Player {
+        &mut self.players[id as usize]
+    }
+
+    /// Dice rolled this turn
+    pub fn dice(&self) -> Option<(u8, u8)> {
+        self.dice
+    }
+
+    pub fn longest_road(&self) -> Option<PlayerId> {
+        self.longest_road
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    /// Apply an action with the base game rules
+    pub fn apply(
+        &mut self,
+        config: &RuleConfig,
+        player: PlayerId,
+        action: &Action,
+    ) -> Result<Vec<Event>, RuleError> {
+        if matches!(self.phase, Phase::Finished(_)) {
+            return Err(RuleError::WrongPhase);
+        }
+        // Players discard or pick whenever they owe cards, everything else waits for their turn
+        match action {
+            Action::Discard(cards) => return self.discard(player, cards),
+            Action::PickResources(cards) => return self.pick_resources(player, cards),
+            _ => (),
+        }
+        if player != self.acting() {
+            return Err(RuleError::NotYourTurn);
+        }
+        match (self.phase.clone(), action) {
+            (Phase::Setup { round, settlement }, Action::BuildSettlement(intersection))
+                if settlement.is_none() =>
+            {
+                self.check_settlement(config, player, *intersection, false)?;
+                let structure = Structure::Settlement {
+                    intersection: *intersection,
+                    owner: player,
+                };
+                self.board.build(structure);
+                self.phase = Phase::Setup {
+                    round,
+                    settlement: Some(*intersection),
+                };
+                let mut events = vec![Event::Built(structure)];
+                // The second settlement collects from the hexes around it
+                if round == 1 {
+                    let cards = self.starting_cards(*intersection);
+                    self.player_mut(player).hand.add_hand(&cards);
+                    events.push(Event::Produced { player, cards });
+                }
+                Ok(events)
+            }
+            (
+                Phase::Setup {
+                    round,
+                    settlement: Some(settlement),
+                },
+                Action::BuildRoad(edge),
+            ) => {
+                if !edge.ends().contains(&settlement) {
+                    return Err(RuleError::NotConnected);
+                }
+                self.check_road(config, player, *edge)?;
+                let structure = Structure::Road {
+                    edge: *edge,
+                    owner: player,
+                };
+                self.board.build(structure);
+                self.advance_setup(round);
+                Ok(vec![Event::Built(structure)])
+            }
+            (Phase::Roll, Action::Roll) => {
+                let dice = self.roll_dice();
+                Ok(self.roll(config, dice))
+            }
+            (Phase::Robber, Action::MoveRobber { hex, victim }) => {
+                self.move_robber(player, *hex, *victim)
+            }
+            (phase, Action::BuildRoad(edge)) if phase.is_building() => {
+                self.check_road(config, player, *edge)?;
+                self.pay(player, &config.cost(Build::Road))?;
+                self.build(Structure::Road {
+                    edge: *edge,
+                    owner: player,
+                })
+            }
+            (phase, Action::BuildSettlement(intersection)) if phase.is_building() => {
+                self.check_settlement(config, player, *intersection, true)?;
+                self.pay(player, &config.cost(Build::Settlement))?;
+                self.build(Structure::Settlement {
+                    intersection: *intersection,
+                    owner: player,
+                })
+            }
+            (phase, Action::BuildCity(intersection)) if phase.is_building() => {
+                match self.board.building_at(*intersection) {
+                    Some(Structure::Settlement { owner, .. }) if *owner == player => (),
+                    _ => return Err(RuleError::InvalidTarget),
+                }
+                self.check_pieces(config, player, Build::City)?;
+                self.pay(player, &config.cost(Build::City))?;
+                self.build(Structure::City {
+                    intersection: *intersection,
+                    owner: player,
+                })
+            }
+            (Phase::Main, Action::TradeBank { give, get }) => {
+                let rate = self.trade_rate(config, player);
+                if give == get {
+                    return Err(RuleError::InvalidTrade);
+                }
+                let gave = Hand::of(&[(*give, rate)]);
+                let got = Hand::of(&[(*get, 1)]);
+                self.pay(player, &gave)?;
+                self.player_mut(player).hand.add_hand(&got);
+                Ok(vec![Event::Traded { player, gave, got }])
+            }
+            (Phase::Main, Action::EndTurn) if self.players.len() >= SPECIAL_BUILDING_PLAYERS => {
+                Ok(vec![self.special_building(self.current())])
+            }
+            (Phase::Main, Action::EndTurn) => Ok(vec![self.end_turn()]),
+            (Phase::SpecialBuilding { builder }, Action::EndTurn) => {
+                Ok(vec![self.special_building(builder)])
+            }
+            (
+                _,
+                Action::BuildShip(_)
+                | Action::MoveShip { .. }
+                | Action::MovePirate { .. }
+                | Action::BuildKnight(_)
+                | Action::PromoteKnight(_)
+                | Action::ActivateKnight(_)
+                | Action::MoveKnight { .. }
+                | Action::ChaseRobber { .. }
+                | Action::BuildWall(_)
+                | Action::Expansion(_),
+            ) => Err(RuleError::Unsupported),
+            _ => Err(RuleError::WrongPhase),
+        }
+    }
+
+    pub fn roll_dice(&mut self) -> (u8, u8) {
+        if let Some(dice) = self.forced_dice.take() {
+            return dice;
+        }
+        (self.rng.gen_range(1..=6), self.rng.gen_range(1..=6))
+    }
+
+    /// Make the next roll give `dice`, whatever the expansion's rules do with them
+    pub fn force_dice(&mut self, dice: (u8, u8)) {
+        self.forced_dice = Some(dice);
+    }
+
+    pub fn forced_dice(&self) -> Option<(u8, u8)> {
+        self.forced_dice
+    }
+
+    /// Resolve a dice roll, producing resources or starting the robber on a 7
+    pub fn roll(&mut self, config: &RuleConfig, dice: (u8, u8)) -> Vec<Event> {
+        self.roll_with(config, dice, |state, total| state.produce(total))
+    }
+
+    /// Resolve a dice roll, `produce` hands out the resources of anything but a 7
+    pub fn roll_with(
+        &mut self,
+        config: &RuleConfig,
+        dice: (u8, u8),
+        produce: impl FnOnce(&mut Self, u8) -> Vec<Event>,
+    ) -> Vec<Event> {
+        let player = self.current();
+        self.dice = Some(dice);
+        let mut events = vec![Event::Rolled { player, dice }];
+        let total = dice.0 + dice.1;
+        if total == 7 {
+            let owing: BTreeMap<PlayerId, u32> = self
+                .players
+                .iter()
+                .filter(|p| p.hand.total() > config.hand_limit)
+                .map(|p| (p.id, p.hand.total() / 2))
+                .collect();
+            events.extend(owing.iter().map(|(player, amount)| Event::MustDiscard {
+                player: *player,
+                amount: *amount,
+            }));
+            self.phase = if owing.is_empty() {
+                Phase::Robber
+            } else {
+                Phase::Discard(owing)
+            };
+        } else {
+            self.phase = Phase::Main;
+            events.extend(produce(self, total));
+        }
+        events
+    }
+
+    /// Hand out the cards of every building next to a hex with the rolled number
+    pub fn produce(&mut self, roll: u8) -> Vec<Event> {
+        self.produce_with(roll, |_, resource| match resource.card() {
+            Some(card) => Hand::of(&[(card, resource.chance() as u32)]),
+            None => Hand::new(),
+        })
+    }
+
+    /// Hand out what `cards` gives a building for each of its hexes with the rolled number,
+    /// the resource holding how many a building of its kind collects
+    pub fn produce_with(
+        &mut self,
+        roll: u8,
+        cards: impl Fn(&Structure, &Resource) -> Hand,
+    ) -> Vec<Event> {
+        let mut produced: BTreeMap<PlayerId, Hand> = BTreeMap::new();
+        for structure in self.board.buildings() {
+            for resource in structure.collect_resources(&self.board, roll) {
+                produced
+                    .entry(structure.owner())
+                    .or_default()
+                    .add_hand(&cards(structure, &resource));
+            }
+        }
+        produced.retain(|_, cards| !cards.is_empty());
+        produced
+            .into_iter()
+            .map(|(player, cards)| {
+                self.player_mut(player).hand.add_hand(&cards);
+                Event::Produced { player, cards }
+            })
+            .collect()
+    }
+
+    fn discard(&mut self, player: PlayerId, cards: &Hand) -> Result<Vec<Event>, RuleError> {
+        let Phase::Discard(owing) = &mut self.phase else {
+            return Err(RuleError::WrongPhase);
+        };
+        if owing.get(&player) != Some(&cards.total()) {
+            return Err(RuleError::InvalidDiscard);
+        }
+        if !self.players[player as usize].hand.remove_hand(cards) {
+            return Err(RuleError::CannotAfford);
+        }
+        owing.remove(&player);
+        if owing.is_empty() {
+            self.phase = Phase::Robber;
+        }
+        Ok(vec![Event::Discarded {
+            player,
+            cards: cards.clone(),
+        }])
+    }
+
+    /// Wait for players to pick the resources they are owed before play goes on
+    pub fn owe_resources(&mut self, owed: BTreeMap<PlayerId, u32>) -> Vec<Event> {
+        let owed: BTreeMap<PlayerId, u32> = owed.into_iter().filter(|(_, n)| *n > 0).collect();
+        if owed.is_empty() {
+            return Vec::new();
+        }
+        let events = owed
+            .iter()
+            .map(|(player, amount)| Event::ResourcesOwed {
+                player: *player,
+                amount: *amount,
+            })
+            .collect();
+        let then = Box::new(self.phase.clone());
+        self.phase = Phase::PickResources { owed, then };
+        events
+    }
+
+    fn pick_resources(&mut self, player: PlayerId, cards: &Hand) -> Result<Vec<Event>, RuleError> {
+        let Phase::PickResources { owed, then } = &mut self.phase else {
+            return Err(RuleError::WrongPhase);
+        };
+        if owed.get(&player) != Some(&cards.total())
+            || cards.iter().any(|(card, _)| card.is_commodity())
+        {
+            return Err(RuleError::InvalidPick);
+        }
+        owed.remove(&player);
+        if owed.is_empty() {
+            self.phase = *then.clone();
+        }
+        self.player_mut(player).hand.add_hand(cards);
+        Ok(vec![Event::Produced {
+            player,
+            cards: cards.clone(),
+        }])
+    }
+
+    /// Players with a building next to the hex and cards to steal
+    pub fn robbable(&self, thief: PlayerId, hex: HexCoord) -> Vec<PlayerId> {
+        let mut victims: Vec<PlayerId> = hex
+            .corners()
+            .iter()
+            .filter_map(|corner| self.board.building_at(*corner))
+            .map(|structure| structure.owner())
+            .filter(|owner| *owner != thief && !self.player(*owner).hand.is_empty())
+            .collect();
+        victims.sort();
+        victims.dedup();
+        victims
+    }
+
+    /// Move the robber to another land hex, stealing from `victim`, then play goes on
+    pub fn move_robber(
+        &mut self,
+        player: PlayerId,
+        hex: HexCoord,
+        victim: Option<PlayerId>,
+    ) -> Result<Vec<Event>, RuleError> {
+        if !self.board.is_land(hex) || self.board.robber() == Some(hex) {
+            return Err(RuleError::InvalidTarget);
+        }
+        let victims = self.robbable(player, hex);
+        match victim {
+            Some(victim) if !victims.contains(&victim) => return Err(RuleError::InvalidTarget),
+            None if !victims.is_empty() => return Err(RuleError::InvalidTarget),
+            _ => (),
+        }
+        self.board.move_robber(hex);
+        let mut events = vec![Event::RobberMoved { hex }];
+        events.extend(victim.and_then(|victim| self.steal(player, victim)));
+        self.phase = Phase::Main;
+        Ok(events)
+    }
+
+    /// Move a random card from the victim to the thief
+    pub fn steal(&mut self, thief: PlayerId, victim: PlayerId) -> Option<Event> {
+        let card = self.players[victim as usize]
+            .hand
+            .take_random(&mut self.rng)?;
+        self.player_mut(thief).hand.add(card, 1);
+        Some(Event::Stole {
+            thief,
+            victim,
+            card,
+        })
+    }
+
+    /// Build a structure that was paid for, updating Longest Road
+    pub fn build(&mut self, structure: Structure) -> Result<Vec<Event>, RuleError> {
+        self.board.build(structure);
+        let mut events = vec![Event::Built(structure)];
+        events.extend(self.update_longest_road());
+        Ok(events)
+    }
+
+    pub fn pay(&mut self, player: PlayerId, cost: &Hand) -> Result<(), RuleError> {
+        if self.player_mut(player).hand.remove_hand(cost) {
+            Ok(())
+        } else {
+            Err(RuleError::CannotAfford)
+        }
+    }
+
+    /// Pieces of a kind the player has placed on the board
+    pub fn pieces_used(&self, player: PlayerId, build: Build) -> u32 {
+        self.board
+            .buildings()
+            .iter()
+            .filter(|structure| structure.owner() == player)
+            .filter(|structure| {
+                matches!(
+                    (structure, build),
+                    (Structure::Road { .. }, Build::Road)
+                        | (Structure::Settlement { .. }, Build::Settlement)
+                        | (Structure::City { .. }, Build::City)
+                        | (Structure::Ship { .. }, Build::Ship)
+                )
+            })
+            .count() as u32
+    }
+
+    pub fn check_pieces(
+        &self,
+        config: &RuleConfig,
+        player: PlayerId,
+        build: Build,
+    ) -> Result<(), RuleError> {
+        if self.pieces_used(player, build) < config.pieces(build) {
+            Ok(())
+        } else {
+            Err(RuleError::NoPiecesLeft)
+        }
+    }
+
+    /// Distance rule, land and (after setup) a connecting road
+    pub fn check_settlement(
+        &self,
+        config: &RuleConfig,
+        player: PlayerId,
+        intersection: Intersection,
+        connected: bool,
+    ) -> Result<(), RuleError> {
+        if !intersection
+            .hexes()
+            .iter()
+            .any(|hex| self.board.is_land(*hex))
+        {
+            return Err(RuleError::NotLand);
+        }
+        if self.board.building_at(intersection).is_some() {
+            return Err(RuleError::Occupied);
+        }
+        if intersection
+            .neighbors()
+            .iter()
+            .any(|neighbor| self.board.building_at(*neighbor).is_some())
+        {
+            return Err(RuleError::TooClose);
+        }
+        if connected
+            && !intersection
+                .edges()
+                .iter()
+                .any(|edge| self.owns_road(player, *edge))
+        {
+            return Err(RuleError::NotConnected);
+        }
+        self.check_pieces(config, player, Build::Settlement)
+    }
+
+    pub fn check_road(
+        &self,
+        config: &RuleConfig,
+        player: PlayerId,
+        edge: Edge,
+    ) -> Result<(), RuleError> {
+        if !edge.hexes().iter().any(|hex| self.board.is_land(*hex)) {
+            return Err(RuleError::NotLand);
+        }
+        if self.board.road_at(edge).is_some() {
+            return Err(RuleError::Occupied);
+        }
+        if !self.connects(player, edge, |structure| {
+            matches!(structure, Structure::Road { .. })
+        }) {
+            return Err(RuleError::NotConnected);
+        }
+        self.check_pieces(config, player, Build::Road)
+    }
+
+    /// Every structure of a kind the player could place right now, ignoring its cost. The
+    /// scene shows these as previews while the player picks where to build.
+    pub fn legal_builds(
+        &self,
+        config: &RuleConfig,
+        player: PlayerId,
+        build: Build,
+    ) -> Vec<Structure> {
+        if player != self.acting() {
+            return Vec::new();
+        }
+        let settlements = |connected: bool| {
+            self.board
+                .intersections()
+                .into_iter()
+                .filter(|i| self.check_settlement(config, player, *i, connected).is_ok())
+                .map(|intersection| Structure::Settlement {
+                    intersection,
+                    owner: player,
+                })
+                .collect()
+        };
+        let roads = |edges: Vec<Edge>| {
+            edges
+                .into_iter()
+                .filter(|edge| self.check_road(config, player, *edge).is_ok())
+                .map(|edge| Structure::Road {
+                    edge,
+                    owner: player,
+                })
+                .collect()
+        };
+        match (&self.phase, build) {
+            (
+                Phase::Setup {
+                    settlement: None, ..
+                },
+                Build::Settlement,
+            ) => settlements(false),
+            (
+                Phase::Setup {
+                    settlement: Some(settlement),
+                    ..
+                },
+                Build::Road,
+            ) => roads(settlement.edges().to_vec()),
+            (phase, Build::Settlement) if phase.is_building() => settlements(true),
+            (phase, Build::Road) if phase.is_building() => roads(self.board.edges()),
+            (phase, Build::City)
+                if phase.is_building() && self.check_pieces(config, player, build).is_ok() =>
+            {
+                self.board
+                    .buildings()
+                    .iter()
+                    .filter_map(|structure| match structure {
+                        Structure::Settlement {
+                            intersection,
+                            owner,
+                        } if *owner == player => Some(Structure::City {
+                            intersection: *intersection,
+                            owner: player,
+                        }),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether the player has a road or ship on the edge
+    fn owns_road(&self, player: PlayerId, edge: Edge) -> bool {
+        self.board
+            .road_at(edge)
+            .is_some_and(|road| road.owner() == player)
+    }
+
+    /// Whether an edge touches one of the player's buildings, or continues one of their
+    /// routes (as told by `route`) through an intersection no opponent has built on
+    pub fn connects(
+        &self,
+        player: PlayerId,
+        edge: Edge,
+        route: impl Fn(&Structure) -> bool,
+    ) -> bool {
+        edge.ends()
+            .iter()
+            .any(|end| match self.board.building_at(*end) {
+                Some(building) => building.owner() == player,
+                None => end.edges().iter().any(|other| {
+                    *other != edge
+                        && self
+                            .board
+                            .road_at(*other)
+                            .is_some_and(|road| road.owner() == player && route(road))
+                }),
+            })
+    }
+
+    /// Longest chain of the player's roads and ships, broken by opponent buildings. Roads and
+    /// ships only join at the player's own settlements and cities (Longest Trade Route).
+    pub fn road_length(&self, player: PlayerId) -> u32 {
+        let routes: Vec<Structure> = self
+            .board
+            .buildings()
+            .iter()
+            .filter(|structure| structure.owner() == player && structure.edge().is_some())
+            .copied()
+            .collect();
+        let mut used = Vec::new();
+        let mut longest = 0;
+        for piece in routes.iter() {
+            let edge = piece.edge().unwrap();
+            for end in edge.ends() {
+                used.push(edge);
+                let length = 1 + self.extend_road(player, &routes, &mut used, piece, end);
+                longest = longest.max(length);
+                used.pop();
+            }
+        }
+        longest
+    }
+
+    fn extend_road(
+        &self,
+        player: PlayerId,
+        routes: &[Structure],
+        used: &mut Vec<Edge>,
+        last: &Structure,
+        from: Intersection,
+    ) -> u32 {
+        let building = self.board.building_at(from);
+        if building.is_some_and(|building| building.owner() != player) {
+            return 0;
+        }
+        let mut longest = 0;
+        for piece in routes {
+            let edge = piece.edge().unwrap();
+            if !from.edges().contains(&edge) || used.contains(&edge) {
+                continue;
+            }
+            if std::mem::discriminant(piece) != std::mem::discriminant(last) && building.is_none() {
+                continue;
+            }
+            used.push(edge);
+            let length = 1 + self.extend_road(player, routes, used, piece, edge.other_end(&from));
+            longest = longest.max(length);
+            used.pop();
+        }
+        longest
+    }
+
+    /// Give Longest Road to whoever holds it now, the holder keeps it on a tie
+    pub fn update_longest_road(&mut self) -> Option<Event> {
+        let lengths: Vec<u32> = self
+            .players
+            .iter()
+            .map(|player| self.road_length(player.id))
+            .collect();
+        let best = lengths.iter().copied().max().unwrap_or(0);
+        let holder = match self.longest_road {
+            Some(holder) if best >= LONGEST_ROAD_MIN && lengths[holder as usize] == best => {
+                Some(holder)
+            }
+            _ => {
+                let leaders: Vec<usize> =
+                    (0..lengths.len()).filter(|i| lengths[*i] == best).collect();
+                (best >= LONGEST_ROAD_MIN && leaders.len() == 1).then(|| leaders[0] as PlayerId)
+            }
+        };
+        if holder == self.longest_road {
+            return None;
+        }
+        self.longest_road = holder;
+        Some(Event::LongestRoad { player: holder })
+    }
+
+    /// Cards given for one, lowered by a building on a harbor
+    pub fn trade_rate(&self, config: &RuleConfig, player: PlayerId) -> u32 {
+        let on_harbor = self.board.buildings().iter().any(|structure| {
+            structure.owner() == player
+                && structure.is_building()
+                && structure.intersection().is_some_and(|intersection| {
+                    intersection.hexes().iter().any(|hex| {
+                        self.board
+                            .hex(*hex)
+                            .is_some_and(|hex| hex.terrain() == Terrain::Harbor)
+                    })
+                })
+        });
+        if on_harbor {
+            config.harbor_rate
+        } else {
+            config.bank_rate
+        }
+    }
+
+    /// Base game victory points: buildings and Longest Road
+    pub fn victory_points(&self, player: PlayerId) -> u32 {
+        let buildings: u32 = self
+            .board
+            .buildings()
+            .iter()
+            .filter(|structure| structure.owner() == player)
+            .map(|structure| match structure {
+                Structure::Road { .. } | Structure::Ship { .. } | Structure::Knight { .. } => 0,
+                Structure::Settlement { .. } => 1,
+                Structure::City { .. } => 2,
+            })
+            .sum();
+        let longest_road = if self.longest_road == Some(player) {
+            2
+        } else {
+            0
+        };
+        buildings + longest_road
+    }
+
+    fn starting_cards(&self, intersection: Intersection) -> Hand {
+        let mut cards = Hand::new();
+        for hex in intersection.hexes() {
+            if let Some(card) = self
+                .board
+                .hex(hex)
+                .filter(|hex| hex.is_land())
+                .and_then(|hex| hex.resource().card())
+            {
+                cards.add(card, 1);
+            }
+        }
+        cards
+    }
+
+    /// Pass setup on to the next player once a road (or ship) was placed
+    pub fn advance_setup(&mut self, round: u8) {
+        let last = self.players.len() - 1;
+        self.phase = match (round, self.current) {
+            (0, current) if current == last => Phase::Setup {
+                round: 1,
+                settlement: None,
+            },
+            (0, _) => {
+                self.current += 1;
+                Phase::Setup {
+                    round: 0,
+                    settlement: None,
+                }
+            }
+            (_, 0) => Phase::Roll,
+            _ => {
+                self.current -= 1;
+                Phase::Setup {
+                    round: 1,
+                    settlement: None,
+                }
+            }
+        };
+    }
+
+    /// Hand the Special Building Phase to the player after `after`, the next turn starts once
+    /// everyone had theirs
+    pub fn special_building(&mut self, after: PlayerId) -> Event {
+        let builder = (after + 1) % self.players.len() as PlayerId;
+        if builder == self.current() {
+            return self.end_turn();
+        }
+        self.phase = Phase::SpecialBuilding { builder };
+        Event::SpecialBuilding { player: builder }
+    }
+
+    pub fn end_turn(&mut self) -> Event {
+        self.current = (self.current + 1) % self.players.len();
+        self.dice = None;
+        self.phase = Phase::Roll;
+        Event::TurnEnded {
+            next: self.current(),
+        }
+    }
+
+    pub fn finish(&mut self, winner: PlayerId) -> Event {
+        self.phase = Phase::Finished(winner);
+        Event::Won { player: winner }
+    }
+}
+
+/// A game with the rules of the expansion its map asked for
+pub struct Game {
+    state: GameState,
+    rules: Box<dyn Ruleset>,
+}
+
+impl Game {
+    pub fn new(board: Board, players: usize, seed: u64) -> Self {
+        let rules = board.expansion().rules();
+        Game {
+            state: GameState::new(board, players, seed),
+            rules,
+        }
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// The state without going through the rules, for debugging tools
+    pub fn state_mut(&mut self) -> &mut GameState {
+        &mut self.state
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.state.board
+    }
+
+    pub fn rules(&self) -> &dyn Ruleset {
+        self.rules.as_ref()
+    }
+
+    /// Apply an action for `player`, ending the game once the current player has enough
+    /// victory points
+    pub fn apply(&mut self, player: PlayerId, action: &Action) -> Result<Vec<Event>, RuleError> {
+        let mut events = self.rules.apply(&mut self.state, player, action)?;
+        let current = self.state.current();
+        if !matches!(self.state.phase(), Phase::Finished(_))
+            && self.rules.victory_points(&self.state, current) >= self.rules.config().victory_points
+        {
+            events.push(self.state.finish(current));
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::settlers::board::maps;
+    use ResourceCard::*;
+
+    /// A game on the default map
+    pub(crate) fn game(players: usize) -> GameState {
+        let mut board: Board = maps::DEFAULT.parse().unwrap();
+        board.randomize();
+        GameState::new(board, players, 1)
+    }
+
+    /// Land intersections that are far enough apart to all hold a settlement
+    pub(crate) fn spots(state: &GameState) -> Vec<Intersection> {
+        let mut spots: Vec<Intersection> = Vec::new();
+        for intersection in state.board.intersections() {
+            if intersection
+                .hexes()
+                .iter()
+                .all(|hex| state.board.is_land(*hex))
+                && !spots
+                    .iter()
+                    .any(|spot| spot.neighbors().contains(&intersection) || *spot == intersection)
+            {
+                spots.push(intersection);
+            }
+        }
+        spots
+    }
+
+    /// Run setup, giving every player two settlements with a road each
+    pub(crate) fn setup(state: &mut GameState, config: &RuleConfig) -> Vec<Intersection> {
+        let spots = spots(state);
+        let order: Vec<PlayerId> = (0..state.players.len() as PlayerId)
+            .chain((0..state.players.len() as PlayerId).rev())
+            .collect();
+        for (i, player) in order.into_iter().enumerate() {
+            let spot = spots[i];
+            state
+                .apply(config, player, &Action::BuildSettlement(spot))
+                .unwrap();
+            state
+                .apply(config, player, &Action::BuildRoad(spot.edges()[0]))
+                .unwrap();
+        }
+        spots
+    }
+
+    #[test]
+    fn setup_order_snakes() {
+        let config = RuleConfig::base();
+        let mut state = game(3);
+        let spot = spots(&state)[0];
+        assert_eq!(
+            state.apply(&config, 1, &Action::BuildSettlement(spot)),
+            Err(RuleError::NotYourTurn)
+        );
+        setup(&mut state, &config);
+        assert_eq!(state.phase(), &Phase::Roll);
+        assert_eq!(state.current(), 0);
+        for player in 0..3 {
+            assert_eq!(state.pieces_used(player, Build::Settlement), 2);
+            assert_eq!(state.pieces_used(player, Build::Road), 2);
+        }
+    }
+
+    #[test]
+    fn distance_rule() {
+        let config = RuleConfig::base();
+        let mut state = game(2);
+        let spot = spots(&state)[0];
+        state
+            .apply(&config, 0, &Action::BuildSettlement(spot))
+            .unwrap();
+        state
+            .apply(&config, 0, &Action::BuildRoad(spot.edges()[0]))
+            .unwrap();
+        assert_eq!(
+            state.apply(&config, 1, &Action::BuildSettlement(spot.neighbors()[0])),
+            Err(RuleError::TooClose)
+        );
+        assert_eq!(
+            state.apply(&config, 1, &Action::BuildSettlement(spot)),
+            Err(RuleError::Occupied)
+        );
+    }
+
+    #[test]
+    fn building_costs_and_connects() {
+        let config = RuleConfig::base();
+        let mut state = game(2);
+        let spots = setup(&mut state, &config);
+        state.roll(&config, (1, 1));
+        state.set_phase(Phase::Main);
+        let road = spots[0].edges()[1];
+        state.player_mut(0).hand = Hand::new();
+        assert_eq!(
+            state.apply(&config, 0, &Action::BuildRoad(road)),
+            Err(RuleError::CannotAfford)
+        );
+        state.player_mut(0).hand = Hand::of(&[(Wood, 1), (Brick, 1)]);
+        state.apply(&config, 0, &Action::BuildRoad(road)).unwrap();
+        assert!(state.player(0).hand.is_empty());
+        // Far from anything player 0 owns
+        state.player_mut(0).hand = Hand::of(&[(Wood, 1), (Brick, 1)]);
+        let far = state
+            .board
+            .edges()
+            .into_iter()
+            .find(|edge| state.check_road(&config, 0, *edge) == Err(RuleError::NotConnected))
+            .unwrap();
+        assert_eq!(
+            state.apply(&config, 0, &Action::BuildRoad(far)),
+            Err(RuleError::NotConnected)
+        );
+        state.player_mut(0).hand = Hand::of(&[(Wheat, 2), (Ore, 3)]);
+        state
+            .apply(&config, 0, &Action::BuildCity(spots[0]))
+            .unwrap();
+        assert_eq!(state.victory_points(0), 3);
+        assert_eq!(
+            state.apply(&config, 0, &Action::BuildCity(spots[1])),
+            Err(RuleError::InvalidTarget)
+        );
+    }
+
+    #[test]
+    fn production_follows_dice() {
+        let config = RuleConfig::base();
+        let mut state = game(2);
+        let spots = setup(&mut state, &config);
+        for player in 0..2 {
+            state.player_mut(player).hand = Hand::new();
+        }
+        let hex = spots[0]
+            .hexes()
+            .into_iter()
+            .find(|hex| state.board.hex(*hex).unwrap().resource().card().is_some())
+            .unwrap();
+        let resource = state.board.hex(hex).unwrap().resource();
+        state.board.move_robber(HexCoord::new(0, 0));
+        let chance = resource.chance();
+        let events = state.roll(&config, (chance / 2, chance - chance / 2));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, Event::Produced { player: 0, .. })));
+        assert!(state.player(0).hand.count(resource.card().unwrap()) >= 1);
+        assert_eq!(state.phase(), &Phase::Main);
+    }
+
+    #[test]
+    fn forced_dice_roll_once() {
+        let config = RuleConfig::base();
+        let mut state = game(2);
+        setup(&mut state, &config);
+        state.force_dice((3, 4));
+        assert_eq!(state.forced_dice(), Some((3, 4)));
+        state.apply(&config, 0, &Action::Roll).unwrap();
+        assert_eq!(state.dice(), Some((3, 4)));
+        assert_eq!(state.phase(), &Phase::Robber);
+        assert_eq!(state.forced_dice(), None);
+    }
+
+    #[test]
+    fn seven_discards_and_robs() {
+        let config = RuleConfig::base();
+        let mut state = game(2);
+        let spots = setup(&mut state, &config);
+        state.player_mut(0).hand = Hand::of(&[(Wood, 9)]);
+        state.player_mut(1).hand = Hand::of(&[(Ore, 2)]);
+        state.roll(&config, (3, 4));
+        assert_eq!(state.phase(), &Phase::Discard([(0, 4)].into()));
+        assert_eq!(
+            state.apply(&config, 0, &Action::Discard(Hand::of(&[(Wood, 3)]))),
+            Err(RuleError::InvalidDiscard)
+        );
+        state
+            .apply(&config, 0, &Action::Discard(Hand::of(&[(Wood, 4)])))
+            .unwrap();
+        assert_eq!(state.phase(), &Phase::Robber);
+        let target = spots[1]
+            .hexes()
+            .into_iter()
+            .find(|hex| state.board.robber() != Some(*hex))
+            .unwrap();
+        assert_eq!(state.robbable(0, target), vec![1]);
+        assert_eq!(
+            state.apply(
+                &config,
+                0,
+                &Action::MoveRobber {
+                    hex: target,
+                    victim: None
+                }
+            ),
+            Err(RuleError::InvalidTarget)
+        );
+        state
+            .apply(
+                &config,
+                0,
+                &Action::MoveRobber {
+                    hex: target,
+                    victim: Some(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(state.board.robber(), Some(target));
+        assert_eq!(state.player(0).hand.count(Ore), 1);
+        assert_eq!(state.phase(), &Phase::Main);
+    }
+
+    #[test]
+    fn longest_road_needs_five() {
+        let config = RuleConfig::base();
+        let mut state = game(2);
+        let spots = setup(&mut state, &config);
+        state.set_phase(Phase::Main);
+        // Walk away from the first settlement, one edge at a time
+        let mut end = spots[0].edges()[0].other_end(&spots[0]);
+        let mut built = vec![spots[0].edges()[0]];
+        let mut awarded = None;
+        for _ in 0..6 {
+            let Some(edge) = end.edges().into_iter().find(|edge| {
+                !built.contains(edge)
+                    && state.check_road(&config, 0, *edge).is_ok()
+                    && state.board.building_at(edge.other_end(&end)).is_none()
+            }) else {
+                break;
+            };
+            state.player_mut(0).hand = Hand::of(&[(Wood, 1), (Brick, 1)]);
+            let events = state.apply(&config, 0, &Action::BuildRoad(edge)).unwrap();
+            if events.contains(&Event::LongestRoad { player: Some(0) }) {
+                awarded = Some(state.road_length(0));
+            }
+            built.push(edge);
+            end = edge.other_end(&end);
+        }
+        assert_eq!(awarded, Some(LONGEST_ROAD_MIN));
+        assert_eq!(state.longest_road(), Some(0));
+    }
+
+    #[test]
+    fn bank_trade() {
+        let config = RuleConfig::base();
+        let mut state = game(2);
+        setup(&mut state, &config);
+        state.set_phase(Phase::Main);
+        let rate = state.trade_rate(&config, 0);
+        state.player_mut(0).hand = Hand::of(&[(Sheep, rate)]);
+        let trade = Action::TradeBank {
+            give: Sheep,
+            get: Ore,
+        };
+        state.apply(&config, 0, &trade).unwrap();
+        assert_eq!(state.player(0).hand, Hand::of(&[(Ore, 1)]));
+        assert_eq!(
+            state.apply(&config, 0, &trade),
+            Err(RuleError::CannotAfford)
+        );
+    }
+
+    #[test]
+    fn special_building_between_turns() {
+        let config = RuleConfig::base();
+        let mut board: Board = maps::SIX.parse().unwrap();
+        board.randomize();
+        let mut state = GameState::new(board, MAX_PLAYERS, 1);
+        let spots = setup(&mut state, &config);
+        state.set_phase(Phase::Main);
+        let events = state.apply(&config, 0, &Action::EndTurn).unwrap();
+        assert_eq!(events, vec![Event::SpecialBuilding { player: 1 }]);
+        assert_eq!(state.acting(), 1);
+        assert_eq!(
+            state.apply(&config, 0, &Action::EndTurn),
+            Err(RuleError::NotYourTurn)
+        );
+        // The builder builds, but doesn't trade
+        state.player_mut(1).hand = Hand::of(&[(Wheat, 2), (Ore, 3), (Sheep, 4)]);
+        state
+            .apply(&config, 1, &Action::BuildCity(spots[1]))
+            .unwrap();
+        assert_eq!(
+            state.apply(
+                &config,
+                1,
+                &Action::TradeBank {
+                    give: Sheep,
+                    get: Ore
+                }
+            ),
+            Err(RuleError::WrongPhase)
+        );
+        for builder in 1..MAX_PLAYERS as PlayerId - 1 {
+            let events = state.apply(&config, builder, &Action::EndTurn).unwrap();
+            assert_eq!(
+                events,
+                vec![Event::SpecialBuilding {
+                    player: builder + 1
+                }]
+            );
+        }
+        let events = state
+            .apply(&config, MAX_PLAYERS as PlayerId - 1, &Action::EndTurn)
+            .unwrap();
+        assert_eq!(events, vec![Event::TurnEnded { next: 1 }]);
+        assert_eq!(state.phase(), &Phase::Roll);
+        assert_eq!(state.acting(), 1);
+    }
+
+    #[test]
+    fn legal_builds_follow_the_rules() {
+        let config = RuleConfig::base();
+        let mut state = game(2);
+        let spot = spots(&state)[0];
+        let settlements = state.legal_builds(&config, 0, Build::Settlement);
+        assert_eq!(settlements.len(), state.board.intersections().len());
+        assert!(state.legal_builds(&config, 1, Build::Settlement).is_empty());
+        assert!(state.legal_builds(&config, 0, Build::Road).is_empty());
+        state
+            .apply(&config, 0, &Action::BuildSettlement(spot))
+            .unwrap();
+        // The setup road goes next to the new settlement
+        let roads = state.legal_builds(&config, 0, Build::Road);
+        assert!(!roads.is_empty());
+        assert!(roads
+            .iter()
+            .all(|road| road.edge().unwrap().ends().contains(&spot)));
+
+        let mut state = game(2);
+        let spots = setup(&mut state, &config);
+        state.set_phase(Phase::Main);
+        let cities: Vec<Intersection> = state
+            .legal_builds(&config, 0, Build::City)
+            .iter()
+            .filter_map(Structure::intersection)
+            .collect();
+        assert_eq!(cities, vec![spots[0], spots[3]]);
+        for settlement in state.legal_builds(&config, 0, Build::Settlement) {
+            let intersection = settlement.intersection().unwrap();
+            assert_eq!(
+                state.check_settlement(&config, 0, intersection, true),
+                Ok(())
+            );
+        }
+        for road in state.legal_builds(&config, 0, Build::Road) {
+            assert_eq!(state.check_road(&config, 0, road.edge().unwrap()), Ok(()));
+        }
+    }
+}
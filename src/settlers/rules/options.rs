@@ -0,0 +1,184 @@
+//! What a new game is started with, as picked in the new game menu. The same options and seed
+//! give the same board and dice.
+
+use super::player::PlayerId;
+use super::{Game, MAX_PLAYERS};
+use crate::settlers::board::focm::ParseMapErrors;
+use crate::settlers::board::map::Board;
+use crate::settlers::board::maps;
+use crate::settlers::expansions::Expansion;
+use rand::{rngs::StdRng, SeedableRng};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+/// Fewest players a game is played with
+pub const MIN_PLAYERS: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapSource {
+    /// A map bundled into the binary, by name, see `maps::BUNDLED`
+    Bundled(&'static str),
+    File(PathBuf),
+}
+
+/// Who plays a seat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seat {
+    Human,
+    Bot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameOptions {
+    pub map: MapSource,
+    /// Rules the map is played with, those the map asks for if `None`
+    pub expansion: Option<Expansion>,
+    /// One seat per player, in turn order
+    pub seats: Vec<Seat>,
+    pub seed: u64,
+}
+
+impl Default for GameOptions {
+    /// One human against three bots on the default map, with a random seed
+    fn default() -> Self {
+        GameOptions {
+            map: MapSource::Bundled("default"),
+            expansion: None,
+            seats: vec![Seat::Human, Seat::Bot, Seat::Bot, Seat::Bot],
+            seed: rand::random(),
+        }
+    }
+}
+
+impl GameOptions {
+    /// The map, shuffled with the seed and set up for the rules picked
+    pub fn board(&self) -> Result<Board, OptionsError> {
+        let mut board: Board = match &self.map {
+            MapSource::Bundled(name) => maps::bundled(name)
+                .ok_or_else(|| OptionsError::UnknownMap(name.to_string()))?
+                .parse()?,
+            MapSource::File(path) => Board::from_file(path)?,
+        };
+        if let Some(expansion) = self.expansion {
+            if !board.set_expansion(expansion) {
+                return Err(OptionsError::WrongExpansion {
+                    map: board.expansion(),
+                    rules: expansion,
+                });
+            }
+        }
+        board.randomize_with(&mut StdRng::seed_from_u64(self.seed));
+        Ok(board)
+    }
+
+    pub fn start(&self) -> Result<Game, OptionsError> {
+        let players = self.seats.len();
+        if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&players) {
+            return Err(OptionsError::Players(players));
+        }
+        Ok(Game::new(self.board()?, players, self.seed))
+    }
+
+    /// Players the computer plays
+    pub fn bots(&self) -> Vec<PlayerId> {
+        self.seats
+            .iter()
+            .enumerate()
+            .filter(|(_, seat)| **seat == Seat::Bot)
+            .map(|(player, _)| player as PlayerId)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionsError {
+    UnknownMap(String),
+    Map(ParseMapErrors),
+    /// The map uses things the rules picked don't play with
+    WrongExpansion {
+        map: Expansion,
+        rules: Expansion,
+    },
+    Players(usize),
+}
+
+impl From<ParseMapErrors> for OptionsError {
+    fn from(errors: ParseMapErrors) -> Self {
+        OptionsError::Map(errors)
+    }
+}
+
+impl Display for OptionsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownMap(name) => write!(f, "no bundled map named {}", name),
+            Self::Map(errors) => write!(f, "{}", errors),
+            Self::WrongExpansion { map, rules } => {
+                write!(f, "a {} map can't be played with {} rules", map, rules)
+            }
+            Self::Players(players) => write!(
+                f,
+                "{} players, a game needs {} to {}",
+                players, MIN_PLAYERS, MAX_PLAYERS
+            ),
+        }
+    }
+}
+
+impl Error for OptionsError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_repeats_the_game() {
+        let options = GameOptions {
+            seed: 3,
+            ..GameOptions::default()
+        };
+        let layout = |game: &Game| {
+            game.board()
+                .hexes()
+                .map(|(_, hex)| format!("{:?}", hex.resource()))
+                .collect::<Vec<String>>()
+        };
+        let (first, second) = (options.start().unwrap(), options.start().unwrap());
+        assert_eq!(layout(&first), layout(&second));
+        assert_eq!(first.state().players.len(), 4);
+        assert_eq!(options.bots(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn invalid_options() {
+        let options = GameOptions {
+            map: MapSource::Bundled("nowhere"),
+            ..GameOptions::default()
+        };
+        assert_eq!(
+            options.start().err(),
+            Some(OptionsError::UnknownMap("nowhere".into()))
+        );
+        let options = GameOptions {
+            seats: vec![Seat::Human],
+            ..GameOptions::default()
+        };
+        assert_eq!(options.start().err(), Some(OptionsError::Players(1)));
+        let options = GameOptions {
+            map: MapSource::File("does/not/exist.focm".into()),
+            ..GameOptions::default()
+        };
+        assert!(matches!(options.start(), Err(OptionsError::Map(_))));
+    }
+
+    #[test]
+    fn rules_from_the_options() {
+        let options = GameOptions {
+            expansion: Some(Expansion::CitiesAndKnights),
+            ..GameOptions::default()
+        };
+        let game = options.start().unwrap();
+        assert_eq!(game.rules().expansion(), Expansion::CitiesAndKnights);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::settlers::board::card::ResourceCard;
+use rand::Rng;
+use std::collections::BTreeMap;
+
+/// Index of a player in `GameState::players`, also used as its colour id
+pub type PlayerId = u8;
+
+/// Cards held by a player. Also used for costs and trades.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hand {
+    cards: BTreeMap<ResourceCard, u32>,
+}
+
+impl Hand {
+    pub fn new() -> Self {
+        Hand::default()
+    }
+
+    pub fn of(cards: &[(ResourceCard, u32)]) -> Self {
+        let mut hand = Hand::new();
+        for (card, amount) in cards {
+            hand.add(*card, *amount);
+        }
+        hand
+    }
+
+    pub fn count(&self, card: ResourceCard) -> u32 {
+        self.cards.get(&card).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u32 {
+        self.cards.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    pub fn add(&mut self, card: ResourceCard, amount: u32) {
+        if amount > 0 {
+            *self.cards.entry(card).or_insert(0) += amount;
+        }
+    }
+
+    pub fn add_hand(&mut self, other: &Hand) {
+        for (card, amount) in other.iter() {
+            self.add(card, amount);
+        }
+    }
+
+    /// Whether every card of `other` is in this hand
+    pub fn contains(&self, other: &Hand) -> bool {
+        other
+            .iter()
+            .all(|(card, amount)| self.count(card) >= amount)
+    }
+
+    /// Remove the cards of `other`, leaving the hand untouched if it doesn't hold them all
+    pub fn remove_hand(&mut self, other: &Hand) -> bool {
+        if !self.contains(other) {
+            return false;
+        }
+        for (card, amount) in other.iter() {
+            self.remove(card, amount);
+        }
+        true
+    }
+
+    /// Remove up to `amount` cards of a kind, returning how many were removed
+    pub fn remove(&mut self, card: ResourceCard, amount: u32) -> u32 {
+        let held = self.count(card);
+        let removed = held.min(amount);
+        if removed == held {
+            self.cards.remove(&card);
+        } else {
+            self.cards.insert(card, held - removed);
+        }
+        removed
+    }
+
+    /// Remove a card picked uniformly among all held cards
+    pub fn take_random(&mut self, rng: &mut impl Rng) -> Option<ResourceCard> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = rng.gen_range(0..total);
+        let card = self
+            .iter()
+            .find(|(_, amount)| {
+                if pick < *amount {
+                    true
+                } else {
+                    pick -= amount;
+                    false
+                }
+            })
+            .map(|(card, _)| card)?;
+        self.remove(card, 1);
+        Some(card)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ResourceCard, u32)> + '_ {
+        self.cards.iter().map(|(card, amount)| (*card, *amount))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub id: PlayerId,
+    pub hand: Hand,
+}
+
+impl Player {
+    pub fn new(id: PlayerId) -> Self {
+        Player {
+            id,
+            hand: Hand::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn remove_hand_is_all_or_nothing() {
+        let mut hand = Hand::of(&[(ResourceCard::Wood, 2), (ResourceCard::Brick, 1)]);
+        let cost = Hand::of(&[(ResourceCard::Wood, 1), (ResourceCard::Sheep, 1)]);
+        assert!(!hand.remove_hand(&cost));
+        assert_eq!(hand.total(), 3);
+        assert!(hand.remove_hand(&Hand::of(&[(ResourceCard::Wood, 2)])));
+        assert_eq!(hand, Hand::of(&[(ResourceCard::Brick, 1)]));
+    }
+
+    #[test]
+    fn take_random_empties_hand() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut hand = Hand::of(&[(ResourceCard::Ore, 2), (ResourceCard::Wheat, 1)]);
+        let mut taken = Hand::new();
+        while let Some(card) = hand.take_random(&mut rng) {
+            taken.add(card, 1);
+        }
+        assert_eq!(
+            taken,
+            Hand::of(&[(ResourceCard::Ore, 2), (ResourceCard::Wheat, 1)])
+        );
+        assert!(hand.is_empty());
+    }
+}
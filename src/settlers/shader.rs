@@ -18,7 +18,7 @@ impl<'a> ProgramManager<'a> {
     }
 
     /// Compile a program for the manager
-    pub fn add_program<F: Sized + Facade>(
+    pub fn add_program<F: ?Sized + Facade>(
         &mut self,
         facade: &F,
         name: &'a str,
@@ -56,7 +56,7 @@ impl<'a> TextureManager<'a> {
     }
 
     /// Compile a Texture for the manager
-    pub fn add_texture<F: Sized + Facade>(
+    pub fn add_texture<F: ?Sized + Facade>(
         &mut self,
         facade: &F,
         name: &'a str,
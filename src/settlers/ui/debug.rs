@@ -0,0 +1,172 @@
+//! Debug overlay, shown with `settings::DEBUG`: frame timing, the camera, what is under the
+//! cursor down to the bits sent to the shaders, and shortcuts around the rules.
+
+use crate::settlers::board::card::ResourceCard;
+use crate::settlers::board::picking::Pick;
+use crate::settlers::board::topology::HexCoord;
+use crate::settlers::matrix::Vec3;
+use crate::settlers::rules::Game;
+use imgui::{Condition, Ui};
+
+/// What the scene knows that the game doesn't
+pub struct DebugInfo {
+    /// Seconds the last frame took
+    pub frame_time: f32,
+    pub camera: Vec3,
+    pub scale: f32,
+    pub hovered: Option<Pick>,
+    /// What the last click landed on
+    pub selected: Option<Pick>,
+    /// `hex_meta` of the hex under the cursor
+    pub hex_meta: Option<u32>,
+    /// `meta` of the structures under the cursor, previews included
+    pub structure_meta: Vec<u32>,
+}
+
+/// Changes to the game that skip the rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugCommand {
+    /// Shuffle the resources and chances of the board again
+    RerollBoard,
+    /// Make the next roll give these dice
+    ForceDice((u8, u8)),
+    /// Give cards to the acting player
+    Give(ResourceCard, u32),
+    /// Put the robber on a hex, without stealing or ending the Robber phase
+    MoveRobber(HexCoord),
+}
+
+pub struct DebugOverlay {
+    dice: [i32; 2],
+    /// Index in `ResourceCard::RESOURCES` then `COMMODITIES`
+    card: usize,
+    amount: i32,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        DebugOverlay {
+            dice: [3, 4],
+            card: 0,
+            amount: 1,
+        }
+    }
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        DebugOverlay::default()
+    }
+
+    pub fn draw(&mut self, ui: &Ui, game: &Game, info: &DebugInfo) -> Option<DebugCommand> {
+        ui.window("Debug")
+            .position([10., 10.], Condition::FirstUseEver)
+            .always_auto_resize(true)
+            .build(|| {
+                self.stats(ui, game, info);
+                ui.separator();
+                self.tools(ui, info)
+            })
+            .flatten()
+    }
+
+    fn stats(&self, ui: &Ui, game: &Game, info: &DebugInfo) {
+        ui.text(format!(
+            "{:.0} FPS, {:.2} ms",
+            fps(info.frame_time),
+            info.frame_time * 1000.
+        ));
+        let (x, y, z) = info.camera.as_tuple();
+        ui.text(format!(
+            "Camera ({:.2}, {:.2}, {:.2}), {:.1} px per unit",
+            x, y, z, info.scale
+        ));
+        let state = game.state();
+        ui.text(format!("Phase {:?}", state.phase()));
+        if let Some(dice) = state.forced_dice() {
+            ui.text(format!("Next roll {:?}", dice));
+        }
+        ui.separator();
+        match info.hovered {
+            Some(Pick::Hex(coord)) => {
+                ui.text(format!("Hex row {}, col {}", coord.row, coord.col));
+                if let Some(hex) = game.board().hex(coord) {
+                    ui.text(format!("{:?} {:?}", hex.terrain(), hex.resource()));
+                    ui.text(format!("Chance {}", hex.resource().chance()));
+                }
+            }
+            Some(pick) => ui.text(format!("{:?}", pick)),
+            None => ui.text("Nothing under the cursor"),
+        }
+        if let Some(meta) = info.hex_meta {
+            ui.text(format!("hex_meta {:#019b}", meta));
+        }
+        for meta in &info.structure_meta {
+            ui.text(format!("meta     {:#020b}", meta));
+        }
+    }
+
+    fn tools(&mut self, ui: &Ui, info: &DebugInfo) -> Option<DebugCommand> {
+        let mut command = None;
+        if ui.button("Reroll board") {
+            command = Some(DebugCommand::RerollBoard);
+        }
+        ui.set_next_item_width(60.);
+        ui.input_int("##die1", &mut self.dice[0]).build();
+        ui.same_line();
+        ui.set_next_item_width(60.);
+        ui.input_int("##die2", &mut self.dice[1]).build();
+        ui.same_line();
+        self.dice = self.dice.map(|die| die.clamp(1, 6));
+        if ui.button("Force next roll") {
+            command = Some(DebugCommand::ForceDice((
+                self.dice[0] as u8,
+                self.dice[1] as u8,
+            )));
+        }
+        let cards: Vec<ResourceCard> = ResourceCard::RESOURCES
+            .into_iter()
+            .chain(ResourceCard::COMMODITIES)
+            .collect();
+        let names: Vec<String> = cards.iter().map(|card| format!("{:?}", card)).collect();
+        ui.set_next_item_width(80.);
+        ui.combo_simple_string("##card", &mut self.card, &names);
+        ui.same_line();
+        ui.set_next_item_width(60.);
+        ui.input_int("##amount", &mut self.amount).build();
+        self.amount = self.amount.max(1);
+        ui.same_line();
+        if ui.button("Give") {
+            command = Some(DebugCommand::Give(cards[self.card], self.amount as u32));
+        }
+        // The cursor is over this window when pressing the button, so use the clicked hex
+        if let Some(Pick::Hex(coord)) = info.selected {
+            if ui.button("Robber to the selected hex") {
+                command = Some(DebugCommand::MoveRobber(coord));
+            }
+        } else {
+            ui.text_disabled("Click a hex to move the robber there");
+        }
+        command
+    }
+}
+
+/// Frames per second of a frame lasting `frame_time` seconds
+fn fps(frame_time: f32) -> f32 {
+    if frame_time > 0. {
+        1. / frame_time
+    } else {
+        0.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_per_second() {
+        assert_eq!(fps(0.02), 50.);
+        assert_eq!(fps(0.), 0.);
+    }
+}
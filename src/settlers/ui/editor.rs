@@ -0,0 +1,456 @@
+//! Map editor: paint the cells of a map, declare the tiles and chances it is randomized with,
+//! and save it as .focm. The map is checked as it is painted, with the errors loading the file
+//! would give.
+
+use crate::settlers::board::card::Resource;
+use crate::settlers::board::draft::{Brush, MapDraft};
+use crate::settlers::board::map::Board;
+use crate::settlers::board::picking::Pick;
+use crate::settlers::board::topology::HexCoord;
+use crate::settlers::camera::Camera;
+use crate::settlers::expansions::base::pan_direction;
+use crate::settlers::expansions::Expansion;
+use crate::settlers::game::{DeltaTime, Scene, Transition};
+use crate::settlers::render::{BoardRenderer, BoardView};
+use glium::backend::Facade;
+use glium::Frame;
+use imgui::{Condition, Ui};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, TouchPhase};
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+/// Rows and columns of a new map
+const NEW_MAP: (usize, usize) = (7, 7);
+/// Most rows or columns a map is given in the editor
+const MAX_SIZE: i32 = 50;
+/// File the map is saved to until another is given
+const DEFAULT_PATH: &str = "map.focm";
+/// Distance of the windows from the sides of the screen
+const MARGIN: f32 = 10.;
+/// Names of the brushes, in the order `MapEditor::brush` matches them
+const BRUSHES: [&str; 7] = [
+    "Random land",
+    "Resource",
+    "Resource and chance",
+    "Water",
+    "Harbor",
+    "Frame",
+    "Void",
+];
+/// Chances a tile can be given
+const CHANCES: [u8; 10] = [2, 3, 4, 5, 6, 8, 9, 10, 11, 12];
+
+pub struct MapEditor<'p> {
+    renderer: BoardRenderer<'p>,
+    camera: Camera,
+    delta_time: DeltaTime,
+    draft: MapDraft,
+    // What is drawn: the draft randomized with `seed` once it is valid, as painted until then
+    preview: Board,
+    seed: u64,
+    // Why the saved draft wouldn't load, empty once it would
+    errors: Vec<String>,
+    cursor: PhysicalPosition<f64>,
+    // Cell of the map under the cursor
+    hovered: Option<HexCoord>,
+    // Left button held, each cell dragged over is painted once
+    painting: bool,
+    painted: Option<HexCoord>,
+    // Right button held, dragging moves the camera
+    panning: bool,
+    // Middle button held, dragging turns the board
+    twisting: bool,
+    // Indices in `BRUSHES`, the expansion's resources and `CHANCES`
+    brush: usize,
+    resource: usize,
+    chance: usize,
+    // Chances as typed, e.g. "5, 2, 6"
+    chances: String,
+    path: String,
+    // Outcome of the last open or save
+    message: Option<String>,
+    transition: Option<Transition>,
+}
+
+impl<'p> MapEditor<'p> {
+    /// Editor of a new map, all water
+    pub fn new() -> Self {
+        use crate::settings::WINDOW_DEFAULT_SIZE;
+        let draft = MapDraft::new(NEW_MAP.0, NEW_MAP.1);
+        let mut camera = Camera::new(8., 0.);
+        camera.set_viewport(WINDOW_DEFAULT_SIZE.width, WINDOW_DEFAULT_SIZE.height);
+        let mut editor = MapEditor {
+            renderer: BoardRenderer::new(),
+            camera,
+            delta_time: DeltaTime::new(),
+            preview: draft.board(),
+            draft,
+            seed: rand::random(),
+            errors: Vec::new(),
+            cursor: PhysicalPosition::new(0., 0.),
+            hovered: None,
+            painting: false,
+            painted: None,
+            panning: false,
+            twisting: false,
+            brush: 0,
+            resource: 0,
+            chance: 0,
+            chances: String::new(),
+            path: DEFAULT_PATH.to_string(),
+            message: None,
+            transition: None,
+        };
+        editor.refresh();
+        editor.fit();
+        editor
+    }
+
+    fn view(&self) -> BoardView {
+        BoardView {
+            hovered: self.hovered.map(Pick::Hex),
+            ..BoardView::new(&self.camera)
+        }
+    }
+
+    /// Zoom and move the camera so the whole map is seen
+    fn fit(&mut self) {
+        if let Some(bounds) = self.preview.extents() {
+            self.camera.fit(bounds);
+        }
+    }
+
+    /// Check the draft again and update what is drawn, after every change
+    fn refresh(&mut self) {
+        self.errors = match self.draft.validate() {
+            Ok(_) => Vec::new(),
+            Err(errors) => errors.errors().iter().map(|e| e.to_string()).collect(),
+        };
+        self.preview = self.draft.preview(self.seed);
+        self.camera.set_bounds(self.preview.extents());
+    }
+
+    /// Cell of the map under the cursor, void cells included
+    fn cell_at(&self, position: PhysicalPosition<f64>) -> Option<HexCoord> {
+        let (x, y) = self.camera.world_at(position.x as f32, position.y as f32)?;
+        Some(HexCoord::at(x, y)).filter(|coord| self.draft.contains(*coord))
+    }
+
+    /// What the brush picked paints
+    fn brush(&self) -> Brush {
+        let resources = self.draft.expansion().rules().resources();
+        let code = resources.get(self.resource).unwrap_or(&resources[0]);
+        let resource = Resource::from_code(code, 0).expect("expansions use known resources");
+        match self.brush {
+            0 => Brush::Land,
+            1 => Brush::Resource(resource),
+            2 => Brush::Tile(resource.clone_with_value(CHANCES[self.chance])),
+            3 => Brush::Water,
+            4 => Brush::Harbor,
+            5 => Brush::Frame,
+            _ => Brush::Void,
+        }
+    }
+
+    /// Paint the cell under the cursor, unless it was just painted
+    fn paint(&mut self) {
+        let Some(coord) = self.hovered else { return };
+        if self.painted == Some(coord) {
+            return;
+        }
+        self.painted = Some(coord);
+        if self.draft.paint(coord, self.brush()) {
+            self.refresh();
+        }
+    }
+
+    fn open(&mut self) {
+        match Board::from_file(&self.path) {
+            Ok(board) => {
+                self.draft = MapDraft::from_board(&board);
+                self.chances = join(self.draft.chances());
+                self.message = Some(format!("Opened {}", self.path));
+                self.refresh();
+                self.fit();
+            }
+            Err(errors) => self.message = Some(errors.to_string()),
+        }
+    }
+
+    fn save(&mut self) {
+        if self.path.is_empty() {
+            self.message = Some("no file given".to_string());
+            return;
+        }
+        self.message = Some(match self.draft.save(&self.path) {
+            Ok(()) if self.errors.is_empty() => format!("Saved {}", self.path),
+            Ok(()) => format!("Saved {}, it won't load until it is fixed", self.path),
+            Err(error) => format!("{} could not be saved: {}", self.path, error),
+        });
+    }
+
+    /// Size, rules and island bonus of the map
+    fn map(&mut self, ui: &Ui) {
+        let names: Vec<&str> = Expansion::ALL.iter().map(|e| e.name()).collect();
+        let mut expansion = Expansion::ALL
+            .iter()
+            .position(|e| *e == self.draft.expansion())
+            .unwrap_or(0);
+        if ui.combo_simple_string("Expansion", &mut expansion, &names) {
+            self.draft.set_expansion(Expansion::ALL[expansion]);
+            self.refresh();
+        }
+        let (rows, columns) = self.draft.size();
+        let mut size = [rows as i32, columns as i32];
+        let resized = ui.input_int("Rows", &mut size[0]).build()
+            | ui.input_int("Columns", &mut size[1]).build();
+        if resized {
+            let [rows, columns] = size.map(|n| n.clamp(1, MAX_SIZE) as usize);
+            self.draft.resize(rows, columns);
+            self.refresh();
+        }
+        let mut bonus = self.draft.island_bonus() as i32;
+        if ui.input_int("Island bonus", &mut bonus).build() {
+            self.draft
+                .set_island_bonus(bonus.clamp(0, u8::MAX as i32) as u8);
+            self.refresh();
+        }
+    }
+
+    /// What clicking a cell paints
+    fn brushes(&mut self, ui: &Ui) {
+        ui.combo_simple_string("Brush", &mut self.brush, &BRUSHES);
+        let brush = self.brush();
+        if matches!(brush, Brush::Resource(_) | Brush::Tile(_)) {
+            let resources = self.draft.expansion().rules().resources();
+            self.resource = self.resource.min(resources.len() - 1);
+            ui.combo_simple_string("Resource", &mut self.resource, resources);
+        }
+        if matches!(brush, Brush::Tile(resource) if resource.code() != "DE") {
+            let chances: Vec<String> = CHANCES.iter().map(|c| c.to_string()).collect();
+            ui.combo_simple_string("Chance", &mut self.chance, &chances);
+        }
+        ui.text_disabled("Left click paints, right drag moves the map");
+    }
+
+    /// Tiles and chances the randomizer deals to the land
+    fn tiles(&mut self, ui: &Ui) {
+        for code in self.draft.expansion().rules().resources() {
+            let mut count = self.draft.count(code) as i32;
+            if ui.input_int(*code, &mut count).build() {
+                self.draft
+                    .set_count(code, count.clamp(0, u8::MAX as i32) as u8);
+                self.refresh();
+            }
+        }
+        // Counts of every chance, or the whole list typed out
+        for chance in CHANCES {
+            let chances = self.draft.chances();
+            let mut count = chances.iter().filter(|c| **c == chance).count() as i32;
+            if ui
+                .input_int(format!("Chance {}", chance), &mut count)
+                .build()
+            {
+                let mut chances: Vec<u8> =
+                    chances.iter().copied().filter(|c| *c != chance).collect();
+                chances.extend(vec![chance; count.clamp(0, u8::MAX as i32) as usize]);
+                chances.sort_unstable();
+                self.chances = join(&chances);
+                self.draft.set_chances(chances);
+                self.refresh();
+            }
+        }
+        if ui.input_text("Chances", &mut self.chances).build() {
+            match parse_chances(&self.chances) {
+                Some(chances) => {
+                    self.draft.set_chances(chances);
+                    self.refresh();
+                }
+                None => self.message = Some("chances are numbers split by commas".to_string()),
+            }
+        }
+    }
+
+    fn file(&mut self, ui: &Ui) {
+        ui.input_text("File", &mut self.path).build();
+        if ui.button("Open") {
+            self.open();
+        }
+        ui.same_line();
+        if ui.button("Save") {
+            self.save();
+        }
+        ui.same_line();
+        if ui.button("Shuffle") {
+            self.seed = rand::random();
+            self.refresh();
+        }
+        ui.same_line();
+        if ui.button("Fit") {
+            self.fit();
+        }
+        ui.same_line();
+        if ui.button("Back") {
+            self.transition = Some(Transition::Pop);
+        }
+        if let Some(message) = &self.message {
+            ui.text_wrapped(message);
+        }
+    }
+
+    /// What the parser thinks of the draft
+    fn validation(&self, ui: &Ui) {
+        let tiles: u32 = self
+            .draft
+            .expansion()
+            .rules()
+            .resources()
+            .iter()
+            .map(|code| self.draft.count(code))
+            .sum();
+        ui.text(format!(
+            "{} land cells, {} tiles, {} chances",
+            self.draft.land(),
+            tiles,
+            self.draft.chances().len()
+        ));
+        if self.errors.is_empty() {
+            ui.text_colored([0.4, 0.9, 0.4, 1.], "The map is valid");
+        }
+        for error in &self.errors {
+            ui.text_colored([1., 0.4, 0.4, 1.], error);
+        }
+    }
+}
+
+impl<'p> Scene for MapEditor<'p> {
+    fn enter(&mut self, facade: &dyn Facade) {
+        self.renderer
+            .load(facade, self.draft.expansion().rules().textures());
+    }
+
+    fn exit(&mut self) {
+        self.renderer.unload();
+    }
+
+    fn mouse_move(&mut self, position: PhysicalPosition<f64>) {
+        let (last, now) = (
+            (self.cursor.x as f32, self.cursor.y as f32),
+            (position.x as f32, position.y as f32),
+        );
+        if self.panning {
+            self.camera.drag(last, now);
+        }
+        if self.twisting {
+            self.camera.twist(last, now);
+        }
+        self.cursor = position;
+        self.hovered = self.cell_at(position);
+        if self.painting {
+            self.paint();
+        }
+    }
+
+    fn mouse_input(&mut self, state: ElementState, button: MouseButton) {
+        let pressed = state == ElementState::Pressed;
+        match button {
+            MouseButton::Left => {
+                self.painting = pressed;
+                self.painted = None;
+                if pressed {
+                    self.paint();
+                }
+            }
+            MouseButton::Right => self.panning = pressed,
+            MouseButton::Middle => self.twisting = pressed,
+            _ => (),
+        }
+    }
+
+    fn scroll_input(&mut self, delta: MouseScrollDelta, _phase: TouchPhase) {
+        if let MouseScrollDelta::LineDelta(_, scroll) = delta {
+            let cursor = self.cursor;
+            self.camera
+                .zoom_at(scroll, cursor.x as f32, cursor.y as f32);
+        }
+    }
+
+    fn keyboard_input(&mut self, event: KeyEvent) {
+        let PhysicalKey::Code(code) = event.physical_key else {
+            return;
+        };
+        if let Some(direction) = pan_direction(code) {
+            self.camera.pan(direction, event.state.is_pressed());
+            return;
+        }
+        if event.state.is_pressed() {
+            match code {
+                KeyCode::Escape => self.transition = Some(Transition::Pop),
+                KeyCode::KeyF => self.fit(),
+                // Between the flat and the tilted view
+                KeyCode::KeyV => self.camera.set_mode(self.camera.mode().toggled()),
+                // A sixth of a turn counter-clockwise or clockwise
+                KeyCode::KeyQ => self.camera.turn(1),
+                KeyCode::KeyE => self.camera.turn(-1),
+                _ => (),
+            }
+        }
+    }
+
+    fn window_size(&mut self, new_size: PhysicalSize<u32>) {
+        self.camera.set_viewport(new_size.width, new_size.height);
+    }
+
+    fn update(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.delta_time.update();
+        self.camera.update(self.delta_time.frame_time());
+        Ok(())
+    }
+
+    fn ui(&mut self, ui: &Ui) {
+        let [_, height] = ui.io().display_size;
+        ui.window("Map editor")
+            .position([MARGIN, MARGIN], Condition::Always)
+            .always_auto_resize(true)
+            .collapsible(false)
+            .build(|| {
+                self.map(ui);
+                ui.separator();
+                self.brushes(ui);
+                ui.separator();
+                self.tiles(ui);
+                ui.separator();
+                self.file(ui);
+            });
+        ui.window("Validation")
+            .position([MARGIN, height - MARGIN], Condition::Always)
+            .position_pivot([0., 1.])
+            .always_auto_resize(true)
+            .collapsible(false)
+            .build(|| self.validation(ui));
+    }
+
+    fn transition(&mut self) -> Option<Transition> {
+        self.transition.take()
+    }
+
+    fn draw(&self, facade: &dyn Facade, mut frame: Frame) -> Frame {
+        self.renderer
+            .draw_hexes(facade, &mut frame, &self.preview, &self.view());
+        frame
+    }
+}
+
+/// Chances as written in [CHANCES], `None` if one isn't a number
+fn parse_chances(text: &str) -> Option<Vec<u8>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|chance| !chance.is_empty())
+        .map(|chance| chance.parse().ok())
+        .collect()
+}
+
+fn join(chances: &[u8]) -> String {
+    let chances: Vec<String> = chances.iter().map(|c| c.to_string()).collect();
+    chances.join(", ")
+}
@@ -0,0 +1,712 @@
+//! In-game HUD: the acting player's hand, building costs, dice and scores, and the buttons
+//! that play a turn.
+
+use crate::settlers::board::building::Structure;
+use crate::settlers::board::card::ResourceCard;
+use crate::settlers::board::picking::Pick;
+use crate::settlers::board::topology::{Edge, HexCoord, Intersection};
+use crate::settlers::expansions::kingdom::progress::{Progress, ProgressCard};
+use crate::settlers::expansions::kingdom::Improvement;
+use crate::settlers::expansions::{Expansion, ExpansionAction};
+use crate::settlers::palette::Palette;
+use crate::settlers::rules::player::{Hand, PlayerId};
+use crate::settlers::rules::{Action, Build, Game, Phase};
+use imgui::{Condition, Ui};
+use std::collections::BTreeMap;
+
+/// Structures placed by picking a spot on the board
+const PLACEABLE: [Build; 5] = [
+    Build::Road,
+    Build::Settlement,
+    Build::City,
+    Build::Ship,
+    Build::Knight,
+];
+/// Distance of the windows from the sides of the screen
+const MARGIN: f32 = 10.;
+
+/// What the player asked for through the HUD
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Apply an action for the acting player
+    Act(Action),
+    /// Apply an action for another player, e.g. a discard out of turn
+    ActAs(PlayerId, Action),
+    /// Pick where to build, previewing the legal spots
+    Place(Build),
+    /// Stop previewing
+    CancelPlace,
+}
+
+pub struct Hud {
+    /// Cards picked for a bank trade, as indices in `ResourceCard::RESOURCES`
+    give: usize,
+    get: usize,
+    /// Outcome of the last command, e.g. why the rules refused it
+    message: Option<String>,
+    /// Cards chosen so far by the players discarding or picking resources
+    chosen: BTreeMap<PlayerId, Hand>,
+    /// Card a progress card names, as an index in `ResourceCard::RESOURCES` then `COMMODITIES`
+    card: usize,
+    /// Player a progress card is played against
+    victim: usize,
+    /// Index of the spied card in the victim's progress cards
+    spied: usize,
+    /// Roll the alchemist makes
+    dice: [i32; 2],
+    /// Moves of the robber or pirate to choose between, one for each player it could rob
+    robberies: Vec<(PlayerId, Action)>,
+}
+
+impl Default for Hud {
+    fn default() -> Self {
+        Hud {
+            give: 0,
+            get: 1,
+            message: None,
+            chosen: BTreeMap::new(),
+            card: 0,
+            victim: 0,
+            spied: 0,
+            dice: [3, 4],
+            robberies: Vec::new(),
+        }
+    }
+}
+
+impl Hud {
+    pub fn new() -> Self {
+        Hud::default()
+    }
+
+    pub fn report(&mut self, message: Option<String>) {
+        self.message = message;
+    }
+
+    /// Ask whom the robber or pirate robs, as the move robbing each victim
+    pub fn offer_robberies(&mut self, robberies: Vec<(PlayerId, Action)>) {
+        self.robberies = robberies;
+    }
+
+    /// Build the HUD windows, returning the button the player pressed. The `bots` choose
+    /// their cards without a window. Knights and progress cards are played on the `targets`,
+    /// the spots last clicked on the board.
+    pub fn draw(
+        &mut self,
+        ui: &Ui,
+        game: &Game,
+        palette: &Palette,
+        placing: Option<Build>,
+        bots: &[PlayerId],
+        targets: &[Pick],
+    ) -> Option<Command> {
+        let [width, height] = ui.io().display_size;
+        let player = game.state().acting();
+        ui.window("Hand")
+            .position([MARGIN, height - MARGIN], Condition::Always)
+            .position_pivot([0., 1.])
+            .always_auto_resize(true)
+            .collapsible(false)
+            .build(|| self.hand(ui, game, palette, player));
+        ui.window("Scores")
+            .position([width - MARGIN, MARGIN], Condition::Always)
+            .position_pivot([1., 0.])
+            .always_auto_resize(true)
+            .collapsible(false)
+            .build(|| scores(ui, game, palette));
+        let turn = ui
+            .window("Turn")
+            .position([width - MARGIN, height - MARGIN], Condition::Always)
+            .position_pivot([1., 1.])
+            .always_auto_resize(true)
+            .collapsible(false)
+            .build(|| self.actions(ui, game, placing))
+            .flatten();
+        let kingdom = if game.rules().expansion() == Expansion::CitiesAndKnights {
+            ui.window("Cities & Knights")
+                .position([MARGIN, height / 2.], Condition::Always)
+                .position_pivot([0., 0.5])
+                .always_auto_resize(true)
+                .collapsible(false)
+                .build(|| self.kingdom(ui, game, player, targets))
+                .flatten()
+        } else {
+            None
+        };
+        let choice = self.choices(ui, game, bots);
+        let draw = draws(ui, game, bots);
+        let robbery = self.robbery(ui, game);
+        turn.or(kingdom).or(choice).or(draw).or(robbery)
+    }
+
+    /// Victims to choose between once the robber or pirate has a hex to go to
+    fn robbery(&mut self, ui: &Ui, game: &Game) -> Option<Command> {
+        if game.state().phase() != &Phase::Robber {
+            self.robberies.clear();
+        }
+        if self.robberies.is_empty() {
+            return None;
+        }
+        let [width, height] = ui.io().display_size;
+        let (mut command, mut cancelled) = (None, false);
+        ui.window("Rob a player")
+            .position([width / 2., height / 3.], Condition::Appearing)
+            .position_pivot([0.5, 0.5])
+            .always_auto_resize(true)
+            .collapsible(false)
+            .build(|| {
+                for (victim, action) in &self.robberies {
+                    if ui.button(format!("Player {}", victim + 1)) {
+                        command = Some(Command::Act(action.clone()));
+                    }
+                    ui.same_line();
+                }
+                cancelled = ui.button("Cancel");
+            });
+        if command.is_some() || cancelled {
+            self.robberies.clear();
+        }
+        command
+    }
+
+    /// Improvements, knights, walls and progress cards of the acting player
+    fn kingdom(
+        &mut self,
+        ui: &Ui,
+        game: &Game,
+        player: PlayerId,
+        targets: &[Pick],
+    ) -> Option<Command> {
+        let expansion = |action| Some(Command::Act(Action::Expansion(action)));
+        let mut command = None;
+        ui.text_disabled("Improve");
+        for improvement in Improvement::ALL {
+            ui.same_line();
+            if ui.button(format!("{:?}", improvement)) {
+                command = expansion(ExpansionAction::Improve(improvement));
+            }
+        }
+        let picked: Vec<&str> = targets.iter().map(describe_pick).collect();
+        if picked.is_empty() {
+            ui.text_disabled("Click the board to pick spots for knights and cards");
+        } else {
+            ui.text_disabled(format!("Picked {}", picked.join(", then ")));
+        }
+        // Knights go and walls are built on the last intersection picked
+        let spot = targets.iter().rev().find_map(|pick| match pick {
+            Pick::Intersection(intersection) => Some(*intersection),
+            _ => None,
+        });
+        if let Some(city) = spot {
+            if ui.button("Wall on the picked city") {
+                command = Some(Command::Act(Action::BuildWall(city)));
+            }
+        }
+        for (i, structure) in game.board().buildings().iter().enumerate() {
+            let Structure::Knight {
+                intersection,
+                owner,
+                strength,
+                active,
+            } = *structure
+            else {
+                continue;
+            };
+            if owner != player {
+                continue;
+            }
+            let state = if active { "active" } else { "inactive" };
+            ui.text(format!("Knight of strength {}, {}", strength, state));
+            ui.same_line();
+            if ui.small_button(format!("Promote##{}", i)) {
+                command = Some(Command::Act(Action::PromoteKnight(intersection)));
+            }
+            ui.same_line();
+            if ui.small_button(format!("Activate##{}", i)) {
+                command = Some(Command::Act(Action::ActivateKnight(intersection)));
+            }
+            if let Some(to) = spot.filter(|to| *to != intersection) {
+                ui.same_line();
+                if ui.small_button(format!("Move to the pick##{}", i)) {
+                    command = Some(Command::Act(Action::MoveKnight {
+                        from: intersection,
+                        to,
+                    }));
+                }
+            }
+        }
+        let cards = game.rules().progress_cards(player);
+        if cards.is_empty() {
+            return command;
+        }
+        ui.separator();
+        ui.text_disabled("Progress cards, played with");
+        let state = game.state();
+        let commodities: Vec<ResourceCard> = ResourceCard::RESOURCES
+            .into_iter()
+            .chain(ResourceCard::COMMODITIES)
+            .collect();
+        let names: Vec<String> = commodities.iter().map(|c| format!("{:?}", c)).collect();
+        ui.set_next_item_width(80.);
+        ui.combo_simple_string("Card", &mut self.card, &names);
+        let players: Vec<String> = (0..state.players.len())
+            .map(|id| format!("Player {}", id + 1))
+            .collect();
+        ui.same_line();
+        ui.set_next_item_width(80.);
+        ui.combo_simple_string("Victim", &mut self.victim, &players);
+        self.victim = self.victim.min(players.len() - 1);
+        let spied = game.rules().progress_cards(self.victim as PlayerId);
+        if !spied.is_empty() {
+            let names: Vec<String> = spied.iter().map(|c| format!("{:?}", c)).collect();
+            self.spied = self.spied.min(names.len() - 1);
+            ui.set_next_item_width(120.);
+            ui.combo_simple_string("Spied card", &mut self.spied, &names);
+        }
+        ui.set_next_item_width(60.);
+        ui.input_int("##die1", &mut self.dice[0]).build();
+        ui.same_line();
+        ui.set_next_item_width(60.);
+        ui.input_int("Alchemist dice", &mut self.dice[1]).build();
+        self.dice = self.dice.map(|die| die.clamp(1, 6));
+        let choices = Choices {
+            targets,
+            card: commodities[self.card],
+            victim: self.victim as PlayerId,
+            dice: (self.dice[0] as u8, self.dice[1] as u8),
+            spied: spied.get(self.spied).copied(),
+        };
+        for (i, card) in cards.iter().enumerate() {
+            if ui.button(format!("{:?}##progress{}", card, i)) {
+                match progress(*card, &choices) {
+                    Ok(progress) => command = expansion(ExpansionAction::PlayProgress(progress)),
+                    Err(missing) => self.message = Some(format!("{:?}: {}", card, missing)),
+                }
+            }
+            if i % 3 != 2 {
+                ui.same_line();
+            }
+        }
+        ui.new_line();
+        command
+    }
+
+    /// A window for every human discarding or picking resources, they play at once
+    fn choices(&mut self, ui: &Ui, game: &Game, bots: &[PlayerId]) -> Option<Command> {
+        let (discard, owing) = match game.state().phase() {
+            Phase::Discard(owing) => (true, owing),
+            Phase::PickResources { owed, .. } => (false, owed),
+            _ => {
+                self.chosen.clear();
+                return None;
+            }
+        };
+        self.chosen.retain(|player, _| owing.contains_key(player));
+        let [width, height] = ui.io().display_size;
+        let mut command = None;
+        for (i, (player, amount)) in owing
+            .iter()
+            .filter(|(player, _)| !bots.contains(player))
+            .enumerate()
+        {
+            let title = if discard {
+                format!("Player {} discards {}", player + 1, amount)
+            } else {
+                format!("Player {} picks {}", player + 1, amount)
+            };
+            let offset = 30. * i as f32;
+            command = ui
+                .window(title)
+                .position(
+                    [width / 2. + offset, height / 3. + offset],
+                    Condition::Appearing,
+                )
+                .position_pivot([0.5, 0.5])
+                .always_auto_resize(true)
+                .collapsible(false)
+                .build(|| self.choose(ui, game, *player, *amount, discard))
+                .flatten()
+                .or(command);
+        }
+        command
+    }
+
+    /// Cards the player gives up or takes, a discard only from what they hold
+    fn choose(
+        &mut self,
+        ui: &Ui,
+        game: &Game,
+        player: PlayerId,
+        amount: u32,
+        discard: bool,
+    ) -> Option<Command> {
+        let hand = &game.state().player(player).hand;
+        let chosen = self.chosen.entry(player).or_default();
+        let commodities = discard && game.rules().expansion() == Expansion::CitiesAndKnights;
+        for card in ResourceCard::RESOURCES
+            .iter()
+            .chain(ResourceCard::COMMODITIES.iter().filter(|_| commodities))
+        {
+            let count = chosen.count(*card);
+            if ui.small_button(format!("-##{:?}", card)) && count > 0 {
+                chosen.remove(*card, 1);
+            }
+            ui.same_line();
+            let room = !discard || count < hand.count(*card);
+            if ui.small_button(format!("+##{:?}", card)) && room && chosen.total() < amount {
+                chosen.add(*card, 1);
+            }
+            ui.same_line();
+            if discard {
+                ui.text(format!("{:?}: {} of {}", card, count, hand.count(*card)));
+            } else {
+                ui.text(format!("{:?}: {}", card, count));
+            }
+        }
+        ui.separator();
+        ui.text(format!("{} of {}", chosen.total(), amount));
+        if chosen.total() != amount {
+            return None;
+        }
+        ui.same_line();
+        let cards = chosen.clone();
+        if discard && ui.button("Discard") {
+            Some(Command::ActAs(player, Action::Discard(cards)))
+        } else if !discard && ui.button("Take") {
+            Some(Command::ActAs(player, Action::PickResources(cards)))
+        } else {
+            None
+        }
+    }
+
+    fn hand(&self, ui: &Ui, game: &Game, palette: &Palette, player: PlayerId) {
+        ui.text_colored(
+            palette_color(palette, player),
+            format!("Player {}", player + 1),
+        );
+        let hand = &game.state().player(player).hand;
+        let commodities = game.rules().expansion() == Expansion::CitiesAndKnights;
+        for card in ResourceCard::RESOURCES
+            .iter()
+            .chain(ResourceCard::COMMODITIES.iter().filter(|_| commodities))
+        {
+            ui.text(format!("{:?}: {}", card, hand.count(*card)));
+        }
+        let progress = game.rules().progress_cards(player);
+        if !progress.is_empty() {
+            ui.text(format!("Progress cards: {}", progress.len()));
+        }
+        ui.separator();
+        ui.text_disabled("Costs");
+        for (build, cost) in game.rules().config().costs.iter() {
+            ui.text(format!("{:?}: {}", build, describe(cost)));
+        }
+    }
+
+    fn actions(&mut self, ui: &Ui, game: &Game, placing: Option<Build>) -> Option<Command> {
+        let mut command = None;
+        ui.text(phase_name(game.state().phase()));
+        if game.state().phase() == &Phase::Robber && game.rules().has_pirate() {
+            ui.text_disabled("A land hex takes the robber, a sea hex the pirate");
+        }
+        if ui.button("Roll") {
+            command = Some(Command::Act(Action::Roll));
+        }
+        ui.same_line();
+        if ui.button("End turn") {
+            command = Some(Command::Act(Action::EndTurn));
+        }
+        ui.separator();
+        let costs = &game.rules().config().costs;
+        for build in PLACEABLE
+            .into_iter()
+            .filter(|build| costs.iter().any(|(b, _)| b == build))
+        {
+            let label = if placing == Some(build) {
+                format!("[{:?}]", build)
+            } else {
+                format!("{:?}", build)
+            };
+            if ui.button(label) {
+                command = Some(Command::Place(build));
+            }
+            ui.same_line();
+        }
+        if ui.button("Cancel") {
+            command = Some(Command::CancelPlace);
+        }
+        ui.separator();
+        let names: Vec<String> = ResourceCard::RESOURCES
+            .iter()
+            .map(|card| format!("{:?}", card))
+            .collect();
+        ui.set_next_item_width(80.);
+        ui.combo_simple_string("Give", &mut self.give, &names);
+        ui.same_line();
+        ui.set_next_item_width(80.);
+        ui.combo_simple_string("Get", &mut self.get, &names);
+        ui.same_line();
+        if ui.button("Trade") {
+            command = Some(Command::Act(Action::TradeBank {
+                give: ResourceCard::RESOURCES[self.give],
+                get: ResourceCard::RESOURCES[self.get],
+            }));
+        }
+        if let Some(message) = &self.message {
+            ui.separator();
+            ui.text_wrapped(message);
+        }
+        command
+    }
+}
+
+/// A window for every human owed a progress card, picking the deck it comes from
+fn draws(ui: &Ui, game: &Game, bots: &[PlayerId]) -> Option<Command> {
+    let [width, height] = ui.io().display_size;
+    let mut owed: Vec<PlayerId> = game.rules().owed_progress().to_vec();
+    owed.dedup();
+    let mut command = None;
+    for (i, player) in owed.into_iter().filter(|p| !bots.contains(p)).enumerate() {
+        let offset = 30. * i as f32;
+        command = ui
+            .window(format!("Player {} draws a progress card", player + 1))
+            .position(
+                [width / 2. + offset, height / 3. + offset],
+                Condition::Appearing,
+            )
+            .position_pivot([0.5, 0.5])
+            .always_auto_resize(true)
+            .collapsible(false)
+            .build(|| {
+                let mut command = None;
+                for deck in Improvement::ALL {
+                    if ui.button(format!("{:?}", deck)) {
+                        let draw = ExpansionAction::DrawProgress(deck);
+                        command = Some(Command::ActAs(player, Action::Expansion(draw)));
+                    }
+                    ui.same_line();
+                }
+                ui.new_line();
+                command
+            })
+            .flatten()
+            .or(command);
+    }
+    command
+}
+
+/// What a progress card is played with, besides the card itself
+struct Choices<'a> {
+    /// Spots clicked on the board, the latest last
+    targets: &'a [Pick],
+    /// Resource or commodity the card names
+    card: ResourceCard,
+    victim: PlayerId,
+    dice: (u8, u8),
+    /// Progress card of the victim the spy takes
+    spied: Option<ProgressCard>,
+}
+
+/// The progress card played with the choices, or what is missing to play it
+fn progress(card: ProgressCard, choices: &Choices) -> Result<Progress, &'static str> {
+    let hexes: Vec<HexCoord> = choices
+        .targets
+        .iter()
+        .filter_map(|pick| match pick {
+            Pick::Hex(hex) => Some(*hex),
+            _ => None,
+        })
+        .collect();
+    let intersections: Vec<Intersection> = choices
+        .targets
+        .iter()
+        .filter_map(|pick| match pick {
+            Pick::Intersection(intersection) => Some(*intersection),
+            _ => None,
+        })
+        .collect();
+    let edges: Vec<Edge> = choices
+        .targets
+        .iter()
+        .filter_map(|pick| match pick {
+            Pick::Edge(edge) => Some(*edge),
+            _ => None,
+        })
+        .collect();
+    let hex = hexes.last().copied().ok_or("pick a hex");
+    let intersection = intersections.last().copied().ok_or("pick an intersection");
+    let (named, victim) = (choices.card, choices.victim);
+    Ok(match card {
+        ProgressCard::Alchemist => Progress::Alchemist { dice: choices.dice },
+        ProgressCard::Crane => Progress::Crane,
+        ProgressCard::Engineer => Progress::Engineer(intersection?),
+        ProgressCard::Inventor => match hexes[..] {
+            [a, b] => Progress::Inventor(a, b),
+            _ => return Err("pick two hexes"),
+        },
+        ProgressCard::Irrigation => Progress::Irrigation,
+        ProgressCard::Medicine => Progress::Medicine(intersection?),
+        ProgressCard::Mining => Progress::Mining,
+        ProgressCard::RoadBuilding => match edges[..] {
+            [a, b] => Progress::RoadBuilding(a, b),
+            _ => return Err("pick two edges"),
+        },
+        ProgressCard::Smith if intersections.is_empty() => return Err("pick one or two knights"),
+        ProgressCard::Smith => Progress::Smith(intersections),
+        ProgressCard::CommercialHarbor => Progress::CommercialHarbor(named),
+        // Two of the named card
+        ProgressCard::MasterMerchant => Progress::MasterMerchant {
+            victim,
+            cards: Hand::of(&[(named, 2)]),
+        },
+        ProgressCard::Merchant => Progress::Merchant(hex?),
+        ProgressCard::MerchantFleet => Progress::MerchantFleet(named),
+        ProgressCard::ResourceMonopoly => Progress::ResourceMonopoly(named),
+        ProgressCard::TradeMonopoly => Progress::TradeMonopoly(named),
+        ProgressCard::Bishop => Progress::Bishop(hex?),
+        ProgressCard::Deserter => match intersections[..] {
+            [knight, to] => Progress::Deserter { knight, to },
+            _ => return Err("pick the knight, then where yours goes"),
+        },
+        // A second edge is where the player's own road is rebuilt
+        ProgressCard::Diplomat => match edges[..] {
+            [road] => Progress::Diplomat { road, to: None },
+            [road, to] => Progress::Diplomat { road, to: Some(to) },
+            _ => return Err("pick a road"),
+        },
+        ProgressCard::Intrigue => Progress::Intrigue(intersection?),
+        ProgressCard::Saboteur => Progress::Saboteur,
+        ProgressCard::Spy => Progress::Spy {
+            victim,
+            card: choices.spied.ok_or("the victim has no progress cards")?,
+        },
+        ProgressCard::Warlord => Progress::Warlord,
+        ProgressCard::Wedding => Progress::Wedding,
+        ProgressCard::Printer | ProgressCard::Constitution => {
+            return Err("victory points are played when drawn")
+        }
+    })
+}
+
+/// Kind of spot picked, e.g. "a hex"
+fn describe_pick(pick: &Pick) -> &'static str {
+    match pick {
+        Pick::Hex(_) => "a hex",
+        Pick::Intersection(_) => "an intersection",
+        Pick::Edge(_) => "an edge",
+    }
+}
+
+/// Everyone's public standing, and the last roll
+fn scores(ui: &Ui, game: &Game, palette: &Palette) {
+    let state = game.state();
+    match state.dice() {
+        Some((a, b)) => ui.text(format!("Dice: {} + {} = {}", a, b, a + b)),
+        None => ui.text("Dice: not rolled"),
+    }
+    ui.separator();
+    for player in state.players.iter() {
+        let id = player.id;
+        let mut line = format!(
+            "Player {}: {} VP, {} cards, road {}",
+            id + 1,
+            game.rules().victory_points(state, id),
+            player.hand.total(),
+            state.road_length(id)
+        );
+        if state.longest_road() == Some(id) {
+            line.push_str(", Longest Road");
+        }
+        if id == state.acting() {
+            line.insert_str(0, "> ");
+        }
+        ui.text_colored(palette_color(palette, id), line);
+    }
+}
+
+fn palette_color(palette: &Palette, player: PlayerId) -> [f32; 4] {
+    let [r, g, b] = palette.color(player);
+    [r, g, b, 1.]
+}
+
+/// Cards of a hand, e.g. "1 Wood, 1 Brick"
+fn describe(hand: &Hand) -> String {
+    hand.iter()
+        .map(|(card, amount)| format!("{} {:?}", amount, card))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn phase_name(phase: &Phase) -> String {
+    match phase {
+        Phase::Setup {
+            settlement: None, ..
+        } => "Place a settlement".into(),
+        Phase::Setup { .. } => "Place a road".into(),
+        Phase::Roll => "Roll the dice".into(),
+        Phase::Discard(_) => "Discarding".into(),
+        Phase::Robber => "Move the robber".into(),
+        Phase::Main => "Build and trade".into(),
+        Phase::PickResources { .. } => "Picking resources".into(),
+        Phase::SpecialBuilding { builder } => format!("Player {} may build", builder + 1),
+        Phase::Finished(winner) => format!("Player {} won!", winner + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_costs() {
+        let hand = Hand::of(&[(ResourceCard::Wheat, 2), (ResourceCard::Ore, 3)]);
+        assert_eq!(describe(&hand), "3 Ore, 2 Wheat");
+        assert_eq!(describe(&Hand::new()), "");
+    }
+
+    #[test]
+    fn progress_cards_are_played_on_the_picks() {
+        let (a, b) = (HexCoord::new(2, 2), HexCoord::new(2, 3));
+        let corner = Intersection::bottom(a);
+        let [road, to, _] = corner.edges();
+        let progress_on = |card, targets: &[Pick]| {
+            let choices = Choices {
+                targets,
+                card: ResourceCard::Ore,
+                victim: 1,
+                dice: (1, 2),
+                spied: None,
+            };
+            progress(card, &choices)
+        };
+        let picks = [Pick::Hex(a), Pick::Intersection(corner)];
+        assert_eq!(
+            progress_on(ProgressCard::Bishop, &picks),
+            Ok(Progress::Bishop(a))
+        );
+        assert_eq!(
+            progress_on(ProgressCard::Medicine, &picks),
+            Ok(Progress::Medicine(corner))
+        );
+        assert!(progress_on(ProgressCard::Inventor, &picks).is_err());
+        assert_eq!(
+            progress_on(ProgressCard::Inventor, &[Pick::Hex(a), Pick::Hex(b)]),
+            Ok(Progress::Inventor(a, b))
+        );
+        assert_eq!(
+            progress_on(ProgressCard::Diplomat, &[Pick::Edge(road)]),
+            Ok(Progress::Diplomat { road, to: None })
+        );
+        assert_eq!(
+            progress_on(ProgressCard::Diplomat, &[Pick::Edge(road), Pick::Edge(to)]),
+            Ok(Progress::Diplomat { road, to: Some(to) })
+        );
+        assert_eq!(
+            progress_on(ProgressCard::ResourceMonopoly, &[]),
+            Ok(Progress::ResourceMonopoly(ResourceCard::Ore))
+        );
+        assert!(progress_on(ProgressCard::Engineer, &[]).is_err());
+        assert!(progress_on(ProgressCard::Spy, &[]).is_err());
+        assert!(progress_on(ProgressCard::Printer, &[]).is_err());
+    }
+}
@@ -0,0 +1,317 @@
+//! Scenes around a game: the main menu, the new game setup, the pause menu and the results.
+//! They only build imgui windows, the frame is left to the scenes under them.
+
+use crate::settlers::board::maps;
+use crate::settlers::expansions::base::BaseGame;
+use crate::settlers::expansions::Expansion;
+use crate::settlers::game::{Scene, Transition};
+use crate::settlers::rules::options::{GameOptions, MapSource, Seat, MIN_PLAYERS};
+use crate::settlers::rules::player::PlayerId;
+use crate::settlers::rules::{Game, Phase, MAX_PLAYERS};
+use crate::settlers::ui::editor::MapEditor;
+use glium::backend::Facade;
+use glium::Frame;
+use imgui::{Condition, Ui};
+use winit::event::KeyEvent;
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+/// Width of the menu windows
+const MENU_WIDTH: f32 = 320.;
+
+/// Menu window in the middle of the screen
+fn centered<'ui>(ui: &'ui Ui, title: &'ui str) -> imgui::Window<'ui, 'ui, &'ui str> {
+    let [width, height] = ui.io().display_size;
+    ui.window(title)
+        .position([width / 2., height / 2.], Condition::Always)
+        .position_pivot([0.5, 0.5])
+        .size([MENU_WIDTH, 0.], Condition::Always)
+        .collapsible(false)
+        .resizable(false)
+}
+
+pub struct MainMenu {
+    debug: bool,
+    transition: Option<Transition>,
+}
+
+impl MainMenu {
+    pub fn new(debug: bool) -> Self {
+        MainMenu {
+            debug,
+            transition: None,
+        }
+    }
+}
+
+impl Scene for MainMenu {
+    fn ui(&mut self, ui: &Ui) {
+        centered(ui, "Fortune of Catan").build(|| {
+            if ui.button_with_size("New game", [-1., 0.]) {
+                self.transition = Some(Transition::Push(Box::new(NewGame::new(self.debug))));
+            }
+            if ui.button_with_size("Map editor", [-1., 0.]) {
+                self.transition = Some(Transition::Push(Box::new(MapEditor::new())));
+            }
+            if ui.button_with_size("Quit", [-1., 0.]) {
+                self.transition = Some(Transition::Quit);
+            }
+        });
+    }
+
+    fn transition(&mut self) -> Option<Transition> {
+        self.transition.take()
+    }
+
+    fn draw(&self, _facade: &dyn Facade, frame: Frame) -> Frame {
+        frame
+    }
+}
+
+/// Pick the map, rules, players and seed of a new game
+pub struct NewGame {
+    debug: bool,
+    options: GameOptions,
+    /// Index in `maps::BUNDLED`, past the end for a map file
+    map: usize,
+    path: String,
+    /// 0 for the rules of the map, then the index in `Expansion::ALL` plus one
+    expansion: usize,
+    seed: String,
+    error: Option<String>,
+    transition: Option<Transition>,
+}
+
+impl NewGame {
+    pub fn new(debug: bool) -> Self {
+        let options = GameOptions::default();
+        NewGame {
+            debug,
+            seed: options.seed.to_string(),
+            options,
+            map: 0,
+            path: String::new(),
+            expansion: 0,
+            error: None,
+            transition: None,
+        }
+    }
+
+    /// Options as picked in the menu
+    fn options(&self) -> Result<GameOptions, String> {
+        let map = match maps::BUNDLED.get(self.map) {
+            Some((name, _)) => MapSource::Bundled(name),
+            None if self.path.is_empty() => return Err("no map file given".into()),
+            None => MapSource::File(self.path.clone().into()),
+        };
+        let seed = self
+            .seed
+            .trim()
+            .parse()
+            .map_err(|_| format!("seed {} isn't a positive number", self.seed))?;
+        Ok(GameOptions {
+            map,
+            expansion: self
+                .expansion
+                .checked_sub(1)
+                .map(|index| Expansion::ALL[index]),
+            seed,
+            ..self.options.clone()
+        })
+    }
+
+    fn start(&mut self) {
+        let options = match self.options() {
+            Ok(options) => options,
+            Err(error) => {
+                self.error = Some(error);
+                return;
+            }
+        };
+        match options.start() {
+            Ok(game) => {
+                let scene = BaseGame::new(game, self.debug, options.bots());
+                self.transition = Some(Transition::Replace(Box::new(scene)));
+            }
+            Err(error) => self.error = Some(error.to_string()),
+        }
+    }
+
+    fn seats(&mut self, ui: &Ui) {
+        let kinds = ["Human", "Bot"];
+        for (player, seat) in self.options.seats.iter_mut().enumerate() {
+            let mut kind = (*seat == Seat::Bot) as usize;
+            if ui.combo_simple_string(format!("Player {}", player + 1), &mut kind, &kinds) {
+                *seat = [Seat::Human, Seat::Bot][kind];
+            }
+        }
+        let players = self.options.seats.len();
+        if players < MAX_PLAYERS && ui.button("Add player") {
+            self.options.seats.push(Seat::Bot);
+        }
+        if players > MIN_PLAYERS {
+            ui.same_line();
+            if ui.button("Remove player") {
+                self.options.seats.pop();
+            }
+        }
+    }
+}
+
+impl Scene for NewGame {
+    fn ui(&mut self, ui: &Ui) {
+        centered(ui, "New game").build(|| {
+            let mut maps: Vec<&str> = maps::BUNDLED.iter().map(|(name, _)| *name).collect();
+            maps.push("From a file");
+            ui.combo_simple_string("Map", &mut self.map, &maps);
+            // Enter in a field starts the game as the button does
+            let mut enter = false;
+            if self.map >= maps::BUNDLED.len() {
+                enter |= ui
+                    .input_text("Path", &mut self.path)
+                    .hint("map.focm, as saved by the editor")
+                    .enter_returns_true(true)
+                    .build();
+            }
+            let mut expansions = vec!["Those of the map"];
+            expansions.extend(Expansion::ALL.iter().map(|expansion| expansion.name()));
+            ui.combo_simple_string("Rules", &mut self.expansion, &expansions);
+            enter |= ui
+                .input_text("Seed", &mut self.seed)
+                .chars_decimal(true)
+                .enter_returns_true(true)
+                .build();
+            ui.same_line();
+            if ui.button("Random") {
+                self.seed = rand::random::<u64>().to_string();
+            }
+            ui.separator();
+            self.seats(ui);
+            ui.separator();
+            if ui.button("Start") || enter {
+                self.start();
+            }
+            ui.same_line();
+            if ui.button("Back") {
+                self.transition = Some(Transition::Pop);
+            }
+            if let Some(error) = &self.error {
+                ui.text_wrapped(error);
+            }
+        });
+    }
+
+    fn transition(&mut self) -> Option<Transition> {
+        self.transition.take()
+    }
+
+    fn draw(&self, _facade: &dyn Facade, frame: Frame) -> Frame {
+        frame
+    }
+}
+
+/// Shown over the game, which stops getting input
+pub struct Pause {
+    debug: bool,
+    transition: Option<Transition>,
+}
+
+impl Pause {
+    pub fn new(debug: bool) -> Self {
+        Pause {
+            debug,
+            transition: None,
+        }
+    }
+}
+
+impl Scene for Pause {
+    fn keyboard_input(&mut self, event: KeyEvent) {
+        if event.state.is_pressed() && event.physical_key == PhysicalKey::Code(KeyCode::Escape) {
+            self.transition = Some(Transition::Pop);
+        }
+    }
+
+    fn ui(&mut self, ui: &Ui) {
+        centered(ui, "Paused").build(|| {
+            if ui.button_with_size("Resume", [-1., 0.]) {
+                self.transition = Some(Transition::Pop);
+            }
+            if ui.button_with_size("Main menu", [-1., 0.]) {
+                self.transition = Some(Transition::Reset(Box::new(MainMenu::new(self.debug))));
+            }
+            if ui.button_with_size("Quit", [-1., 0.]) {
+                self.transition = Some(Transition::Quit);
+            }
+        });
+    }
+
+    fn transition(&mut self) -> Option<Transition> {
+        self.transition.take()
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn draw(&self, _facade: &dyn Facade, frame: Frame) -> Frame {
+        frame
+    }
+}
+
+/// Final scores, shown over the finished game
+pub struct Results {
+    debug: bool,
+    winner: Option<PlayerId>,
+    /// Victory points of every player, in turn order
+    scores: Vec<u32>,
+    transition: Option<Transition>,
+}
+
+impl Results {
+    pub fn new(game: &Game, debug: bool) -> Self {
+        let state = game.state();
+        Results {
+            debug,
+            winner: match state.phase() {
+                Phase::Finished(winner) => Some(*winner),
+                _ => None,
+            },
+            scores: state
+                .players
+                .iter()
+                .map(|player| game.rules().victory_points(state, player.id))
+                .collect(),
+            transition: None,
+        }
+    }
+}
+
+impl Scene for Results {
+    fn ui(&mut self, ui: &Ui) {
+        centered(ui, "Game over").build(|| {
+            if let Some(winner) = self.winner {
+                ui.text(format!("Player {} won!", winner + 1));
+                ui.separator();
+            }
+            for (player, points) in self.scores.iter().enumerate() {
+                ui.text(format!("Player {}: {} VP", player + 1, points));
+            }
+            ui.separator();
+            if ui.button_with_size("Main menu", [-1., 0.]) {
+                self.transition = Some(Transition::Reset(Box::new(MainMenu::new(self.debug))));
+            }
+        });
+    }
+
+    fn transition(&mut self) -> Option<Transition> {
+        self.transition.take()
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn draw(&self, _facade: &dyn Facade, frame: Frame) -> Frame {
+        frame
+    }
+}
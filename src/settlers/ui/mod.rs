@@ -0,0 +1,250 @@
+//! Dear ImGui drawn with glium. `Gui` feeds imgui the window's mouse and keyboard input and
+//! draws the windows that scenes build in `Scene::ui` over the frame.
+
+pub mod debug;
+pub mod editor;
+pub mod hud;
+pub mod menu;
+
+use crate::settlers::shader::ProgramManager;
+use glium::backend::Facade;
+use glium::index::PrimitiveType;
+use glium::texture::RawImage2d;
+use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter};
+use glium::{Blend, DrawParameters, Frame, IndexBuffer, Rect, Surface, Texture2d, VertexBuffer};
+use imgui::{DrawCmd, DrawCmdParams, FontSource, Key, TextureId, Ui};
+use std::time::Instant;
+use winit::dpi::PhysicalSize;
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+/// Pixels of a touchpad scroll that make one line of a mouse wheel
+const PIXELS_PER_LINE: f32 = 20.;
+
+#[derive(Clone, Copy)]
+struct UiVertex {
+    pos: [f32; 2],
+    uv: [f32; 2],
+    col: [f32; 4],
+}
+implement_vertex!(UiVertex, pos, uv, col);
+
+pub struct Gui {
+    context: imgui::Context,
+    program_manager: ProgramManager<'static>,
+    font_texture: Texture2d,
+    last_frame: Instant,
+}
+
+impl Gui {
+    pub fn new<F>(facade: &F, window_size: PhysicalSize<u32>) -> Self
+    where
+        F: Sized + Facade,
+    {
+        let mut context = imgui::Context::create();
+        // The scenes place their windows, there is no layout to remember between runs
+        context.set_ini_filename(None);
+        context
+            .fonts()
+            .add_font(&[FontSource::DefaultFontData { config: None }]);
+        let font_texture = {
+            let atlas = context.fonts().build_rgba32_texture();
+            let image = RawImage2d::from_raw_rgba(atlas.data.to_vec(), (atlas.width, atlas.height));
+            Texture2d::new(facade, image).expect("Font atlas texture")
+        };
+        // Only the font texture is ever drawn
+        context.fonts().tex_id = TextureId::new(0);
+        context.io_mut().display_size = [window_size.width as f32, window_size.height as f32];
+
+        let mut program_manager = ProgramManager::new();
+        program_manager
+            .add_program(facade, "ui", "glsl/ui/ui.v.glsl", "glsl/ui/ui.f.glsl", None)
+            .expect("UI shaders properly compiling");
+        Gui {
+            context,
+            program_manager,
+            font_texture,
+            last_frame: Instant::now(),
+        }
+    }
+
+    /// Pass window input on to imgui
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        let io = self.context.io_mut();
+        match event {
+            WindowEvent::Resized(size) => {
+                io.display_size = [size.width as f32, size.height as f32];
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                io.mouse_pos = [position.x as f32, position.y as f32];
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let index = match button {
+                    MouseButton::Left => 0,
+                    MouseButton::Right => 1,
+                    MouseButton::Middle => 2,
+                    _ => return,
+                };
+                io.mouse_down[index] = *state == ElementState::Pressed;
+            }
+            WindowEvent::MouseWheel { delta, .. } => match delta {
+                MouseScrollDelta::LineDelta(_, lines) => io.mouse_wheel += lines,
+                MouseScrollDelta::PixelDelta(pixels) => {
+                    io.mouse_wheel += pixels.y as f32 / PIXELS_PER_LINE
+                }
+            },
+            WindowEvent::KeyboardInput { event, .. } => {
+                if let Some(key) = match event.physical_key {
+                    PhysicalKey::Code(code) => imgui_key(code),
+                    PhysicalKey::Unidentified(_) => None,
+                } {
+                    io.add_key_event(key, event.state.is_pressed());
+                }
+                // Backspace, Enter and the like come as keys, not text
+                if let (ElementState::Pressed, Some(text)) = (event.state, &event.text) {
+                    for character in text.chars().filter(|c| !c.is_control()) {
+                        io.add_input_character(character);
+                    }
+                }
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                let state = modifiers.state();
+                io.add_key_event(Key::ModCtrl, state.control_key());
+                io.add_key_event(Key::ModShift, state.shift_key());
+                io.add_key_event(Key::ModAlt, state.alt_key());
+                io.add_key_event(Key::ModSuper, state.super_key());
+            }
+            _ => (),
+        }
+    }
+
+    /// Whether the cursor is over a window, in which case the scene shouldn't use the mouse
+    pub fn wants_mouse(&self) -> bool {
+        self.context.io().want_capture_mouse
+    }
+
+    /// Whether a text field has focus, in which case the scene shouldn't use the keyboard
+    pub fn wants_keyboard(&self) -> bool {
+        self.context.io().want_capture_keyboard
+    }
+
+    /// Start a frame, building its windows with `build`
+    pub fn frame(&mut self, build: impl FnOnce(&Ui)) {
+        let now = Instant::now();
+        // imgui asserts time went forward
+        self.context.io_mut().delta_time = (now - self.last_frame).as_secs_f32().max(f32::EPSILON);
+        self.last_frame = now;
+        build(self.context.new_frame());
+    }
+
+    /// Draw the windows of the last frame over the target
+    pub fn draw<F>(&mut self, facade: &F, target: &mut Frame)
+    where
+        F: ?Sized + Facade,
+    {
+        let draw_data = self.context.render();
+        let [width, height] = draw_data.display_size;
+        if width <= 0. || height <= 0. {
+            return;
+        }
+        let program = self
+            .program_manager
+            .program("ui")
+            .expect("UI program exists");
+        // Pixels with the origin top left to normalized device coordinates
+        let matrix = [
+            [2. / width, 0., 0., 0.],
+            [0., -2. / height, 0., 0.],
+            [0., 0., -1., 0.],
+            [-1., 1., 0., 1.],
+        ];
+        let (_, target_height) = target.get_dimensions();
+        for draw_list in draw_data.draw_lists() {
+            let vertices: Vec<UiVertex> = draw_list
+                .vtx_buffer()
+                .iter()
+                .map(|vertex| UiVertex {
+                    pos: vertex.pos,
+                    uv: vertex.uv,
+                    col: vertex.col.map(|c| c as f32 / 255.),
+                })
+                .collect();
+            let vertex_buffer = VertexBuffer::new(facade, &vertices).unwrap();
+            for command in draw_list.commands() {
+                let DrawCmd::Elements {
+                    count,
+                    cmd_params:
+                        DrawCmdParams {
+                            clip_rect,
+                            vtx_offset,
+                            idx_offset,
+                            ..
+                        },
+                } = command
+                else {
+                    continue;
+                };
+                let indices = &draw_list.idx_buffer()[idx_offset..idx_offset + count];
+                let index_buffer =
+                    IndexBuffer::new(facade, PrimitiveType::TrianglesList, indices).unwrap();
+                // Clip rectangles have their origin top left, glium's bottom left
+                let [left, top, right, bottom] = clip_rect.map(|x| x.max(0.));
+                let scissor = Rect {
+                    left: left as u32,
+                    bottom: (target_height as f32 - bottom).max(0.) as u32,
+                    width: (right - left).max(0.) as u32,
+                    height: (bottom - top).max(0.) as u32,
+                };
+                target
+                    .draw(
+                        vertex_buffer.slice(vtx_offset..).unwrap(),
+                        &index_buffer,
+                        program,
+                        &uniform! {
+                            u_matrix: matrix,
+                            u_font: self.font_texture
+                                .sampled()
+                                .magnify_filter(MagnifySamplerFilter::Linear)
+                                .minify_filter(MinifySamplerFilter::Linear),
+                        },
+                        &DrawParameters {
+                            blend: Blend::alpha_blending(),
+                            scissor: Some(scissor),
+                            ..Default::default()
+                        },
+                    )
+                    .unwrap();
+            }
+        }
+    }
+}
+
+/// Keys imgui needs to edit text: moving the cursor, deleting, confirming and the clipboard
+fn imgui_key(code: KeyCode) -> Option<Key> {
+    let key = match code {
+        KeyCode::Tab => Key::Tab,
+        KeyCode::ArrowLeft => Key::LeftArrow,
+        KeyCode::ArrowRight => Key::RightArrow,
+        KeyCode::ArrowUp => Key::UpArrow,
+        KeyCode::ArrowDown => Key::DownArrow,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Enter => Key::Enter,
+        KeyCode::NumpadEnter => Key::KeypadEnter,
+        KeyCode::Escape => Key::Escape,
+        KeyCode::ControlLeft => Key::LeftCtrl,
+        KeyCode::ControlRight => Key::RightCtrl,
+        KeyCode::ShiftLeft => Key::LeftShift,
+        KeyCode::ShiftRight => Key::RightShift,
+        KeyCode::KeyA => Key::A,
+        KeyCode::KeyC => Key::C,
+        KeyCode::KeyV => Key::V,
+        KeyCode::KeyX => Key::X,
+        KeyCode::KeyY => Key::Y,
+        KeyCode::KeyZ => Key::Z,
+        _ => return None,
+    };
+    Some(key)
+}